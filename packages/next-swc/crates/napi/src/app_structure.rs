@@ -6,9 +6,12 @@ use napi::{
     threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
     JsFunction,
 };
-use next_core::app_structure::{
-    find_app_dir, get_entrypoints as get_entrypoints_impl, Components, Entrypoint, Entrypoints,
-    LoaderTree, MetadataWithAltItem,
+use next_core::{
+    app_structure::{
+        find_app_dir, get_entrypoints as get_entrypoints_impl, AppStructureOptions, Components,
+        Entrypoint, Entrypoints, LoaderTree, MetadataWithAltItem,
+    },
+    next_config::NextConfig,
 };
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{unit, ReadRef, Vc};
@@ -90,6 +93,8 @@ struct ComponentsForJs {
     template: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "not-found")]
     not_found: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "global-error")]
+    global_error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     default: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -135,6 +140,7 @@ async fn prepare_components_for_js(
         loading,
         template,
         not_found,
+        global_error,
         default,
         route,
         metadata,
@@ -156,6 +162,7 @@ async fn prepare_components_for_js(
     add(&mut result.loading, project_path, loading).await?;
     add(&mut result.template, project_path, template).await?;
     add(&mut result.not_found, project_path, not_found).await?;
+    add(&mut result.global_error, project_path, global_error).await?;
     add(&mut result.default, project_path, default).await?;
     add(&mut result.route, project_path, route).await?;
     async fn add_meta<'a>(
@@ -267,6 +274,7 @@ async fn get_value(
     root_dir: String,
     project_dir: String,
     page_extensions: Vec<String>,
+    next_config: String,
     watching: bool,
 ) -> Result<Vc<OptionEntrypointsForJs>> {
     let page_extensions = Vc::cell(page_extensions);
@@ -281,7 +289,14 @@ async fn get_value(
     let app_dir = find_app_dir(project_path);
 
     let result = if let Some(app_dir) = *app_dir.await? {
-        let entrypoints = get_entrypoints_impl(app_dir, page_extensions);
+        let entrypoints = get_entrypoints_impl(
+            app_dir,
+            page_extensions,
+            AppStructureOptions::from_next_config(
+                NextConfig::from_string(Vc::cell(next_config)),
+                project_path,
+            ),
+        );
         let entrypoints_for_js = prepare_entrypoints_for_js(project_path, entrypoints);
 
         Some(entrypoints_for_js)
@@ -298,6 +313,7 @@ pub fn stream_entrypoints(
     root_dir: String,
     project_dir: String,
     page_extensions: Vec<String>,
+    next_config: String,
     func: JsFunction,
 ) -> napi::Result<()> {
     register();
@@ -310,16 +326,19 @@ pub fn stream_entrypoints(
     let root_dir = Arc::new(root_dir);
     let project_dir = Arc::new(project_dir);
     let page_extensions = Arc::new(page_extensions);
+    let next_config = Arc::new(next_config);
     turbo_tasks.spawn_root_task(move || {
         let func: ThreadsafeFunction<Option<ReadRef<EntrypointsForJs>>> = func.clone();
         let project_dir = project_dir.clone();
         let root_dir = root_dir.clone();
         let page_extensions: Arc<Vec<String>> = page_extensions.clone();
+        let next_config = next_config.clone();
         Box::pin(async move {
             if let Some(entrypoints) = &*get_value(
                 (*root_dir).clone(),
                 (*project_dir).clone(),
                 page_extensions.iter().map(|s| s.to_string()).collect(),
+                (*next_config).clone(),
                 true,
             )
             .await?
@@ -344,6 +363,7 @@ pub async fn get_entrypoints(
     root_dir: String,
     project_dir: String,
     page_extensions: Vec<String>,
+    next_config: String,
 ) -> napi::Result<serde_json::Value> {
     register();
     let result = turbo_tasks
@@ -352,6 +372,7 @@ pub async fn get_entrypoints(
                 root_dir,
                 project_dir,
                 page_extensions.iter().map(|s| s.to_string()).collect(),
+                next_config,
                 false,
             )
             .await?
@@ -1,13 +1,18 @@
 use std::ops::Deref;
 
+use anyhow::Result;
 use napi::{bindgen_prelude::External, JsFunction};
 use next_api::route::{Endpoint, WrittenEndpoint};
-use turbo_tasks::Vc;
-use turbopack_binding::turbopack::core::error::PrettyPrintError;
+use next_core::{next_manifests::RouteHas, split_shared_assets, util::MiddlewareMatcherKind};
+use turbo_tasks::{TryJoinIterExt, ValueToString, Vc};
+use turbopack_binding::turbopack::core::{
+    error::PrettyPrintError,
+    output::{OutputAsset, OutputAssets},
+};
 
 use super::utils::{
-    get_diagnostics, get_issues, subscribe, NapiDiagnostic, NapiIssue, RootTask, TurbopackResult,
-    VcArc,
+    get_diagnostics, get_issues, napi_diagnostics, subscribe, NapiDiagnostic, NapiIssue, RootTask,
+    TurbopackResult, VcArc,
 };
 
 #[napi(object)]
@@ -52,6 +57,58 @@ impl From<&WrittenEndpoint> for NapiWrittenEndpoint {
     }
 }
 
+#[napi(object)]
+pub struct NapiRouteHas {
+    pub r#type: String,
+    pub key: Option<String>,
+    pub value: Option<String>,
+}
+
+impl From<&RouteHas> for NapiRouteHas {
+    fn from(route_has: &RouteHas) -> Self {
+        let (r#type, key, value) = match route_has {
+            RouteHas::Header { key, value } => ("header", Some(key), value.as_ref()),
+            RouteHas::Cookie { key, value } => ("cookie", Some(key), value.as_ref()),
+            RouteHas::Query { key, value } => ("query", Some(key), value.as_ref()),
+            RouteHas::Host { value } => ("host", None, Some(value)),
+        };
+        Self {
+            r#type: r#type.to_string(),
+            key: key.cloned(),
+            value: value.cloned(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct NapiEndpointMatcher {
+    pub source: String,
+    pub has: Option<Vec<NapiRouteHas>>,
+    pub missing: Option<Vec<NapiRouteHas>>,
+}
+
+impl From<&MiddlewareMatcherKind> for NapiEndpointMatcher {
+    fn from(matcher: &MiddlewareMatcherKind) -> Self {
+        let conditions = |conditions: &Option<Vec<RouteHas>>| {
+            conditions
+                .as_ref()
+                .map(|conditions| conditions.iter().map(NapiRouteHas::from).collect())
+        };
+        match matcher {
+            MiddlewareMatcherKind::Str(source) => Self {
+                source: source.clone(),
+                has: None,
+                missing: None,
+            },
+            MiddlewareMatcherKind::Matcher(matcher) => Self {
+                source: matcher.source.clone(),
+                has: conditions(&matcher.has),
+                missing: conditions(&matcher.missing),
+            },
+        }
+    }
+}
+
 // NOTE(alexkirsz) We go through an extra layer of indirection here because of
 // two factors:
 // 1. rustc currently has a bug where using a dyn trait as a type argument to
@@ -71,6 +128,7 @@ impl Deref for ExternalEndpoint {
 #[napi]
 pub async fn endpoint_write_to_disk(
     #[napi(ts_arg_type = "{ __napiType: \"Endpoint\" }")] endpoint: External<ExternalEndpoint>,
+    diagnostics_category: Option<String>,
 ) -> napi::Result<TurbopackResult<NapiWrittenEndpoint>> {
     let turbo_tasks = endpoint.turbo_tasks().clone();
     let endpoint = ***endpoint;
@@ -88,14 +146,141 @@ pub async fn endpoint_write_to_disk(
     Ok(TurbopackResult {
         result: NapiWrittenEndpoint::from(&*written),
         issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: napi_diagnostics(&diags, diagnostics_category.as_deref()),
+    })
+}
+
+/// Returns the server files required to run the endpoint, relative to the
+/// node root, so that it can be packaged on its own.
+#[napi]
+pub async fn endpoint_required_server_files(
+    #[napi(ts_arg_type = "{ __napiType: \"Endpoint\" }")] endpoint: External<ExternalEndpoint>,
+) -> napi::Result<TurbopackResult<Vec<String>>> {
+    let turbo_tasks = endpoint.turbo_tasks().clone();
+    let endpoint = ***endpoint;
+    let (files, issues, diags) = turbo_tasks
+        .run_once(async move {
+            let required_server_files = endpoint.required_server_files();
+            let issues = get_issues(required_server_files).await?;
+            let diags = get_diagnostics(required_server_files).await?;
+            let files = required_server_files.strongly_consistent().await?;
+            Ok((files, issues, diags))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: files.clone_value(),
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
         diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
     })
 }
 
+/// Returns the middleware matchers configured in the endpoint's source,
+/// without writing anything to disk. The list is empty when the endpoint
+/// doesn't configure a matcher.
+#[napi]
+pub async fn endpoint_matchers(
+    #[napi(ts_arg_type = "{ __napiType: \"Endpoint\" }")] endpoint: External<ExternalEndpoint>,
+) -> napi::Result<TurbopackResult<Vec<NapiEndpointMatcher>>> {
+    let turbo_tasks = endpoint.turbo_tasks().clone();
+    let endpoint = ***endpoint;
+    let (config, issues, diags) = turbo_tasks
+        .run_once(async move {
+            let source_config = endpoint.source_config();
+            let issues = get_issues(source_config).await?;
+            let diags = get_diagnostics(source_config).await?;
+            let config = source_config.strongly_consistent().await?;
+            Ok((config, issues, diags))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: config
+            .matcher
+            .iter()
+            .flatten()
+            .map(NapiEndpointMatcher::from)
+            .collect(),
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
+    })
+}
+
+#[napi(object)]
+pub struct NapiSharedAssets {
+    /// Assets referenced by more than one of the endpoints.
+    pub shared: Vec<String>,
+    /// The assets only referenced by each endpoint, in the order of the
+    /// endpoints.
+    pub unique: Vec<Vec<String>>,
+}
+
+/// Returns the assets which are shared between multiple endpoints and the
+/// assets unique to each endpoint, without writing anything to disk.
+#[napi]
+pub async fn endpoints_shared_assets(
+    #[napi(ts_arg_type = "{ __napiType: \"Endpoint\" }[]")] endpoints: Vec<
+        External<ExternalEndpoint>,
+    >,
+) -> napi::Result<TurbopackResult<NapiSharedAssets>> {
+    let Some(first) = endpoints.first() else {
+        return Ok(TurbopackResult {
+            result: NapiSharedAssets {
+                shared: vec![],
+                unique: vec![],
+            },
+            issues: vec![],
+            diagnostics: vec![],
+        });
+    };
+    let turbo_tasks = first.turbo_tasks().clone();
+    let endpoints = endpoints
+        .iter()
+        .map(|endpoint| ***endpoint)
+        .collect::<Vec<_>>();
+    let ((shared, unique), issues, diags) = turbo_tasks
+        .run_once(async move {
+            let shared_assets = split_shared_assets(Vc::cell(
+                endpoints
+                    .into_iter()
+                    .map(|endpoint| endpoint.output_assets())
+                    .collect(),
+            ));
+            let issues = get_issues(shared_assets).await?;
+            let diags = get_diagnostics(shared_assets).await?;
+            let shared_assets = shared_assets.strongly_consistent().await?;
+            let shared = asset_paths(shared_assets.shared).await?;
+            let unique = shared_assets
+                .unique
+                .iter()
+                .map(|&assets| asset_paths(assets))
+                .try_join()
+                .await?;
+            Ok(((shared, unique), issues, diags))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: NapiSharedAssets { shared, unique },
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
+    })
+}
+
+pub(super) async fn asset_paths(assets: Vc<OutputAssets>) -> Result<Vec<String>> {
+    assets
+        .await?
+        .iter()
+        .map(|asset| async move { Ok(asset.ident().path().to_string().await?.clone_value()) })
+        .try_join()
+        .await
+}
+
 #[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
 pub fn endpoint_server_changed_subscribe(
     #[napi(ts_arg_type = "{ __napiType: \"Endpoint\" }")] endpoint: External<ExternalEndpoint>,
     func: JsFunction,
+    diagnostics_category: Option<String>,
 ) -> napi::Result<External<RootTask>> {
     let turbo_tasks = endpoint.turbo_tasks().clone();
     let endpoint = ***endpoint;
@@ -109,12 +294,12 @@ pub fn endpoint_server_changed_subscribe(
             changed.strongly_consistent().await?;
             Ok((issues, diags))
         },
-        |ctx| {
+        move |ctx| {
             let (issues, diags) = ctx.value;
             Ok(vec![TurbopackResult {
                 result: (),
                 issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
-                diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
+                diagnostics: napi_diagnostics(&diags, diagnostics_category.as_deref()),
             }])
         },
     )
@@ -124,6 +309,7 @@ pub fn endpoint_server_changed_subscribe(
 pub fn endpoint_client_changed_subscribe(
     #[napi(ts_arg_type = "{ __napiType: \"Endpoint\" }")] endpoint: External<ExternalEndpoint>,
     func: JsFunction,
+    diagnostics_category: Option<String>,
 ) -> napi::Result<External<RootTask>> {
     let turbo_tasks = endpoint.turbo_tasks().clone();
     let endpoint = ***endpoint;
@@ -137,12 +323,12 @@ pub fn endpoint_client_changed_subscribe(
             changed.strongly_consistent().await?;
             Ok((issues, diags))
         },
-        |ctx| {
+        move |ctx| {
             let (issues, diags) = ctx.value;
             Ok(vec![TurbopackResult {
                 result: (),
                 issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
-                diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
+                diagnostics: napi_diagnostics(&diags, diagnostics_category.as_deref()),
             }])
         },
     )
@@ -10,8 +10,11 @@ use next_api::{
     project::{Middleware, ProjectContainer, ProjectOptions},
     route::{Endpoint, Route},
 };
-use next_core::tracing_presets::{
-    TRACING_NEXT_TARGETS, TRACING_NEXT_TURBOPACK_TARGETS, TRACING_NEXT_TURBO_TASKS_TARGETS,
+use next_core::{
+    tracing_presets::{
+        TRACING_NEXT_TARGETS, TRACING_NEXT_TURBOPACK_TARGETS, TRACING_NEXT_TURBO_TASKS_TARGETS,
+    },
+    EmitProgressCallback, EmittedFile,
 };
 use tracing_subscriber::{
     prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry,
@@ -35,10 +38,10 @@ use turbopack_binding::{
 };
 
 use super::{
-    endpoint::ExternalEndpoint,
+    endpoint::{asset_paths, ExternalEndpoint},
     utils::{
-        get_diagnostics, get_issues, serde_enum_to_string, subscribe, NapiDiagnostic, NapiIssue,
-        RootTask, TurbopackResult, VcArc,
+        get_diagnostics, get_issues, napi_diagnostics, serde_enum_to_string, subscribe,
+        NapiDiagnostic, NapiIssue, RootTask, TurbopackResult, VcArc,
     },
 };
 use crate::register;
@@ -193,6 +196,25 @@ pub async fn project_update(
     Ok(())
 }
 
+/// Drops everything computed from the project's files, so that subsequent
+/// calls recompute from scratch without restarting the process.
+///
+/// This is as expensive as a cold start, as every entrypoint and chunk is
+/// rebuilt on its next use. Only use it when files changed without the file
+/// watcher noticing, e.g. after upgrading the `next` package at runtime.
+#[napi]
+pub async fn project_invalidate_all(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<()> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    turbo_tasks
+        .run_once(async move { container.invalidate_all().await })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(())
+}
+
 #[napi(object)]
 #[derive(Default)]
 struct NapiRoute {
@@ -277,8 +299,13 @@ impl NapiMiddleware {
     ) -> Result<Self> {
         Ok(NapiMiddleware {
             endpoint: External::new(VcArc::new(turbo_tasks.clone(), value.endpoint)),
-            runtime: serde_enum_to_string(&value.config.runtime)?,
-            matcher: value.config.matcher.clone(),
+            runtime: serde_enum_to_string(&value.config.runtime.unwrap_or_default())?,
+            matcher: value.config.matcher.as_ref().map(|matchers| {
+                matchers
+                    .iter()
+                    .map(|matcher| matcher.source().to_string())
+                    .collect()
+            }),
         })
     }
 }
@@ -295,6 +322,7 @@ struct NapiEntrypoints {
 pub fn project_entrypoints_subscribe(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
     func: JsFunction,
+    diagnostics_category: Option<String>,
 ) -> napi::Result<External<RootTask>> {
     let turbo_tasks = project.turbo_tasks.clone();
     let container = project.container;
@@ -344,12 +372,201 @@ pub fn project_entrypoints_subscribe(
                     .iter()
                     .map(|issue| NapiIssue::from(&**issue))
                     .collect(),
+                diagnostics: napi_diagnostics(&diags, diagnostics_category.as_deref()),
+            }])
+        },
+    )
+}
+
+/// Subscribes to the diagnostics (e.g. feature telemetry) of the project,
+/// without any issues. This allows collecting analytics independently from
+/// error reporting.
+///
+/// The diagnostics reported while building endpoints are included, so all
+/// endpoints are built. The builds are shared with `endpoint_write_to_disk`.
+#[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
+pub fn project_diagnostics_subscribe(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    func: JsFunction,
+    diagnostics_category: Option<String>,
+) -> napi::Result<External<RootTask>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    subscribe(
+        turbo_tasks,
+        func,
+        move || async move {
+            let entrypoints = container.entrypoints();
+            let mut diags = get_diagnostics(entrypoints).await?;
+            for endpoint in entrypoints.strongly_consistent().await?.endpoints() {
+                diags.extend(get_diagnostics(endpoint.write_to_disk()).await?);
+            }
+            Ok(diags)
+        },
+        move |ctx| {
+            let diags = ctx.value;
+            Ok(vec![napi_diagnostics(
+                &diags,
+                diagnostics_category.as_deref(),
+            )])
+        },
+    )
+}
+
+/// Collects the issues of the project structure and config without writing any
+/// endpoint.
+#[napi]
+pub async fn project_collect_issues(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<TurbopackResult<()>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let (issues, diags) = turbo_tasks
+        .run_once(async move {
+            let validate = container.validate();
+            let issues = get_issues(validate).await?;
+            let diags = get_diagnostics(validate).await?;
+            validate.strongly_consistent().await?;
+            Ok((issues, diags))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: (),
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
+    })
+}
+
+/// Returns the paths of all assets of the page or route at `pathname`, without
+/// building any other route or writing anything to disk.
+#[napi]
+pub async fn project_route_assets(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    pathname: String,
+) -> napi::Result<TurbopackResult<Vec<String>>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let (paths, issues, diags) = turbo_tasks
+        .run_once(async move {
+            let assets = container.route_output_assets(pathname);
+            let issues = get_issues(assets).await?;
+            let diags = get_diagnostics(assets).await?;
+            assets.strongly_consistent().await?;
+            let paths = asset_paths(assets).await?;
+            Ok((paths, issues, diags))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: paths,
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
+    })
+}
+
+#[napi(object)]
+pub struct NapiEmittedFile {
+    /// The path of the file, relative to the project root.
+    pub path: String,
+    /// The size of the written content in bytes.
+    pub size: f64,
+}
+
+/// Writes the assets of the page or route at `pathname` to disk. `on_emit` is
+/// called with each file as soon as it has been written, and `func` with the
+/// issues once all of them have been.
+#[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
+pub fn project_emit_route_assets_subscribe(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    pathname: String,
+    on_emit: JsFunction,
+    func: JsFunction,
+) -> napi::Result<External<RootTask>> {
+    let on_emit: ThreadsafeFunction<EmittedFile> =
+        on_emit.create_threadsafe_function(0, |ctx| {
+            let EmittedFile { path, size } = ctx.value;
+            Ok(vec![NapiEmittedFile {
+                path,
+                size: size as f64,
+            }])
+        })?;
+    let on_emit: EmitProgressCallback = Arc::new(move |file| {
+        on_emit.call(Ok(file), ThreadsafeFunctionCallMode::NonBlocking);
+    });
+    let on_emit = TransientInstance::new(on_emit);
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    subscribe(
+        turbo_tasks,
+        func,
+        move || {
+            let pathname = pathname.clone();
+            let on_emit = on_emit.clone();
+            async move {
+                let completion = container.emit_route_output_assets(pathname, on_emit);
+                let issues = get_issues(completion).await?;
+                let diags = get_diagnostics(completion).await?;
+                completion.strongly_consistent().await?;
+                Ok((issues, diags))
+            }
+        },
+        |ctx| {
+            let (issues, diags) = ctx.value;
+            Ok(vec![TurbopackResult {
+                result: (),
+                issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
                 diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
             }])
         },
     )
 }
 
+#[napi(object)]
+pub struct NapiAppDir {
+    /// The absolute path of the app directory, with symlinks resolved.
+    pub path: String,
+    /// Whether the app directory is `src/app` instead of `app`.
+    pub is_src_dir: bool,
+}
+
+/// Returns the app directory of the project, or null when it has none.
+#[napi]
+pub async fn project_app_dir(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<Option<NapiAppDir>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let app_dir = turbo_tasks
+        .run_once(async move {
+            Ok(container
+                .app_dir_info()
+                .await?
+                .as_ref()
+                .map(|app_dir| NapiAppDir {
+                    path: app_dir.sys_path.clone(),
+                    is_src_dir: app_dir.is_src_dir,
+                }))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(app_dir)
+}
+
+/// Returns the resolved next config of the project as a JSON string.
+#[napi]
+pub async fn project_config_json(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<String> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let config_json = turbo_tasks
+        .run_once(async move { Ok(container.config_json().await?.clone_value()) })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(config_json)
+}
+
 #[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
 pub fn project_hmr_events(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
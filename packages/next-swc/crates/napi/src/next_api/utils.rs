@@ -212,6 +212,20 @@ impl NapiDiagnostic {
     }
 }
 
+/// Converts diagnostics to be passed to JS. When `category` is set, only the
+/// diagnostics of that category (e.g. `"telemetry"`) are kept, so the others
+/// don't have to be serialized.
+pub fn napi_diagnostics(
+    diagnostics: &[ReadRef<PlainDiagnostic>],
+    category: Option<&str>,
+) -> Vec<NapiDiagnostic> {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| category.map_or(true, |category| diagnostic.category == category))
+        .map(|diagnostic| NapiDiagnostic::from(diagnostic))
+        .collect()
+}
+
 pub struct TurbopackResult<T: ToNapiValue> {
     pub result: T,
     pub issues: Vec<NapiIssue>,
@@ -274,3 +288,57 @@ pub fn subscribe<T: 'static + Send + Sync, F: Future<Output = Result<T>> + Send,
         task_id: Some(task_id),
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::{run_once, TurboTasks};
+    use turbopack_binding::{
+        turbo::tasks_memory::MemoryBackend, turbopack::core::diagnostics::PlainDiagnostic,
+    };
+
+    use super::napi_diagnostics;
+
+    #[tokio::test]
+    async fn test_napi_diagnostics_category() -> Result<()> {
+        crate::register();
+
+        let tt = TurboTasks::new(MemoryBackend::default());
+        run_once(tt, async move {
+            let diagnostic = |category: &str, name: &str| {
+                PlainDiagnostic {
+                    category: category.to_string(),
+                    name: name.to_string(),
+                    payload: HashMap::from([("value".to_string(), name.to_string())]),
+                }
+                .cell()
+            };
+            let diagnostics = vec![
+                diagnostic("telemetry", "swcPlugins").await?,
+                diagnostic("build", "trace").await?,
+                diagnostic("telemetry", "middleware").await?,
+            ];
+
+            let all = napi_diagnostics(&diagnostics, None);
+            assert_eq!(all.len(), 3);
+
+            let telemetry = napi_diagnostics(&diagnostics, Some("telemetry"));
+            let names: Vec<_> = telemetry
+                .iter()
+                .map(|diagnostic| diagnostic.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["swcPlugins", "middleware"]);
+            assert!(telemetry
+                .iter()
+                .all(|diagnostic| diagnostic.category == "telemetry"));
+            assert_eq!(telemetry[1].payload["value"], "middleware");
+
+            assert!(napi_diagnostics(&diagnostics, Some("unknown")).is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -53,6 +53,7 @@ impl TryFrom<NextBuildContext> for NextBuildOptions {
             show_all: true,
             log_detail: true,
             full_stats: true,
+            build_trace: false,
             memory_limit: None,
             build_context: Some(BuildContext {
                 build_id: value
@@ -123,6 +124,8 @@ impl From<NapiRewrite> for Rewrite {
             missing: val
                 .missing
                 .map(|missing| missing.into_iter().map(|missing| missing.into()).collect()),
+            regex: None,
+            regex_keys: vec![],
         }
     }
 }
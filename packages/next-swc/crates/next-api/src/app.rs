@@ -2,7 +2,8 @@ use anyhow::{bail, Context, Result};
 use next_core::{
     all_server_paths,
     app_structure::{
-        get_entrypoints, Entrypoint as AppEntrypoint, Entrypoints as AppEntrypoints, LoaderTree,
+        get_entrypoints, AppStructureOptions, Entrypoint as AppEntrypoint,
+        Entrypoints as AppEntrypoints, LoaderTree,
     },
     get_edge_resolve_options_context,
     mode::NextMode,
@@ -14,6 +15,9 @@ use next_core::{
         get_client_module_options_context, get_client_resolve_options_context,
         get_client_runtime_entries, ClientContextType, RuntimeEntries,
     },
+    next_client_component::environment_only::{
+        validate_client_module_graph, validate_server_module_graph,
+    },
     next_client_reference::{
         ClientReferenceGraph, ClientReferenceType, NextEcmascriptClientReferenceTransition,
     },
@@ -27,10 +31,12 @@ use next_core::{
         get_server_module_options_context, get_server_resolve_options_context,
         get_server_runtime_entries, ServerContextType,
     },
-    util::NextRuntime,
+    util::{NextRuntime, NextSourceConfig},
 };
 use serde::{Deserialize, Serialize};
-use turbo_tasks::{trace::TraceRawVcs, Completion, TryFlatJoinIterExt, TryJoinIterExt, Value, Vc};
+use turbo_tasks::{
+    trace::TraceRawVcs, Completion, TryFlatJoinIterExt, TryJoinIterExt, Value, ValueDefault, Vc,
+};
 use turbopack_binding::{
     turbo::{
         tasks_env::{CustomProcessEnv, ProcessEnv},
@@ -114,8 +120,13 @@ impl AppProject {
     }
 
     #[turbo_tasks::function]
-    fn app_entrypoints(&self) -> Vc<AppEntrypoints> {
-        get_entrypoints(self.app_dir, self.project.next_config().page_extensions())
+    pub(crate) fn app_entrypoints(&self) -> Vc<AppEntrypoints> {
+        let next_config = self.project.next_config();
+        get_entrypoints(
+            self.app_dir,
+            next_config.resolved_page_extensions(),
+            AppStructureOptions::from_next_config(next_config, self.project.project_path()),
+        )
     }
 
     #[turbo_tasks::function]
@@ -459,14 +470,10 @@ impl AppEndpoint {
             self.pathname.clone(),
             self.original_name.clone(),
             self.app_project.project().project_path(),
+            self.app_project.project().next_config(),
         )
     }
 
-    #[turbo_tasks::function]
-    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
-        self.output().output_assets()
-    }
-
     #[turbo_tasks::function]
     async fn output(self: Vc<Self>) -> Result<Vc<AppEndpointOutput>> {
         let this = self.await?;
@@ -531,6 +538,15 @@ impl AppEndpoint {
             .flatten()
             .collect();
 
+        // `server-only` and `client-only` modules must not be bundled for the
+        // other environment.
+        validate_server_module_graph(Vc::upcast(rsc_entry)).await?;
+        for client_reference_ty in client_reference_types.await?.iter() {
+            if let ClientReferenceType::EcmascriptClientReference(entry) = client_reference_ty {
+                validate_client_module_graph(Vc::upcast(entry.await?.client_module)).await?;
+            }
+        }
+
         let app_node_entries: Vec<_> = app_ssr_entries.iter().copied().chain([rsc_entry]).collect();
 
         // TODO(alexkirsz) Handle dynamic entries and dynamic chunks.
@@ -618,6 +634,7 @@ impl AppEndpoint {
             client_references_chunks,
             this.app_project.project().client_chunking_context(),
             Vc::upcast(this.app_project.project().ssr_chunking_context()),
+            this.app_project.project().next_config(),
         );
         server_assets.push(entry_manifest);
 
@@ -876,6 +893,22 @@ impl Endpoint for AppEndpoint {
     fn client_changed(self: Vc<Self>) -> Vc<Completion> {
         any_content_changed_of_output_assets(self.output().client_assets())
     }
+    #[turbo_tasks::function]
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.output().output_assets()
+    }
+
+    #[turbo_tasks::function]
+    async fn required_server_files(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
+        let node_root = self.await?.app_project.project().node_root();
+        Ok(all_server_paths(self.output().server_assets(), node_root))
+    }
+
+    #[turbo_tasks::function]
+    fn source_config(&self) -> Vc<NextSourceConfig> {
+        // App routes are configured with segment config exports instead.
+        NextSourceConfig::value_default()
+    }
 }
 
 #[turbo_tasks::value]
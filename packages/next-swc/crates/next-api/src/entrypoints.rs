@@ -14,3 +14,38 @@ pub struct Entrypoints {
     pub pages_app_endpoint: Vc<Box<dyn Endpoint>>,
     pub pages_error_endpoint: Vc<Box<dyn Endpoint>>,
 }
+
+impl Entrypoints {
+    /// Returns all endpoints of the project, including the ones of middleware
+    /// and the special pages.
+    pub fn endpoints(&self) -> Vec<Vc<Box<dyn Endpoint>>> {
+        let mut endpoints = Vec::new();
+        for route in self.routes.values() {
+            match *route {
+                Route::Page {
+                    html_endpoint,
+                    data_endpoint,
+                } => endpoints.extend([html_endpoint, data_endpoint]),
+                Route::PageApi { endpoint } | Route::AppRoute { endpoint } => {
+                    endpoints.push(endpoint)
+                }
+                Route::AppPage {
+                    html_endpoint,
+                    rsc_endpoint,
+                } => endpoints.extend([html_endpoint, rsc_endpoint]),
+                Route::Conflict => {}
+            }
+        }
+        endpoints.extend(
+            self.middleware
+                .as_ref()
+                .map(|middleware| middleware.endpoint),
+        );
+        endpoints.extend([
+            self.pages_document_endpoint,
+            self.pages_app_endpoint,
+            self.pages_error_endpoint,
+        ]);
+        endpoints
+    }
+}
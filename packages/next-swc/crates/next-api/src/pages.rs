@@ -8,6 +8,7 @@ use next_core::{
         get_client_module_options_context, get_client_resolve_options_context,
         get_client_runtime_entries, ClientContextType, RuntimeEntries,
     },
+    next_client_component::environment_only::validate_client_module_graph,
     next_dynamic::NextDynamicTransition,
     next_manifests::{BuildManifest, PagesManifest},
     next_pages::create_page_ssr_entry_module,
@@ -18,7 +19,10 @@ use next_core::{
     pages_structure::{
         find_pages_structure, PagesDirectoryStructure, PagesStructure, PagesStructureItem,
     },
-    util::{get_asset_prefix_from_pathname, parse_config_from_source, NextRuntime},
+    util::{
+        get_asset_prefix_from_pathname, get_page_runtime, parse_page_config_from_source,
+        NextRuntime, NextSourceConfig,
+    },
     PageLoaderAsset,
 };
 use serde::{Deserialize, Serialize};
@@ -212,13 +216,13 @@ impl PagesProject {
     }
 
     #[turbo_tasks::function]
-    fn pages_structure(&self) -> Vc<PagesStructure> {
+    pub(crate) fn pages_structure(&self) -> Vc<PagesStructure> {
         let next_router_fs = Vc::upcast::<Box<dyn FileSystem>>(VirtualFileSystem::new());
         let next_router_root = next_router_fs.root();
         find_pages_structure(
             self.project.project_path(),
             next_router_root,
-            self.project.next_config().page_extensions(),
+            self.project.next_config().resolved_page_extensions(),
         )
     }
 
@@ -513,6 +517,9 @@ impl PageEndpoint {
         let client_module =
             create_page_loader_entry_module(client_module_context, self.source(), this.pathname);
 
+        // `server-only` modules must not be bundled for the client.
+        validate_client_module_graph(client_module).await?;
+
         let Some(client_module) =
             Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(client_module).await?
         else {
@@ -590,8 +597,13 @@ impl PageEndpoint {
 
         let ssr_module = module_context.process(self.source(), reference_type.clone());
 
-        let config = parse_config_from_source(ssr_module).await?;
-        let is_edge = matches!(config.runtime, NextRuntime::Edge);
+        let is_edge = get_page_runtime(
+            ssr_module,
+            self.source(),
+            this.pages_project.project().next_config(),
+        )
+        .await?
+            == NextRuntime::Edge;
 
         if is_edge {
             let ssr_module = create_page_ssr_entry_module(
@@ -763,11 +775,6 @@ impl PageEndpoint {
         )))
     }
 
-    #[turbo_tasks::function]
-    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
-        self.output().output_assets()
-    }
-
     #[turbo_tasks::function]
     async fn output(self: Vc<Self>) -> Result<Vc<PageEndpointOutput>> {
         let this = self.await?;
@@ -878,6 +885,34 @@ impl Endpoint for PageEndpoint {
     fn client_changed(self: Vc<Self>) -> Vc<Completion> {
         any_content_changed_of_output_assets(self.output().client_assets())
     }
+    #[turbo_tasks::function]
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.output().output_assets()
+    }
+
+    #[turbo_tasks::function]
+    async fn required_server_files(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
+        let node_root = self.await?.pages_project.project().node_root();
+        Ok(all_server_paths(self.output().server_assets(), node_root))
+    }
+
+    #[turbo_tasks::function]
+    async fn source_config(self: Vc<Self>) -> Result<Vc<NextSourceConfig>> {
+        let this = self.await?;
+        let reference_type = match this.ty {
+            PageEndpointType::Api => EntryReferenceSubType::PagesApi,
+            _ => EntryReferenceSubType::Page,
+        };
+        let ssr_module = this.pages_project.ssr_module_context().process(
+            self.source(),
+            Value::new(ReferenceType::Entry(reference_type)),
+        );
+        Ok(parse_page_config_from_source(
+            ssr_module,
+            self.source(),
+            this.pages_project.project().next_config(),
+        ))
+    }
 }
 
 #[turbo_tasks::value]
@@ -11,7 +11,9 @@ use next_core::{
     next_config::{JsConfig, NextConfig},
     next_server::{get_server_chunking_context, get_server_compile_time_info},
     next_telemetry::NextFeatureTelemetry,
-    util::NextSourceConfig,
+    route_conflicts::validate_route_conflicts,
+    util::{invalidate_disk_fs, NextSourceConfig},
+    EmitOptions, EmitProgressCallback,
 };
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
@@ -79,6 +81,18 @@ pub struct Middleware {
     pub config: NextSourceConfig,
 }
 
+/// The app directory of a project, see [Project::app_dir_info].
+#[turbo_tasks::value(shared)]
+pub struct AppDirInfo {
+    /// The absolute path of the app directory, with symlinks resolved.
+    pub sys_path: String,
+    /// Whether the app directory is `src/app` instead of `app`.
+    pub is_src_dir: bool,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionAppDirInfo(Option<AppDirInfo>);
+
 #[turbo_tasks::value]
 pub struct ProjectContainer {
     options_state: State<ProjectOptions>,
@@ -136,6 +150,56 @@ impl ProjectContainer {
     pub fn hmr_identifiers(self: Vc<Self>) -> Vc<Vec<String>> {
         self.project().hmr_identifiers()
     }
+
+    /// See [Project::validate].
+    #[turbo_tasks::function]
+    pub fn validate(self: Vc<Self>) -> Vc<Completion> {
+        self.project().validate()
+    }
+
+    /// See [Project::config_json].
+    #[turbo_tasks::function]
+    pub fn config_json(self: Vc<Self>) -> Vc<String> {
+        self.project().config_json()
+    }
+
+    /// See [Project::route_output_assets].
+    #[turbo_tasks::function]
+    pub fn route_output_assets(self: Vc<Self>, pathname: String) -> Vc<OutputAssets> {
+        self.project().route_output_assets(pathname)
+    }
+
+    /// See [Project::emit_route_output_assets].
+    #[turbo_tasks::function]
+    pub fn emit_route_output_assets(
+        self: Vc<Self>,
+        pathname: String,
+        on_emit: TransientInstance<EmitProgressCallback>,
+    ) -> Vc<Completion> {
+        self.project().emit_route_output_assets(pathname, on_emit)
+    }
+
+    /// See [Project::app_dir_info].
+    #[turbo_tasks::function]
+    pub fn app_dir_info(self: Vc<Self>) -> Vc<OptionAppDirInfo> {
+        self.project().app_dir_info()
+    }
+}
+
+impl ProjectContainer {
+    /// Invalidates every file read of the project, so that everything computed
+    /// from the project's files is recomputed on its next use.
+    ///
+    /// This is as expensive as a cold start: all entrypoints, chunks and
+    /// assets are rebuilt from scratch. It is meant for files changing behind
+    /// the file watcher's back, e.g. the `next` package being upgraded at
+    /// runtime.
+    pub async fn invalidate_all(self: Vc<Self>) -> Result<()> {
+        let project = self.project();
+        invalidate_disk_fs(project.project_fs()).await?;
+        invalidate_disk_fs(project.node_fs()).await?;
+        Ok(())
+    }
 }
 
 #[turbo_tasks::value]
@@ -471,6 +535,11 @@ impl Project {
         if let Some(app_project) = &*app_project.await? {
             let app_routes = app_project.routes();
             routes.extend(app_routes.await?.iter().map(|(k, v)| (k.clone(), *v)));
+            validate_route_conflicts(
+                app_project.app_entrypoints(),
+                pages_project.pages_structure(),
+            )
+            .await?;
         }
 
         for (pathname, page_route) in pages_project.routes().await?.iter() {
@@ -495,6 +564,60 @@ impl Project {
         .cell())
     }
 
+    /// Resolves the project structure (app/pages directories and the next
+    /// config) without building any endpoint. Issues emitted while doing so,
+    /// e.g. conflicting routes, can be collected from the returned completion.
+    #[turbo_tasks::function]
+    pub async fn validate(self: Vc<Self>) -> Result<Vc<Completion>> {
+        self.next_config().await?;
+        self.entrypoints().await?;
+        Ok(Completion::new())
+    }
+
+    /// The resolved next config of the project, serialized as JSON.
+    #[turbo_tasks::function]
+    pub fn config_json(self: Vc<Self>) -> Vc<String> {
+        self.next_config().to_json()
+    }
+
+    /// The app directory of the project (see [find_app_dir]), e.g. for tools
+    /// watching it. `None` when the project has no app directory.
+    #[turbo_tasks::function]
+    pub async fn app_dir_info(self: Vc<Self>) -> Result<Vc<OptionAppDirInfo>> {
+        let project_path = self.project_path();
+        let Some(app_dir) = *find_app_dir(project_path).await? else {
+            return Ok(Vc::cell(None));
+        };
+        let Some(disk_fs) =
+            Vc::try_resolve_downcast_type::<DiskFileSystem>(self.project_fs()).await?
+        else {
+            return Ok(Vc::cell(None));
+        };
+        let sys_path = disk_fs.await?.to_sys_path(app_dir).await?;
+        let src_app_dir = project_path.join("src/app".to_string()).realpath();
+        Ok(Vc::cell(Some(AppDirInfo {
+            sys_path: sys_path.to_string_lossy().to_string(),
+            is_src_dir: src_app_dir.await?.path == app_dir.await?.path,
+        })))
+    }
+
+    /// All assets of the page or route at `pathname` (including the leading
+    /// slash), i.e. its entry chunks and everything they reference. Only the
+    /// endpoint of that route is built, and nothing is written to disk. Pages
+    /// get the assets of their HTML endpoint. Unknown and conflicting paths
+    /// have no assets.
+    #[turbo_tasks::function]
+    pub async fn route_output_assets(self: Vc<Self>, pathname: String) -> Result<Vc<OutputAssets>> {
+        let endpoint = match self.entrypoints().await?.routes.get(&pathname) {
+            Some(Route::Page { html_endpoint, .. } | Route::AppPage { html_endpoint, .. }) => {
+                *html_endpoint
+            }
+            Some(Route::PageApi { endpoint } | Route::AppRoute { endpoint }) => *endpoint,
+            Some(Route::Conflict) | None => return Ok(OutputAssets::empty()),
+        };
+        Ok(all_assets_from_entries(endpoint.output_assets()))
+    }
+
     #[turbo_tasks::function]
     pub async fn emit_all_output_assets(
         self: Vc<Self>,
@@ -512,9 +635,30 @@ impl Project {
             self.node_root(),
             self.client_relative_path(),
             self.node_root(),
+            EmitOptions::default().cell(),
+            None,
         ))
     }
 
+    /// Writes the assets of the page or route at `pathname` (see
+    /// [Project::route_output_assets]) to disk, calling `on_emit` with each
+    /// file as soon as it has been written.
+    #[turbo_tasks::function]
+    pub fn emit_route_output_assets(
+        self: Vc<Self>,
+        pathname: String,
+        on_emit: TransientInstance<EmitProgressCallback>,
+    ) -> Vc<Completion> {
+        emit_assets(
+            self.route_output_assets(pathname),
+            self.node_root(),
+            self.client_relative_path(),
+            self.node_root(),
+            EmitOptions::default().cell(),
+            Some(on_emit),
+        )
+    }
+
     #[turbo_tasks::function]
     async fn hmr_content(
         self: Vc<Self>,
@@ -1,5 +1,7 @@
 use indexmap::IndexMap;
+use next_core::util::NextSourceConfig;
 use turbo_tasks::{Completion, Vc};
+use turbopack_binding::turbopack::core::output::OutputAssets;
 
 #[turbo_tasks::value(shared)]
 #[derive(Copy, Clone, Debug)]
@@ -26,6 +28,15 @@ pub trait Endpoint {
     fn write_to_disk(self: Vc<Self>) -> Vc<WrittenEndpoint>;
     fn server_changed(self: Vc<Self>) -> Vc<Completion>;
     fn client_changed(self: Vc<Self>) -> Vc<Completion>;
+    /// The server files required to run this endpoint, relative to the node
+    /// root. This doesn't write anything to disk.
+    fn required_server_files(self: Vc<Self>) -> Vc<Vec<String>>;
+    /// The config statically exported from the endpoint's source (e.g. its
+    /// runtime and middleware matchers). This doesn't write anything to disk.
+    fn source_config(self: Vc<Self>) -> Vc<NextSourceConfig>;
+    /// All server and client assets emitted for this endpoint. This doesn't
+    /// write anything to disk.
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets>;
 }
 
 #[turbo_tasks::value(shared)]
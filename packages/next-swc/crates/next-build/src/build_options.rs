@@ -25,6 +25,10 @@ pub struct BuildOptions {
     /// Whether to compute full stats.
     pub full_stats: bool,
 
+    /// Whether to write a trace of the durations of the build phases to
+    /// `.next/trace-build.json`.
+    pub build_trace: bool,
+
     /// The Next.js build context.
     pub build_context: Option<BuildContext>,
 }
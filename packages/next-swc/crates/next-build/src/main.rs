@@ -46,6 +46,11 @@ pub struct BuildCliArgs {
     #[clap(long)]
     pub full_stats: bool,
 
+    /// Write the durations and asset counts of the build phases to
+    /// `.next/trace-build.json`.
+    #[clap(long)]
+    pub build_trace: bool,
+
     /// Enable experimental garbage collection with the provided memory limit in
     /// MB.
     #[clap(long)]
@@ -99,6 +104,7 @@ async fn main_inner() -> Result<()> {
         show_all: args.show_all,
         log_detail: args.log_detail,
         full_stats: args.full_stats,
+        build_trace: args.build_trace,
         build_context: None,
     })
     .await
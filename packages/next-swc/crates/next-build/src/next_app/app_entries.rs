@@ -2,11 +2,15 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use next_core::{
-    app_structure::{find_app_dir_if_enabled, get_entrypoints, get_global_metadata, Entrypoint},
+    app_structure::{
+        find_app_dir_if_enabled, get_entrypoints, get_global_metadata, AppStructureOptions,
+        Entrypoint,
+    },
     mode::NextMode,
     next_app::{
         get_app_client_shared_chunks, get_app_page_entry, get_app_route_entry,
-        get_app_route_favicon_entry, AppEntry, ClientReferencesChunks,
+        get_app_route_favicon_entry, validate_metadata_default_exports, AppEntry,
+        ClientReferencesChunks,
     },
     next_client::{
         get_client_module_options_context, get_client_resolve_options_context,
@@ -71,7 +75,11 @@ pub async fn get_app_entries(
         }));
     };
 
-    let entrypoints = get_entrypoints(app_dir, next_config.page_extensions());
+    let entrypoints = get_entrypoints(
+        app_dir,
+        next_config.resolved_page_extensions(),
+        AppStructureOptions::from_next_config(next_config, project_root),
+    );
 
     let mode = NextMode::Build;
 
@@ -213,13 +221,19 @@ pub async fn get_app_entries(
                     pathname.clone(),
                     original_name.clone(),
                     project_root,
+                    next_config,
                 ),
             })
         })
         .try_join()
         .await?;
 
-    let global_metadata = get_global_metadata(app_dir, next_config.page_extensions());
+    let global_metadata = get_global_metadata(
+        app_dir,
+        next_config.resolved_page_extensions(),
+        AppStructureOptions::from_next_config(next_config, project_root),
+    );
+    validate_metadata_default_exports(global_metadata, Vc::upcast(rsc_context)).await?;
     let global_metadata = global_metadata.await?;
 
     if let Some(favicon) = global_metadata.favicon {
@@ -229,6 +243,7 @@ pub async fn get_app_entries(
             rsc_context,
             favicon,
             project_root,
+            next_config,
         ));
     }
 
@@ -267,6 +282,7 @@ pub async fn compute_app_entries_chunks(
     ssr_chunking_context: Vc<Box<dyn EcmascriptChunkingContext>>,
     node_root: Vc<FileSystemPath>,
     client_relative_path: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
     app_paths_manifest_dir_path: &FileSystemPath,
     app_build_manifest: &mut AppBuildManifest,
     build_manifest: &mut BuildManifest,
@@ -360,6 +376,7 @@ pub async fn compute_app_entries_chunks(
             app_client_references_chunks,
             client_chunking_context,
             ssr_chunking_context,
+            next_config,
         );
 
         all_chunks.push(entry_manifest);
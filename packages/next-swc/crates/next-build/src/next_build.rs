@@ -7,6 +7,7 @@ use std::{
 use anyhow::{Context, Result};
 use dunce::canonicalize;
 use next_core::{
+    build_trace::BuildTrace,
     mode::NextMode,
     next_app::get_app_client_references_chunks,
     next_client::{get_client_chunking_context, get_client_compile_time_info},
@@ -133,6 +134,8 @@ pub(crate) async fn next_build(options: TransientInstance<BuildOptions>) -> Resu
     // TODO(alexkirsz) Pages should build their own routes, outside of a FS.
     let next_router_fs = Vc::upcast::<Box<dyn FileSystem>>(VirtualFileSystem::new());
     let next_router_root = next_router_fs.root();
+    let mut trace = BuildTrace::default();
+
     let page_entries = get_page_entries(
         next_router_root,
         project_root,
@@ -152,25 +155,28 @@ pub(crate) async fn next_build(options: TransientInstance<BuildOptions>) -> Resu
         next_config,
     );
 
-    handle_issues(
-        page_entries,
-        issue_reporter,
-        MIN_FAILING_SEVERITY.cell(),
-        None,
-        None,
-    )
-    .await?;
-    handle_issues(
-        app_entries,
-        issue_reporter,
-        MIN_FAILING_SEVERITY.cell(),
-        None,
-        None,
-    )
-    .await?;
+    let (page_entries, app_entries) = trace
+        .record("entries", async {
+            handle_issues(
+                page_entries,
+                issue_reporter,
+                MIN_FAILING_SEVERITY.cell(),
+                None,
+                None,
+            )
+            .await?;
+            handle_issues(
+                app_entries,
+                issue_reporter,
+                MIN_FAILING_SEVERITY.cell(),
+                None,
+                None,
+            )
+            .await?;
 
-    let page_entries = page_entries.await?;
-    let app_entries = app_entries.await?;
+            Ok((page_entries.await?, app_entries.await?))
+        })
+        .await?;
 
     let app_rsc_entries: Vec<_> = app_entries
         .entries
@@ -265,27 +271,10 @@ pub(crate) async fn next_build(options: TransientInstance<BuildOptions>) -> Resu
     let client_relative_path = client_root.join("_next".to_string());
     let client_relative_path_ref = client_relative_path.await?;
 
-    // PAGE CHUNKING
-
     let mut pages_manifest: PagesManifest = Default::default();
     let pages_manifest_path = node_root.join("server/pages-manifest.json".to_string());
     let pages_manifest_dir_path = pages_manifest_path.parent().await?;
 
-    compute_page_entries_chunks(
-        &page_entries,
-        client_chunking_context,
-        ssr_chunking_context,
-        node_root,
-        &pages_manifest_dir_path,
-        &client_relative_path_ref,
-        &mut pages_manifest,
-        &mut build_manifest,
-        &mut all_chunks,
-    )
-    .await?;
-
-    // APP CHUNKING
-
     let mut app_build_manifest = AppBuildManifest::default();
     let app_build_manifest_path = client_root.join("app-build-manifest.json".to_string());
 
@@ -293,41 +282,63 @@ pub(crate) async fn next_build(options: TransientInstance<BuildOptions>) -> Resu
     let app_paths_manifest_path = node_root.join("server/app-paths-manifest.json".to_string());
     let app_paths_manifest_dir_path = app_paths_manifest_path.parent().await?;
 
-    // APP CLIENT REFERENCES CHUNKING
-
-    let app_client_references_chunks = get_app_client_references_chunks(
-        app_client_reference_tys,
-        client_chunking_context,
-        ssr_chunking_context,
-    );
-    let app_client_references_chunks_ref = app_client_references_chunks.await?;
-
-    for app_client_reference_chunks in app_client_references_chunks_ref.values() {
-        let client_chunks = &app_client_reference_chunks.client_chunks.await?;
-        let ssr_chunks = &app_client_reference_chunks.ssr_chunks.await?;
-        all_chunks.extend(client_chunks.iter().copied());
-        all_chunks.extend(ssr_chunks.iter().copied());
-    }
+    trace
+        .record_assets("chunking", async {
+            // PAGE CHUNKING
+
+            compute_page_entries_chunks(
+                &page_entries,
+                client_chunking_context,
+                ssr_chunking_context,
+                node_root,
+                &pages_manifest_dir_path,
+                &client_relative_path_ref,
+                &mut pages_manifest,
+                &mut build_manifest,
+                &mut all_chunks,
+            )
+            .await?;
+
+            // APP CLIENT REFERENCES CHUNKING
+
+            let app_client_references_chunks = get_app_client_references_chunks(
+                app_client_reference_tys,
+                client_chunking_context,
+                ssr_chunking_context,
+            );
+            let app_client_references_chunks_ref = app_client_references_chunks.await?;
+
+            for app_client_reference_chunks in app_client_references_chunks_ref.values() {
+                let client_chunks = &app_client_reference_chunks.client_chunks.await?;
+                let ssr_chunks = &app_client_reference_chunks.ssr_chunks.await?;
+                all_chunks.extend(client_chunks.iter().copied());
+                all_chunks.extend(ssr_chunks.iter().copied());
+            }
 
-    // APP RSC CHUNKING
-    // TODO(alexkirsz) Do some of that in parallel with the above.
+            // APP RSC CHUNKING
+            // TODO(alexkirsz) Do some of that in parallel with the above.
+
+            compute_app_entries_chunks(
+                &app_entries,
+                app_client_references,
+                app_client_references_chunks,
+                rsc_chunking_context,
+                client_chunking_context,
+                Vc::upcast(ssr_chunking_context),
+                node_root,
+                client_relative_path,
+                next_config,
+                &app_paths_manifest_dir_path,
+                &mut app_build_manifest,
+                &mut build_manifest,
+                &mut app_paths_manifest,
+                &mut all_chunks,
+            )
+            .await?;
 
-    compute_app_entries_chunks(
-        &app_entries,
-        app_client_references,
-        app_client_references_chunks,
-        rsc_chunking_context,
-        client_chunking_context,
-        Vc::upcast(ssr_chunking_context),
-        node_root,
-        client_relative_path,
-        &app_paths_manifest_dir_path,
-        &mut app_build_manifest,
-        &mut build_manifest,
-        &mut app_paths_manifest,
-        &mut all_chunks,
-    )
-    .await?;
+            Ok(Vc::cell(all_chunks.clone()))
+        })
+        .await?;
 
     let mut completions = vec![];
 
@@ -434,15 +445,24 @@ pub(crate) async fn next_build(options: TransientInstance<BuildOptions>) -> Resu
         node_root.join("react-loadable-manifest.json".to_string()),
     )?);
 
-    completions.push(
-        emit_all_assets(
-            all_chunks,
-            &node_root_ref,
-            client_relative_path,
-            client_root,
-        )
-        .await?,
-    );
+    let emitted = emit_all_assets(
+        all_chunks,
+        &node_root_ref,
+        client_relative_path,
+        client_root,
+    )
+    .await?;
+    trace
+        .record("emit", async {
+            emitted.await?;
+            Ok(())
+        })
+        .await?;
+    completions.push(emitted);
+
+    if options.build_trace {
+        completions.push(trace.write(node_root.join("trace-build.json".to_string()))?);
+    }
 
     Ok(Completions::all(completions))
 }
@@ -63,7 +63,7 @@ pub async fn get_page_entries(
     let pages_structure = find_pages_structure(
         project_root,
         next_router_root,
-        next_config.page_extensions(),
+        next_config.resolved_page_extensions(),
     );
 
     let pages_dir = if let Some(pages) = pages_structure.await?.pages {
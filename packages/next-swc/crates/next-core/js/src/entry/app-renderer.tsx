@@ -146,12 +146,20 @@ async function runOperation(renderData: RenderData) {
   } else {
     body.write(result.toUnchunkedString())
   }
+  const headers: [string, string][] = [
+    ['Content-Type', result.contentType ?? MIME_TEXT_HTML_UTF8],
+    ['Vary', RSC_VARY_HEADER],
+  ]
+  const revalidate = renderData.data?.revalidate
+  if (!result.isDynamic && typeof revalidate === 'number' && revalidate > 0) {
+    headers.push([
+      'Cache-Control',
+      `s-maxage=${revalidate}, stale-while-revalidate`,
+    ])
+  }
   return {
     statusCode: res.statusCode,
-    headers: [
-      ['Content-Type', result.contentType ?? MIME_TEXT_HTML_UTF8],
-      ['Vary', RSC_VARY_HEADER],
-    ] as [string, string][],
+    headers,
     body,
   }
 }
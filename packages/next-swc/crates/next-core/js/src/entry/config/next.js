@@ -1,5 +1,7 @@
 import loadConfig from 'next/dist/server/config'
 import loadCustomRoutes from 'next/dist/lib/load-custom-routes'
+import { buildCustomRoute } from 'next/dist/lib/build-custom-route'
+import { parse as parsePath } from 'next/dist/compiled/path-to-regexp'
 import { PHASE_DEVELOPMENT_SERVER } from 'next/dist/shared/lib/constants'
 import assert from 'node:assert'
 
@@ -14,7 +16,10 @@ const loadNextConfig = async (silent) => {
 
   nextConfig.generateBuildId = await nextConfig.generateBuildId?.()
 
-  const customRoutes = await loadCustomRoutes(nextConfig)
+  const customRoutes = compileCustomRoutes(
+    await loadCustomRoutes(nextConfig),
+    nextConfig.basePath
+  )
 
   // TODO: these functions takes arguments, have to be supported in a different way
   nextConfig.exportPathMap = nextConfig.exportPathMap && {}
@@ -53,6 +58,33 @@ const loadNextConfig = async (silent) => {
 
 export { loadNextConfig as default }
 
+// Adds the regexes the routes are matched with, as they're written to the
+// routes manifest, together with the names of their capture groups.
+function compileCustomRoutes({ redirects, headers, rewrites }, basePath) {
+  const restrictedRedirectPaths = ['/_next'].map((p) =>
+    basePath ? `${basePath}${p}` : p
+  )
+  const compile = (type) => (route) => {
+    const { regex } = buildCustomRoute(route, type, restrictedRedirectPaths)
+    return {
+      ...route,
+      regex,
+      regexKeys: parsePath(route.source)
+        .filter((token) => typeof token !== 'string')
+        .map((token) => String(token.name)),
+    }
+  }
+  return {
+    redirects: redirects.map(compile('redirect')),
+    headers: headers.map(compile('header')),
+    rewrites: {
+      beforeFiles: rewrites.beforeFiles.map(compile('rewrite')),
+      afterFiles: rewrites.afterFiles.map(compile('rewrite')),
+      fallback: rewrites.fallback.map(compile('rewrite')),
+    },
+  }
+}
+
 function ensureLoadersHaveSerializableOptions(turbopackLoaders) {
   for (const [ext, loaderItems] of Object.entries(turbopackLoaders)) {
     for (const loaderItem of loaderItems) {
@@ -15,5 +15,7 @@ export type RenderData = {
     allowedRevalidateHeaderKeys?: string[]
     fetchCacheKeyPrefix?: string
     isrMemoryCacheSize?: number
+    /** The effective `revalidate` of the route's segment configs. */
+    revalidate?: number | false
   }
 }
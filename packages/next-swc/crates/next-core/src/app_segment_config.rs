@@ -1,13 +1,17 @@
 use std::ops::Deref;
 
 use anyhow::{bail, Result};
+use async_recursion::async_recursion;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use swc_core::{
     common::{source_map::Pos, Span, Spanned},
-    ecma::ast::{Expr, Ident, Program},
+    ecma::ast::{
+        Decl, ExportSpecifier, Expr, Ident, ModuleDecl, ModuleExportName, ModuleItem, Program,
+    },
 };
-use turbo_tasks::{trace::TraceRawVcs, TryJoinIterExt, ValueDefault, Vc};
+use turbo_tasks::{trace::TraceRawVcs, Completion, TryJoinIterExt, ValueDefault, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_binding::turbopack::{
     core::{
@@ -16,7 +20,7 @@ use turbopack_binding::turbopack::{
         ident::AssetIdent,
         issue::{Issue, IssueExt, IssueSeverity, IssueSource, OptionIssueSource},
         module::Module,
-        reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
+        reference_type::{EcmaScriptModulesReferenceSubType, EntryReferenceSubType, ReferenceType},
         source::Source,
     },
     ecmascript::{
@@ -26,7 +30,11 @@ use turbopack_binding::turbopack::{
     },
 };
 
-use crate::{app_structure::LoaderTree, util::NextRuntime};
+use crate::{
+    app_structure::{Components, Entrypoint, Entrypoints, LoaderTree},
+    next_config::NextConfig,
+    util::NextRuntime,
+};
 
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug, TraceRawVcs, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -62,7 +70,7 @@ pub enum NextRevalidate {
 }
 
 #[turbo_tasks::value]
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct NextSegmentConfig {
     pub dynamic: Option<NextSegmentDynamic>,
     pub dynamic_params: Option<bool>,
@@ -70,6 +78,17 @@ pub struct NextSegmentConfig {
     pub fetch_cache: Option<NextSegmentFetchCache>,
     pub runtime: Option<NextRuntime>,
     pub preferred_region: Option<String>,
+    /// The maximum duration of the route's function in seconds.
+    pub max_duration: Option<u32>,
+    /// Whether the segment exports a `generateMetadata` function.
+    pub generate_metadata: bool,
+    /// Whether the segment exports a `generateViewport` function.
+    pub generate_viewport: bool,
+    /// Whether the segment exports a `generateStaticParams` function.
+    pub generate_static_params: bool,
+    /// Whether the segment imports dynamic APIs (e.g. `cookies()` from
+    /// `next/headers`) which opt it into dynamic rendering.
+    pub uses_dynamic_apis: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -91,6 +110,11 @@ impl NextSegmentConfig {
             fetch_cache,
             runtime,
             preferred_region,
+            max_duration,
+            generate_metadata,
+            generate_viewport,
+            generate_static_params,
+            uses_dynamic_apis,
         } = self;
         *dynamic = dynamic.or(parent.dynamic);
         *dynamic_params = dynamic_params.or(parent.dynamic_params);
@@ -98,6 +122,11 @@ impl NextSegmentConfig {
         *fetch_cache = fetch_cache.or(parent.fetch_cache);
         *runtime = runtime.or(parent.runtime);
         *preferred_region = preferred_region.take().or(parent.preferred_region.clone());
+        *max_duration = max_duration.or(parent.max_duration);
+        *generate_metadata |= parent.generate_metadata;
+        *generate_viewport |= parent.generate_viewport;
+        *generate_static_params |= parent.generate_static_params;
+        *uses_dynamic_apis |= parent.uses_dynamic_apis;
     }
 
     /// Applies a config from a paralllel route to this config, returning an
@@ -131,6 +160,11 @@ impl NextSegmentConfig {
             fetch_cache,
             runtime,
             preferred_region,
+            max_duration,
+            generate_metadata,
+            generate_viewport,
+            generate_static_params,
+            uses_dynamic_apis,
         } = self;
         merge_parallel(dynamic, &parallel_config.dynamic, "dynamic")?;
         merge_parallel(
@@ -146,6 +180,11 @@ impl NextSegmentConfig {
             &parallel_config.preferred_region,
             "referredRegion",
         )?;
+        merge_parallel(max_duration, &parallel_config.max_duration, "maxDuration")?;
+        *generate_metadata |= parallel_config.generate_metadata;
+        *generate_viewport |= parallel_config.generate_viewport;
+        *generate_static_params |= parallel_config.generate_static_params;
+        *uses_dynamic_apis |= parallel_config.uses_dynamic_apis;
         Ok(())
     }
 }
@@ -229,6 +268,8 @@ pub async fn parse_segment_config_from_source(
     };
 
     let mut config = NextSegmentConfig::default();
+    detect_special_exports(&mut config, &module_ast.body);
+    detect_dynamic_api_usage(&mut config, &module_ast.body);
 
     for item in &module_ast.body {
         let Some(decl) = item
@@ -253,6 +294,89 @@ pub async fn parse_segment_config_from_source(
     Ok(config.cell())
 }
 
+/// Parses the segment config of the app route `source`, processed as an app
+/// route entry in `context`.
+#[turbo_tasks::function]
+pub fn parse_app_route_config(
+    source: Vc<Box<dyn Source>>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Vc<NextSegmentConfig> {
+    parse_segment_config_from_source(
+        context.process(
+            source,
+            turbo_tasks::Value::new(ReferenceType::Entry(EntryReferenceSubType::AppRoute)),
+        ),
+        source,
+    )
+}
+
+/// Returns the runtime of the app route at `route`: the `runtime` exported by
+/// the route itself, or otherwise the default runtime of the Next.js config.
+pub async fn get_app_route_runtime(
+    route: Vc<FileSystemPath>,
+    context: Vc<Box<dyn AssetContext>>,
+    next_config: Vc<NextConfig>,
+) -> Result<NextRuntime> {
+    let config = parse_app_route_config(Vc::upcast(FileSource::new(route)), context).await?;
+    Ok(next_config.await?.effective_runtime(config.runtime))
+}
+
+/// Records whether the module exports `generateMetadata`, `generateViewport`
+/// or `generateStaticParams`, either declared in the module or re-exported from
+/// another one, so that static shortcuts can be skipped for the segment.
+fn detect_special_exports(config: &mut NextSegmentConfig, body: &[ModuleItem]) {
+    let mut exported_names = Vec::new();
+    for item in body {
+        match item.as_module_decl() {
+            Some(ModuleDecl::ExportDecl(export_decl)) => match &export_decl.decl {
+                Decl::Fn(fn_decl) => exported_names.push(&fn_decl.ident.sym),
+                Decl::Var(var_decl) => exported_names.extend(
+                    var_decl
+                        .decls
+                        .iter()
+                        .filter_map(|decl| decl.name.as_ident().map(|ident| &ident.id.sym)),
+                ),
+                _ => {}
+            },
+            Some(ModuleDecl::ExportNamed(named_export)) => {
+                exported_names.extend(named_export.specifiers.iter().filter_map(|specifier| {
+                    let ExportSpecifier::Named(named) = specifier else {
+                        return None;
+                    };
+                    match named.exported.as_ref().unwrap_or(&named.orig) {
+                        ModuleExportName::Ident(ident) => Some(&ident.sym),
+                        ModuleExportName::Str(_) => None,
+                    }
+                }))
+            }
+            _ => {}
+        }
+    }
+
+    for name in exported_names {
+        match &**name {
+            "generateMetadata" => config.generate_metadata = true,
+            "generateViewport" => config.generate_viewport = true,
+            "generateStaticParams" => config.generate_static_params = true,
+            _ => {}
+        }
+    }
+}
+
+/// Modules whose imports opt a segment into dynamic rendering.
+const DYNAMIC_API_MODULES: &[&str] = &["next/headers"];
+
+/// Records whether the module imports any request-time APIs.
+fn detect_dynamic_api_usage(config: &mut NextSegmentConfig, body: &[ModuleItem]) {
+    config.uses_dynamic_apis |= body.iter().any(|item| {
+        matches!(
+            item.as_module_decl(),
+            Some(ModuleDecl::Import(import)) if !import.type_only
+                && DYNAMIC_API_MODULES.contains(&&*import.src.value)
+        )
+    });
+}
+
 fn issue_source(source: Vc<Box<dyn Source>>, span: Span) -> Vc<IssueSource> {
     IssueSource::from_byte_offset(source, span.lo.to_usize(), span.hi.to_usize())
 }
@@ -365,6 +489,24 @@ fn parse_config_value(
 
             config.preferred_region = Some(val.to_string());
         }
+        "maxDuration" => {
+            let value = eval_context.eval(init);
+            match value {
+                // Reject zero, fractions, infinity and values out of range
+                // instead of truncating them.
+                JsValue::Constant(ConstantValue::Num(ConstantNumber(val)))
+                    if val.is_finite()
+                        && val.fract() == 0.0
+                        && (1.0..=u32::MAX as f64).contains(&val) =>
+                {
+                    config.max_duration = Some(val as u32);
+                }
+                _ => invalid_config(
+                    "`maxDuration` needs to be a static positive integer",
+                    &value,
+                ),
+            }
+        }
         _ => {}
     }
 }
@@ -375,7 +517,6 @@ pub async fn parse_segment_config_from_loader_tree(
     context: Vc<Box<dyn AssetContext>>,
 ) -> Result<Vc<NextSegmentConfig>> {
     let loader_tree = loader_tree.await?;
-    let components = loader_tree.components.await?;
     let mut config = NextSegmentConfig::default();
     let parallel_configs = loader_tree
         .parallel_routes
@@ -387,6 +528,18 @@ pub async fn parse_segment_config_from_loader_tree(
     for tree in parallel_configs {
         config.apply_parallel_config(&tree)?;
     }
+    config.apply_parent_config(
+        &parse_segment_config_from_components(loader_tree.components, context).await?,
+    );
+    Ok(config.cell())
+}
+
+async fn parse_segment_config_from_components(
+    components: Vc<Components>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Result<NextSegmentConfig> {
+    let components = components.await?;
+    let mut config = NextSegmentConfig::default();
     for component in [components.page, components.default, components.layout]
         .into_iter()
         .flatten()
@@ -405,5 +558,757 @@ pub async fn parse_segment_config_from_loader_tree(
             .await?,
         );
     }
-    Ok(config.cell())
+    Ok(config)
+}
+
+/// Whether a segment can be prerendered at build time.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, TraceRawVcs, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SegmentRendering {
+    Static,
+    Dynamic,
+}
+
+/// The [SegmentRendering] of each segment of a loader tree, keyed by the
+/// segment path (e.g. `/blog/[slug]` or `/@modal`).
+#[turbo_tasks::value(transparent)]
+pub struct SegmentRenderingMap(IndexMap<String, SegmentRendering>);
+
+/// Classifies a segment from its effective config (i.e. with the config of
+/// its parents applied). The first matching rule wins:
+///
+/// 1. `dynamic = "force-dynamic"` is dynamic.
+/// 2. `dynamic = "force-static"` and `dynamic = "error"` are static.
+/// 3. `revalidate = 0` is dynamic.
+/// 4. Importing a dynamic API (e.g. `next/headers`) is dynamic.
+/// 5. A dynamic segment (e.g. `[slug]`) without `generateStaticParams` is
+///    dynamic, as its params are only known at request time.
+/// 6. Everything else is static.
+pub fn classify_segment(segment: &str, config: &NextSegmentConfig) -> SegmentRendering {
+    match config.dynamic {
+        Some(NextSegmentDynamic::ForceDynamic) => return SegmentRendering::Dynamic,
+        Some(NextSegmentDynamic::ForceStatic | NextSegmentDynamic::Error) => {
+            return SegmentRendering::Static
+        }
+        Some(NextSegmentDynamic::Auto) | None => {}
+    }
+    if matches!(
+        config.revalidate,
+        Some(NextRevalidate::Frequency { seconds: 0 })
+    ) {
+        return SegmentRendering::Dynamic;
+    }
+    if config.uses_dynamic_apis {
+        return SegmentRendering::Dynamic;
+    }
+    if segment.starts_with('[') && !config.generate_static_params {
+        return SegmentRendering::Dynamic;
+    }
+    SegmentRendering::Static
+}
+
+/// Classifies every segment of the loader tree as static or dynamic. The
+/// page or default of a segment is considered part of that segment.
+#[turbo_tasks::function]
+pub async fn classify_segments_from_loader_tree(
+    loader_tree: Vc<LoaderTree>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<SegmentRenderingMap>> {
+    let mut result = IndexMap::new();
+    classify_loader_tree(
+        loader_tree,
+        context,
+        String::new(),
+        &NextSegmentConfig::default(),
+        &mut result,
+    )
+    .await?;
+    Ok(Vc::cell(result))
+}
+
+#[async_recursion]
+async fn classify_loader_tree(
+    loader_tree: Vc<LoaderTree>,
+    context: Vc<Box<dyn AssetContext>>,
+    path: String,
+    parent_config: &NextSegmentConfig,
+    result: &mut IndexMap<String, SegmentRendering>,
+) -> Result<()> {
+    let loader_tree = loader_tree.await?;
+    let path = if loader_tree.segment.is_empty() || loader_tree.segment.starts_with("__") {
+        path
+    } else {
+        format!("{path}/{}", loader_tree.segment)
+    };
+
+    let mut config = parse_segment_config_from_components(loader_tree.components, context).await?;
+    let mut children = Vec::new();
+    for (key, &tree) in &loader_tree.parallel_routes {
+        if key == "children" {
+            let child = tree.await?;
+            if child.segment.starts_with("__") {
+                // The page or default of this segment.
+                let mut page_config =
+                    parse_segment_config_from_components(child.components, context).await?;
+                page_config.apply_parent_config(&config);
+                config = page_config;
+                continue;
+            }
+            children.push((path.clone(), tree));
+        } else {
+            children.push((format!("{path}/@{key}"), tree));
+        }
+    }
+    // `generateStaticParams` only provides the params of the segment defining
+    // it, so it's not inherited.
+    let generate_static_params = config.generate_static_params;
+    config.apply_parent_config(parent_config);
+    config.generate_static_params = generate_static_params;
+
+    let key = if path.is_empty() {
+        "/".to_string()
+    } else {
+        path
+    };
+    result.insert(key, classify_segment(&loader_tree.segment, &config));
+
+    for (path, tree) in children {
+        classify_loader_tree(tree, context, path, &config, result).await?;
+    }
+    Ok(())
+}
+
+/// Emits a warning for each `loading` component which is never shown, as the
+/// segment defining it and all segments below it are static in every route
+/// (see [classify_segment]). Static segments are rendered ahead of time, so
+/// nothing suspends while they are served.
+///
+/// This is an optional lint, enabled with
+/// `experimental.lintUnusedLoadingBoundaries`.
+#[turbo_tasks::function]
+pub async fn lint_unused_loading_boundaries(
+    entrypoints: Vc<Entrypoints>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<Completion>> {
+    // Whether each loading component is above a dynamic segment in any route,
+    // keyed by its path.
+    let mut boundaries = IndexMap::new();
+    for entrypoint in entrypoints.await?.values() {
+        let Entrypoint::AppPage { loader_tree, .. } = *entrypoint else {
+            continue;
+        };
+        let mut route_boundaries = Vec::new();
+        collect_loading_boundaries(loader_tree, String::new(), &mut route_boundaries).await?;
+        if route_boundaries.is_empty() {
+            continue;
+        }
+        let segments = classify_segments_from_loader_tree(loader_tree, context).await?;
+        for (segment_path, loading) in route_boundaries {
+            let has_dynamic_segment = segments.iter().any(|(path, rendering)| {
+                *rendering == SegmentRendering::Dynamic
+                    && (segment_path == "/"
+                        || *path == segment_path
+                        || path.starts_with(&format!("{segment_path}/")))
+            });
+            let (_, _, is_used) = boundaries.entry(loading.await?.path.clone()).or_insert((
+                loading,
+                segment_path,
+                false,
+            ));
+            *is_used |= has_dynamic_segment;
+        }
+    }
+
+    for (loading, segment_path, is_used) in boundaries.into_values() {
+        if !is_used {
+            UnusedLoadingBoundaryIssue {
+                path: loading,
+                segment_path,
+            }
+            .cell()
+            .emit();
+        }
+    }
+    Ok(Completion::new())
+}
+
+/// Collects the `loading` components of the loader tree with the path of their
+/// segment, using the segment paths of [classify_segments_from_loader_tree].
+#[async_recursion]
+async fn collect_loading_boundaries(
+    loader_tree: Vc<LoaderTree>,
+    path: String,
+    result: &mut Vec<(String, Vc<FileSystemPath>)>,
+) -> Result<()> {
+    let loader_tree = loader_tree.await?;
+    let path = if loader_tree.segment.is_empty() || loader_tree.segment.starts_with("__") {
+        path
+    } else {
+        format!("{path}/{}", loader_tree.segment)
+    };
+    if let Some(loading) = loader_tree.components.await?.loading {
+        let key = if path.is_empty() {
+            "/".to_string()
+        } else {
+            path.clone()
+        };
+        result.push((key, loading));
+    }
+    for (key, &tree) in &loader_tree.parallel_routes {
+        let child_path = if key == "children" {
+            path.clone()
+        } else {
+            format!("{path}/@{key}")
+        };
+        collect_loading_boundaries(tree, child_path, result).await?;
+    }
+    Ok(())
+}
+
+#[turbo_tasks::value(shared)]
+struct UnusedLoadingBoundaryIssue {
+    path: Vc<FileSystemPath>,
+    segment_path: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for UnusedLoadingBoundaryIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("lint".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("Loading UI is never shown".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "{} and all segments below it are static, so they are rendered ahead of time and this \
+             loading UI can't be shown. Remove it, or make a segment below it dynamic.",
+            self.segment_path
+        ))
+    }
+}
+
+/// The effective `revalidate` of a route, if any of its segments sets one.
+#[turbo_tasks::value(transparent)]
+pub struct OptionNextRevalidate(Option<NextRevalidate>);
+
+/// Combines the `revalidate` of two segments of the same route. A route has
+/// to be revalidated as soon as any of its segments has to, so the lowest
+/// frequency wins over `false` and `"force-cache"`.
+pub fn lowest_revalidate(
+    a: Option<NextRevalidate>,
+    b: Option<NextRevalidate>,
+) -> Option<NextRevalidate> {
+    match (a, b) {
+        (
+            Some(NextRevalidate::Frequency { seconds: a }),
+            Some(NextRevalidate::Frequency { seconds: b }),
+        ) => Some(NextRevalidate::Frequency { seconds: a.min(b) }),
+        (Some(frequency @ NextRevalidate::Frequency { .. }), _)
+        | (_, Some(frequency @ NextRevalidate::Frequency { .. })) => Some(frequency),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Returns the effective `revalidate` of the route with the given loader
+/// tree, combining the configs of all its segments (see [lowest_revalidate]).
+/// A segment with `dynamic = "force-dynamic"` counts as `revalidate = 0`.
+#[turbo_tasks::function]
+pub async fn get_route_revalidate(
+    loader_tree: Vc<LoaderTree>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<OptionNextRevalidate>> {
+    let mut revalidate = None;
+    collect_route_revalidate(loader_tree, context, &mut revalidate).await?;
+    Ok(Vc::cell(revalidate))
+}
+
+#[async_recursion]
+async fn collect_route_revalidate(
+    loader_tree: Vc<LoaderTree>,
+    context: Vc<Box<dyn AssetContext>>,
+    revalidate: &mut Option<NextRevalidate>,
+) -> Result<()> {
+    let loader_tree = loader_tree.await?;
+    let config = parse_segment_config_from_components(loader_tree.components, context).await?;
+    let segment_revalidate = match config.dynamic {
+        Some(NextSegmentDynamic::ForceDynamic) => Some(NextRevalidate::Frequency { seconds: 0 }),
+        _ => config.revalidate,
+    };
+    *revalidate = lowest_revalidate(*revalidate, segment_revalidate);
+    for &tree in loader_tree.parallel_routes.values() {
+        collect_route_revalidate(tree, context, revalidate).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use swc_core::{
+        common::{sync::Lrc, FileName, SourceMap},
+        ecma::{
+            ast::Module,
+            parser::{Parser, StringInput, Syntax},
+        },
+    };
+    use turbo_tasks::{Value, Vc};
+    use turbopack_binding::turbopack::{
+        core::{
+            context::AssetContext, environment::ServerAddr, file_source::FileSource,
+            reference_type::ReferenceType,
+        },
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::{
+        classify_segment, classify_segments_from_loader_tree, detect_dynamic_api_usage,
+        detect_special_exports, get_app_route_runtime, get_route_revalidate,
+        lint_unused_loading_boundaries, lowest_revalidate, parse_segment_config_from_source,
+        NextRevalidate, NextSegmentConfig, NextSegmentDynamic, SegmentRendering,
+    };
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions, Entrypoint},
+        next_config::{ExperimentalConfig, NextConfig},
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{issues_of, Fixture},
+        util::NextRuntime,
+    };
+
+    fn parse(code: &str) -> Module {
+        let cm = Lrc::<SourceMap>::default();
+        let fm = cm.new_source_file(FileName::Anon, code.to_string());
+        let mut parser = Parser::new(
+            Syntax::Es(Default::default()),
+            StringInput::from(&*fm),
+            None,
+        );
+        parser.parse_module().unwrap()
+    }
+
+    fn detect(code: &str) -> NextSegmentConfig {
+        let mut config = NextSegmentConfig::default();
+        let module = parse(code);
+        detect_special_exports(&mut config, &module.body);
+        detect_dynamic_api_usage(&mut config, &module.body);
+        config
+    }
+
+    #[test]
+    fn test_generate_metadata_function() {
+        let config = detect("export async function generateMetadata() { return {} }");
+        assert!(config.generate_metadata);
+        assert!(!config.generate_viewport);
+    }
+
+    #[test]
+    fn test_generate_viewport_const() {
+        let config = detect("export const generateViewport = () => ({})");
+        assert!(!config.generate_metadata);
+        assert!(config.generate_viewport);
+    }
+
+    #[test]
+    fn test_generate_static_params_const() {
+        assert!(detect("export const generateStaticParams = () => []").generate_static_params);
+    }
+
+    #[test]
+    fn test_named_exports() {
+        assert!(
+            detect("function a() {}\nexport { a as generateStaticParams }").generate_static_params
+        );
+        assert!(
+            detect("function generateStaticParams() {}\nexport { generateStaticParams }")
+                .generate_static_params
+        );
+    }
+
+    #[test]
+    fn test_re_exports() {
+        assert!(detect("export { generateStaticParams } from './params'").generate_static_params);
+        assert!(
+            detect("export { params as generateStaticParams } from './params'")
+                .generate_static_params
+        );
+    }
+
+    #[test]
+    fn test_no_generators() {
+        let config = detect(
+            "export const metadata = {}\nexport { generateStaticParams as params } from \
+             './params'\nfunction generateStaticParams() {}\nfunction generateMetadata() {}",
+        );
+        assert!(!config.generate_metadata);
+        assert!(!config.generate_viewport);
+        assert!(!config.generate_static_params);
+    }
+
+    #[test]
+    fn test_generate_static_params() {
+        let config = detect("export async function generateStaticParams() { return [] }");
+        assert!(config.generate_static_params);
+        assert!(!config.uses_dynamic_apis);
+    }
+
+    #[test]
+    fn test_dynamic_api_usage() {
+        assert!(detect("import { cookies } from 'next/headers'").uses_dynamic_apis);
+        assert!(!detect("import { Suspense } from 'react'").uses_dynamic_apis);
+    }
+
+    #[test]
+    fn test_classify_segments_of_route() {
+        // app/layout.tsx
+        let root = NextSegmentConfig::default();
+        // app/shop/layout.tsx: `export const revalidate = 60`
+        let mut shop = NextSegmentConfig {
+            revalidate: Some(NextRevalidate::Frequency { seconds: 60 }),
+            ..Default::default()
+        };
+        shop.apply_parent_config(&root);
+        // app/shop/[category]/page.tsx: `export function generateStaticParams()`
+        let mut category = NextSegmentConfig {
+            generate_static_params: true,
+            ..Default::default()
+        };
+        category.apply_parent_config(&shop);
+        // app/shop/[category]/[item]/page.tsx
+        let mut item = NextSegmentConfig::default();
+        item.apply_parent_config(&category);
+        item.generate_static_params = false;
+        // app/shop/[category]/[item]/reviews/page.tsx: `import { cookies } from
+        // 'next/headers'`
+        let mut reviews = NextSegmentConfig {
+            uses_dynamic_apis: true,
+            ..Default::default()
+        };
+        reviews.apply_parent_config(&item);
+
+        assert_eq!(classify_segment("", &root), SegmentRendering::Static);
+        assert_eq!(classify_segment("shop", &shop), SegmentRendering::Static);
+        assert_eq!(
+            classify_segment("[category]", &category),
+            SegmentRendering::Static
+        );
+        assert_eq!(classify_segment("[item]", &item), SegmentRendering::Dynamic);
+        assert_eq!(
+            classify_segment("reviews", &reviews),
+            SegmentRendering::Dynamic
+        );
+    }
+
+    #[test]
+    fn test_classify_segment_config_overrides() {
+        let force_static = NextSegmentConfig {
+            dynamic: Some(NextSegmentDynamic::ForceStatic),
+            uses_dynamic_apis: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            classify_segment("[slug]", &force_static),
+            SegmentRendering::Static
+        );
+
+        let force_dynamic = NextSegmentConfig {
+            dynamic: Some(NextSegmentDynamic::ForceDynamic),
+            ..Default::default()
+        };
+        assert_eq!(
+            classify_segment("about", &force_dynamic),
+            SegmentRendering::Dynamic
+        );
+
+        let no_cache = NextSegmentConfig {
+            revalidate: Some(NextRevalidate::Frequency { seconds: 0 }),
+            ..Default::default()
+        };
+        assert_eq!(
+            classify_segment("about", &no_cache),
+            SegmentRendering::Dynamic
+        );
+    }
+
+    #[test]
+    fn test_lowest_revalidate() {
+        let frequency = |seconds| Some(NextRevalidate::Frequency { seconds });
+        assert_eq!(
+            lowest_revalidate(frequency(60), frequency(10)),
+            frequency(10)
+        );
+        assert_eq!(
+            lowest_revalidate(Some(NextRevalidate::Never), frequency(60)),
+            frequency(60)
+        );
+        assert_eq!(
+            lowest_revalidate(None, Some(NextRevalidate::Never)),
+            Some(NextRevalidate::Never)
+        );
+        assert_eq!(lowest_revalidate(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_parse_max_duration() -> Result<()> {
+        Fixture::new([
+            ("integer.js", "export const maxDuration = 30\n"),
+            ("fraction.js", "export const maxDuration = 10.5\n"),
+            ("infinite.js", "export const maxDuration = 1 / 0\n"),
+            ("zero.js", "export const maxDuration = 0\n"),
+            ("negative.js", "export const maxDuration = -1\n"),
+            ("too-large.js", "export const maxDuration = 4294967296\n"),
+        ])?
+        .run(|app_dir| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let config = |file: &str| {
+                let source = Vc::upcast(FileSource::new(app_dir.join(file.to_string())));
+                parse_segment_config_from_source(
+                    context.process(source, Value::new(ReferenceType::Undefined)),
+                    source,
+                )
+            };
+
+            let integer = config("integer.js");
+            assert_eq!(integer.await?.max_duration, Some(30));
+            assert!(issues_of(integer).await?.is_empty());
+
+            for file in [
+                "fraction.js",
+                "infinite.js",
+                "zero.js",
+                "negative.js",
+                "too-large.js",
+            ] {
+                let invalid = config(file);
+                assert_eq!(invalid.await?.max_duration, None, "{file}");
+                let issues = issues_of(invalid).await?;
+                assert_eq!(issues.len(), 1, "{file}");
+                assert!(
+                    issues[0]
+                        .detail
+                        .contains("`maxDuration` needs to be a static positive integer"),
+                    "{file}"
+                );
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_get_app_route_runtime() -> Result<()> {
+        Fixture::new([
+            ("edge/route.js", "export const runtime = 'edge'\n"),
+            ("node/route.js", "export const runtime = 'nodejs'\n"),
+            ("default/route.js", "export function GET() {}\n"),
+        ])?
+        .run(|app_dir| async move {
+            let context = Vc::upcast(ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            ));
+            let node_default = NextConfig::default().cell();
+            let edge_default = NextConfig {
+                experimental: ExperimentalConfig {
+                    runtime: Some(serde_json::json!("edge")),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .cell();
+            let route = |dir: &str| app_dir.join(format!("{dir}/route.js"));
+
+            assert_eq!(
+                get_app_route_runtime(route("edge"), context, node_default).await?,
+                NextRuntime::Edge
+            );
+            assert_eq!(
+                get_app_route_runtime(route("node"), context, edge_default).await?,
+                NextRuntime::NodeJs
+            );
+            assert_eq!(
+                get_app_route_runtime(route("default"), context, node_default).await?,
+                NextRuntime::NodeJs
+            );
+            assert_eq!(
+                get_app_route_runtime(route("default"), context, edge_default).await?,
+                NextRuntime::Edge
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_route_revalidate() -> Result<()> {
+        Fixture::new([
+            (
+                "layout.js",
+                "export const revalidate = 3600\nexport default function Layout() {}\n",
+            ),
+            (
+                "blog/page.js",
+                "export const revalidate = 60\nexport default function Page() {}\n",
+            ),
+            (
+                "about/page.js",
+                "export const dynamic = 'force-dynamic'\nexport default function Page() {}\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let context = Vc::upcast(ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            ));
+            let entrypoints = get_entrypoints(
+                app_dir,
+                Vc::cell(vec!["js".to_string()]),
+                AppStructureOptions::default().cell(),
+            )
+            .await?;
+            let revalidate = |pathname: &str| {
+                let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get(pathname)
+                else {
+                    panic!("expected a page at {pathname}");
+                };
+                get_route_revalidate(loader_tree, context)
+            };
+
+            assert_eq!(
+                *revalidate("/blog").await?,
+                Some(NextRevalidate::Frequency { seconds: 60 })
+            );
+            assert_eq!(
+                *revalidate("/about").await?,
+                Some(NextRevalidate::Frequency { seconds: 0 })
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_classify_segments_from_loader_tree() -> Result<()> {
+        Fixture::new([
+            ("layout.js", "export default function Layout() {}\n"),
+            (
+                "shop/layout.js",
+                "export const revalidate = 60\nexport default function Layout() {}\n",
+            ),
+            (
+                "shop/[category]/layout.js",
+                "export async function generateStaticParams() { return [] }\nexport default \
+                 function Layout() {}\n",
+            ),
+            (
+                "shop/[category]/[id]/page.js",
+                "export default function Page() {}\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let context = Vc::upcast(ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            ));
+            let entrypoints = get_entrypoints(
+                app_dir,
+                Vc::cell(vec!["js".to_string()]),
+                AppStructureOptions::default().cell(),
+            )
+            .await?;
+            let Some(&Entrypoint::AppPage { loader_tree, .. }) =
+                entrypoints.get("/shop/[category]/[id]")
+            else {
+                panic!("expected a page at /shop/[category]/[id]");
+            };
+            let segments = classify_segments_from_loader_tree(loader_tree, context).await?;
+
+            assert_eq!(segments.get("/"), Some(&SegmentRendering::Static));
+            // `revalidate = 60` still allows prerendering.
+            assert_eq!(segments.get("/shop"), Some(&SegmentRendering::Static));
+            // Its params are known at build time.
+            assert_eq!(
+                segments.get("/shop/[category]"),
+                Some(&SegmentRendering::Static)
+            );
+            // `generateStaticParams` isn't inherited, so `[id]` is only known
+            // at request time.
+            assert_eq!(
+                segments.get("/shop/[category]/[id]"),
+                Some(&SegmentRendering::Dynamic)
+            );
+            assert_eq!(segments.len(), 4);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_unused_loading_boundaries() -> Result<()> {
+        Fixture::new([
+            ("layout.js", "export default function Layout() {}\n"),
+            ("blog/loading.js", "export default function Loading() {}\n"),
+            ("blog/page.js", "export default function Page() {}\n"),
+            ("feed/loading.js", "export default function Loading() {}\n"),
+            (
+                "feed/page.js",
+                "export const dynamic = 'force-dynamic'\nexport default function Page() {}\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let context = Vc::upcast(ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            ));
+            let entrypoints = get_entrypoints(
+                app_dir,
+                Vc::cell(vec!["js".to_string()]),
+                AppStructureOptions::default().cell(),
+            );
+            let issues = issues_of(lint_unused_loading_boundaries(entrypoints, context)).await?;
+
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].title, "Loading UI is never shown");
+            assert!(issues[0].file_path.ends_with("blog/loading.js"));
+
+            Ok(())
+        })
+        .await
+    }
 }
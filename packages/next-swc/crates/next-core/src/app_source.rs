@@ -54,10 +54,13 @@ use turbopack_binding::{
 
 use crate::{
     app_render::next_server_component_transition::NextServerComponentTransition,
-    app_segment_config::{parse_segment_config_from_loader_tree, parse_segment_config_from_source},
+    app_segment_config::{
+        get_route_revalidate, lint_unused_loading_boundaries,
+        parse_segment_config_from_loader_tree, parse_segment_config_from_source,
+    },
     app_structure::{
-        get_entrypoints, get_global_metadata, Entrypoint, GlobalMetadata, LoaderTree, MetadataItem,
-        OptionAppDir,
+        get_entrypoints, get_global_metadata, AppStructureOptions, Entrypoint, GlobalMetadata,
+        LoaderTree, MetadataItem, OptionAppDir,
     },
     bootstrap::{route_bootstrap, BootstrapConfig},
     embed_js::{next_asset, next_js_file_path},
@@ -92,7 +95,7 @@ use crate::{
         },
         route_transition::NextRouteTransition,
     },
-    util::{render_data, NextRuntime},
+    util::{render_data, route_render_data, NextRuntime},
 };
 
 fn pathname_to_segments(pathname: &str) -> Result<(Vec<BaseSegment>, RouteType)> {
@@ -601,8 +604,16 @@ pub async fn create_app_source(
     let Some(app_dir) = *app_dir.await? else {
         return Ok(Vc::upcast(NoContentSource::new()));
     };
-    let entrypoints = get_entrypoints(app_dir, next_config.page_extensions());
-    let metadata = get_global_metadata(app_dir, next_config.page_extensions());
+    let entrypoints = get_entrypoints(
+        app_dir,
+        next_config.resolved_page_extensions(),
+        AppStructureOptions::from_next_config(next_config, project_path),
+    );
+    let metadata = get_global_metadata(
+        app_dir,
+        next_config.resolved_page_extensions(),
+        AppStructureOptions::from_next_config(next_config, project_path),
+    );
 
     let context_ssr = app_context(
         project_path,
@@ -651,6 +662,15 @@ pub async fn create_app_source(
     );
     let render_data = render_data(next_config, server_addr);
 
+    if next_config
+        .await?
+        .experimental
+        .lint_unused_loading_boundaries
+        .unwrap_or(false)
+    {
+        lint_unused_loading_boundaries(entrypoints, Vc::upcast(context)).await?;
+    }
+
     let entrypoints = entrypoints.await?;
     let mut sources: Vec<_> = entrypoints
         .iter()
@@ -695,10 +715,18 @@ pub async fn create_app_source(
         )))
         .collect();
 
+    // With multiple root layouts there might be no top-level not-found page, in
+    // which case the first not-found page of a route group is used instead.
+    let not_found_entrypoint = entrypoints.get("/_not-found").or_else(|| {
+        entrypoints
+            .iter()
+            .find(|(pathname, _)| pathname.ends_with("/_not-found"))
+            .map(|(_, entrypoint)| entrypoint)
+    });
     if let Some(&Entrypoint::AppPage {
         original_name: _,
         loader_tree,
-    }) = entrypoints.get("/_not-found")
+    }) = not_found_entrypoint
     {
         if loader_tree.await?.components.await?.not_found.is_some() {
             // Only add a source for the app 404 page if a top-level not-found page is
@@ -788,6 +816,11 @@ async fn create_app_page_source_for_route(
 
     let (base_segments, route_type) = pathname_to_segments(&pathname)?;
 
+    let render_data = route_render_data(
+        render_data,
+        get_route_revalidate(loader_tree, Vc::upcast(context)),
+    );
+
     let source = create_node_rendered_source(
         project_path,
         env,
@@ -1,17 +1,34 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anyhow::{bail, Result};
+use async_recursion::async_recursion;
+use futures::try_join;
 use indexmap::{indexmap, map::Entry, IndexMap};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    debug::ValueDebugFormat, trace::TraceRawVcs, Completion, Completions, TaskInput, ValueToString,
-    Vc,
+    debug::ValueDebugFormat, trace::TraceRawVcs, Completion, Completions, TaskInput,
+    TryJoinIterExt, ValueToString, Vc,
 };
 use turbopack_binding::{
-    turbo::tasks_fs::{DirectoryContent, DirectoryEntry, FileSystemEntryType, FileSystemPath},
-    turbopack::core::issue::{Issue, IssueExt, IssueSeverity},
+    turbo::{
+        tasks_fs::{
+            glob::Glob, json::parse_json_rope_with_source_context, DirectoryContent,
+            DirectoryEntry, FileContent, FileSystemEntryType, FileSystemPath, LinkContent,
+            LinkType,
+        },
+        tasks_hash::hash_xxh3_hash64,
+    },
+    turbopack::{
+        core::{
+            asset::{Asset, AssetContent},
+            file_source::FileSource,
+            issue::{Issue, IssueExt, IssueSeverity},
+            source::Source,
+        },
+        image::process::get_meta_data,
+    },
 };
 
 use crate::{next_config::NextConfig, next_import_map::get_next_package};
@@ -32,6 +49,10 @@ pub struct Components {
     pub template: Option<Vc<FileSystemPath>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub not_found: Option<Vc<FileSystemPath>>,
+    /// The `global-error` boundary, which replaces the root layout when it
+    /// throws. Only used at the root of the app directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_error: Option<Vc<FileSystemPath>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<Vc<FileSystemPath>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -49,6 +70,7 @@ impl Components {
             loading: self.loading,
             template: self.template,
             not_found: self.not_found,
+            global_error: self.global_error,
             default: None,
             route: None,
             metadata: self.metadata.clone(),
@@ -63,6 +85,7 @@ impl Components {
             loading: a.loading.or(b.loading),
             template: a.template.or(b.template),
             not_found: a.not_found.or(b.not_found),
+            global_error: a.global_error.or(b.global_error),
             default: a.default.or(b.default),
             route: a.route.or(b.route),
             metadata: Metadata::merge(&a.metadata, &b.metadata),
@@ -111,6 +134,9 @@ pub struct Metadata {
     pub twitter: Vec<MetadataWithAltItem>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub open_graph: Vec<MetadataWithAltItem>,
+    /// A favicon in a route group takes precedence over the favicons of its
+    /// parents for all routes inside that group. The `/favicon.ico` route
+    /// itself is always served from the root favicon.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub favicon: Vec<MetadataWithAltItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -135,21 +161,62 @@ impl Metadata {
             && manifest.is_none()
     }
 
+    /// Merges two sets of metadata of the same segment. Items present in both
+    /// are only kept once, which happens when the loader trees of two routes
+    /// inside the same route group (e.g. `app/(marketing)/icon.png`) are
+    /// merged at the group level.
     fn merge(a: &Self, b: &Self) -> Self {
+        fn merge_items(
+            a: &[MetadataWithAltItem],
+            b: &[MetadataWithAltItem],
+        ) -> Vec<MetadataWithAltItem> {
+            let mut items = a.to_vec();
+            for item in b {
+                if !items.contains(item) {
+                    items.push(*item);
+                }
+            }
+            items
+        }
+
         Self {
-            icon: a.icon.iter().chain(b.icon.iter()).copied().collect(),
-            apple: a.apple.iter().chain(b.apple.iter()).copied().collect(),
-            twitter: a.twitter.iter().chain(b.twitter.iter()).copied().collect(),
-            open_graph: a
-                .open_graph
-                .iter()
-                .chain(b.open_graph.iter())
-                .copied()
-                .collect(),
-            favicon: a.favicon.iter().chain(b.favicon.iter()).copied().collect(),
+            icon: merge_items(&a.icon, &b.icon),
+            apple: merge_items(&a.apple, &b.apple),
+            twitter: merge_items(&a.twitter, &b.twitter),
+            open_graph: merge_items(&a.open_graph, &b.open_graph),
+            favicon: merge_items(&a.favicon, &b.favicon),
             manifest: a.manifest.or(b.manifest),
         }
     }
+
+    /// Returns the items of these default metadata for the kinds of metadata
+    /// which `metadata` doesn't define, so that merging them (see
+    /// [Metadata::merge]) never overrides an item of `metadata`.
+    fn defaults_for(&self, metadata: &Self) -> Self {
+        fn default_items(
+            defaults: &[MetadataWithAltItem],
+            items: &[MetadataWithAltItem],
+        ) -> Vec<MetadataWithAltItem> {
+            if items.is_empty() {
+                defaults.to_vec()
+            } else {
+                Vec::new()
+            }
+        }
+
+        Self {
+            icon: default_items(&self.icon, &metadata.icon),
+            apple: default_items(&self.apple, &metadata.apple),
+            twitter: default_items(&self.twitter, &metadata.twitter),
+            open_graph: default_items(&self.open_graph, &metadata.open_graph),
+            favicon: default_items(&self.favicon, &metadata.favicon),
+            manifest: if metadata.manifest.is_none() {
+                self.manifest
+            } else {
+                None
+            },
+        }
+    }
 }
 
 /// Metadata files that can be placed in the root of the app directory.
@@ -162,6 +229,12 @@ pub struct GlobalMetadata {
     pub robots: Option<MetadataItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sitemap: Option<MetadataItem>,
+    /// The file browsers use as the icon of the site. Next.js links the
+    /// `favicon` with `sizes="any"` before the root `icon` files, so browsers
+    /// prefer the first root `icon` when there is one, and the `favicon` is
+    /// only the fallback served at `/favicon.ico`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_icon: Option<MetadataItem>,
 }
 
 impl GlobalMetadata {
@@ -170,8 +243,361 @@ impl GlobalMetadata {
             favicon,
             robots,
             sitemap,
+            site_icon,
         } = self;
-        favicon.is_none() && robots.is_none() && sitemap.is_none()
+        favicon.is_none() && robots.is_none() && sitemap.is_none() && site_icon.is_none()
+    }
+}
+
+/// Options controlling how the app directory is scanned.
+#[turbo_tasks::value(shared)]
+#[derive(Default, Clone, Debug)]
+pub struct AppStructureOptions {
+    /// Glob patterns matched against directory names (e.g. `__tests__`).
+    /// Matching directories are skipped, in addition to directories starting
+    /// with an underscore.
+    pub ignored_directories: Vec<String>,
+    /// Severity overrides for app structure issues. Issues without an
+    /// override use [AppStructureIssue::default_severity].
+    pub issue_severity_overrides: IndexMap<AppStructureIssue, IssueSeverity>,
+    /// Additional file extensions for static metadata files, keyed by
+    /// metadata type (e.g. `apple-icon`). Only extensions accepted by
+    /// [is_supported_metadata_extension] are used.
+    pub metadata_extensions: IndexMap<String, Vec<String>>,
+    /// Whether to warn about static `opengraph-image` files which are smaller
+    /// than recommended by social platforms.
+    pub validate_metadata_images: bool,
+    /// Whether to warn about static metadata files larger than
+    /// [AppStructureOptions::max_metadata_file_size].
+    pub validate_metadata_file_size: bool,
+    /// Size in bytes above which static metadata files are reported. Defaults
+    /// to [DEFAULT_MAX_METADATA_FILE_SIZE].
+    pub max_metadata_file_size: Option<u64>,
+    /// Whether to report static `manifest.json` and `manifest.webmanifest`
+    /// files which aren't valid JSON. Manifests which don't look like a web
+    /// app manifest (see [AppStructureIssue::InvalidManifestSchema]) are
+    /// reported as well.
+    pub validate_metadata_manifest: bool,
+    /// Maximum directory depth below the app directory. Deeper directories
+    /// are not scanned. Defaults to [DEFAULT_MAX_DIRECTORY_DEPTH].
+    pub max_depth: Option<u32>,
+    /// Skips looking for metadata files (e.g. `icon.png` or `robots.txt`), for
+    /// apps which define all metadata in code.
+    pub disable_metadata_files: bool,
+    /// Page module used for leaf segments which have a layout but no page,
+    /// e.g. to redirect to an index route. Such segments are not routable
+    /// when this isn't set. This can't be configured in `next.config.js` and
+    /// is meant for frameworks building on top of Next.js.
+    pub default_page: Option<Vc<FileSystemPath>>,
+    /// Module rendered for parallel route slots without a `default`, instead
+    /// of the one of the `next` package. Like
+    /// [AppStructureOptions::default_page], this is meant for tools
+    /// embedding Next.js with a custom package layout. The package default is
+    /// used when this file doesn't exist.
+    pub parallel_route_default: Option<Vc<FileSystemPath>>,
+    /// Directory checked for a `favicon.ico` when the app directory has
+    /// neither a favicon nor an icon. Defaults to the `public` directory of
+    /// the project containing the app directory.
+    pub public_dir: Option<Vc<FileSystemPath>>,
+    /// Maximum number of subdirectories of a directory which are scanned in
+    /// parallel. With a limit, subdirectories are scanned in batches, each
+    /// one only starting once the previous batch has been fully scanned. All
+    /// subdirectories are scanned in parallel by default.
+    pub scan_concurrency: Option<u32>,
+    /// Forces case-sensitive keying of routes. Routes are always keyed by
+    /// their exact pathname, but by default a warning is emitted for routes
+    /// which only differ in case (e.g. `/about` and `/About`), as only one of
+    /// them can be checked out on case-insensitive filesystems (e.g. on macOS
+    /// or Windows). Setting this skips that detection, so tests can assert the
+    /// exact routes and issues of a fixture regardless of the host. This
+    /// can't be configured in `next.config.js`.
+    pub force_case_sensitive_routes: bool,
+    /// Directory with metadata files (e.g. `opengraph-image.png`) applied to
+    /// all routes. They follow the same conventions as metadata files in the
+    /// app directory and have the lowest precedence: they are merged into the
+    /// root segment for each kind of metadata the root doesn't define itself,
+    /// and nested segments override them as usual. Configured by
+    /// `experimental.defaultMetadataDir`, relative to the project directory.
+    pub default_metadata_dir: Option<Vc<FileSystemPath>>,
+    /// Scans directories starting with an underscore (e.g. `_components`),
+    /// which are private folders skipped by default. `%5F` at the start of a
+    /// directory name is always normalized to an underscore, so with this set,
+    /// `_blog` and `%5Fblog` are the same segment and their routes are merged.
+    pub scan_underscore_directories: bool,
+}
+
+/// Default for [AppStructureOptions::max_depth]. This is far deeper than any
+/// reasonable route, but stops scanning symlink loops and similar.
+pub const DEFAULT_MAX_DIRECTORY_DEPTH: u32 = 128;
+
+/// Default for [AppStructureOptions::max_metadata_file_size]. Images this
+/// large are rarely intended, as they are downloaded by every crawler.
+pub const DEFAULT_MAX_METADATA_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// The issues reported while scanning the app directory. Their severity can
+/// be overridden with `experimental.appDirIssueSeverity`, keyed by
+/// [AppStructureIssue::name].
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs, ValueDebugFormat,
+)]
+pub enum AppStructureIssue {
+    /// The configured default metadata directory doesn't exist.
+    MissingDefaultMetadataDir,
+    /// A symlink points to itself or to a directory containing it.
+    SymlinkCycle,
+    /// The app directory is nested deeper than
+    /// [AppStructureOptions::max_depth].
+    MaxDepthExceeded,
+    /// Two directories resolve to the same parallel route slot.
+    DuplicateParallelRouteSlot,
+    /// A slot is nested directly inside another slot.
+    NestedParallelRouteSlot,
+    /// A parallel route default resolves to the same path as a page.
+    ConflictingParallelRouteDefault,
+    /// The configured parallel route default doesn't exist.
+    MissingParallelRouteDefault,
+    /// Two pages or route handlers resolve to the same path.
+    ConflictingRoutes,
+    /// Two routes only differ in case.
+    CaseConflictingRoutes,
+    /// A dynamic segment name is used twice within a route.
+    DuplicateDynamicParam,
+    /// A page is only reachable at `/index` because of a literal `index`
+    /// segment.
+    LiteralIndexSegment,
+    /// Pages without a root layout.
+    MissingRootLayout,
+    /// Pages which are only rendered in parallel route slots.
+    UnreachablePage,
+    /// Intercepting routes which can't be resolved.
+    InvalidInterceptionRoute,
+    /// Several route groups define a root not-found page.
+    DuplicateRootNotFound,
+    /// `.alt.txt` files without a matching image.
+    OrphanedAltText,
+    /// `opengraph-image` files below the recommended size, or which can't be
+    /// decoded.
+    UndersizedOpenGraphImage,
+    /// Static metadata files above
+    /// [AppStructureOptions::max_metadata_file_size].
+    OversizedMetadataFile,
+    /// Static manifests which aren't valid JSON.
+    InvalidManifest,
+    /// Static manifests which are valid JSON, but not an object with a `name`
+    /// or `short_name` and an `icons` array (if any). This is only a hint by
+    /// default, as browsers ignore what they don't understand.
+    InvalidManifestSchema,
+    /// Configured metadata extensions which aren't supported.
+    UnsupportedMetadataExtension,
+}
+
+impl AppStructureIssue {
+    pub const ALL: &'static [AppStructureIssue] = &[
+        AppStructureIssue::MissingDefaultMetadataDir,
+        AppStructureIssue::SymlinkCycle,
+        AppStructureIssue::MaxDepthExceeded,
+        AppStructureIssue::DuplicateParallelRouteSlot,
+        AppStructureIssue::NestedParallelRouteSlot,
+        AppStructureIssue::ConflictingParallelRouteDefault,
+        AppStructureIssue::MissingParallelRouteDefault,
+        AppStructureIssue::ConflictingRoutes,
+        AppStructureIssue::CaseConflictingRoutes,
+        AppStructureIssue::DuplicateDynamicParam,
+        AppStructureIssue::LiteralIndexSegment,
+        AppStructureIssue::MissingRootLayout,
+        AppStructureIssue::UnreachablePage,
+        AppStructureIssue::InvalidInterceptionRoute,
+        AppStructureIssue::DuplicateRootNotFound,
+        AppStructureIssue::OrphanedAltText,
+        AppStructureIssue::UndersizedOpenGraphImage,
+        AppStructureIssue::OversizedMetadataFile,
+        AppStructureIssue::InvalidManifest,
+        AppStructureIssue::InvalidManifestSchema,
+        AppStructureIssue::UnsupportedMetadataExtension,
+    ];
+
+    /// The name of the issue in `experimental.appDirIssueSeverity`.
+    pub fn name(self) -> &'static str {
+        match self {
+            AppStructureIssue::MissingDefaultMetadataDir => "missing-default-metadata-dir",
+            AppStructureIssue::SymlinkCycle => "symlink-cycle",
+            AppStructureIssue::MaxDepthExceeded => "max-depth-exceeded",
+            AppStructureIssue::DuplicateParallelRouteSlot => "duplicate-parallel-route-slot",
+            AppStructureIssue::NestedParallelRouteSlot => "nested-parallel-route-slot",
+            AppStructureIssue::ConflictingParallelRouteDefault => {
+                "conflicting-parallel-route-default"
+            }
+            AppStructureIssue::MissingParallelRouteDefault => "missing-parallel-route-default",
+            AppStructureIssue::ConflictingRoutes => "conflicting-routes",
+            AppStructureIssue::CaseConflictingRoutes => "case-conflicting-routes",
+            AppStructureIssue::DuplicateDynamicParam => "duplicate-dynamic-param",
+            AppStructureIssue::LiteralIndexSegment => "literal-index-segment",
+            AppStructureIssue::MissingRootLayout => "missing-root-layout",
+            AppStructureIssue::UnreachablePage => "unreachable-page",
+            AppStructureIssue::InvalidInterceptionRoute => "invalid-interception-route",
+            AppStructureIssue::DuplicateRootNotFound => "duplicate-root-not-found",
+            AppStructureIssue::OrphanedAltText => "orphaned-alt-text",
+            AppStructureIssue::UndersizedOpenGraphImage => "undersized-opengraph-image",
+            AppStructureIssue::OversizedMetadataFile => "oversized-metadata-file",
+            AppStructureIssue::InvalidManifest => "invalid-manifest",
+            AppStructureIssue::InvalidManifestSchema => "invalid-manifest-schema",
+            AppStructureIssue::UnsupportedMetadataExtension => "unsupported-metadata-extension",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|issue| issue.name() == name)
+    }
+
+    /// The severity of the issue when it isn't overridden.
+    pub fn default_severity(self) -> IssueSeverity {
+        match self {
+            AppStructureIssue::CaseConflictingRoutes
+            | AppStructureIssue::LiteralIndexSegment
+            | AppStructureIssue::UnreachablePage
+            | AppStructureIssue::OrphanedAltText
+            | AppStructureIssue::UndersizedOpenGraphImage
+            | AppStructureIssue::OversizedMetadataFile
+            | AppStructureIssue::UnsupportedMetadataExtension => IssueSeverity::Warning,
+            AppStructureIssue::InvalidManifestSchema => IssueSeverity::Hint,
+            _ => IssueSeverity::Error,
+        }
+    }
+}
+
+/// Parses a severity of `experimental.appDirIssueSeverity`.
+fn parse_issue_severity(severity: &str) -> Option<IssueSeverity> {
+    match severity {
+        "error" => Some(IssueSeverity::Error),
+        "warning" => Some(IssueSeverity::Warning),
+        "hint" => Some(IssueSeverity::Hint),
+        "info" => Some(IssueSeverity::Info),
+        _ => None,
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AppStructureOptions {
+    #[turbo_tasks::function]
+    pub async fn from_next_config(
+        next_config: Vc<NextConfig>,
+        project_path: Vc<FileSystemPath>,
+    ) -> Result<Vc<Self>> {
+        Ok(AppStructureOptions {
+            ignored_directories: next_config.app_dir_ignore_patterns().await?.clone_value(),
+            issue_severity_overrides: parse_issue_severity_overrides(next_config, project_path)
+                .await?,
+            metadata_extensions: next_config
+                .await?
+                .experimental
+                .metadata_extensions
+                .clone()
+                .unwrap_or_default(),
+            validate_metadata_images: next_config
+                .await?
+                .experimental
+                .validate_metadata_images
+                .unwrap_or(false),
+            validate_metadata_file_size: next_config
+                .await?
+                .experimental
+                .validate_metadata_file_size
+                .unwrap_or(false),
+            max_metadata_file_size: next_config.await?.experimental.max_metadata_file_size,
+            validate_metadata_manifest: next_config
+                .await?
+                .experimental
+                .validate_metadata_manifest
+                .unwrap_or(false),
+            max_depth: next_config.await?.experimental.app_dir_max_depth,
+            disable_metadata_files: next_config
+                .await?
+                .experimental
+                .disable_metadata_files
+                .unwrap_or(false),
+            default_page: None,
+            parallel_route_default: None,
+            public_dir: None,
+            scan_concurrency: next_config.await?.experimental.app_dir_scan_concurrency,
+            force_case_sensitive_routes: false,
+            default_metadata_dir: next_config
+                .await?
+                .experimental
+                .default_metadata_dir
+                .clone()
+                .map(|dir| project_path.join(dir)),
+            scan_underscore_directories: next_config
+                .await?
+                .experimental
+                .app_dir_scan_underscore_directories
+                .unwrap_or(false),
+        }
+        .cell())
+    }
+}
+
+/// Parses `experimental.appDirIssueSeverity`. Unknown issue names and
+/// severities are reported and ignored.
+async fn parse_issue_severity_overrides(
+    next_config: Vc<NextConfig>,
+    project_path: Vc<FileSystemPath>,
+) -> Result<IndexMap<AppStructureIssue, IssueSeverity>> {
+    let mut overrides = IndexMap::new();
+    let next_config = next_config.await?;
+    let Some(config) = &next_config.experimental.app_dir_issue_severity else {
+        return Ok(overrides);
+    };
+    for (name, severity) in config {
+        let message = match (
+            AppStructureIssue::from_name(name),
+            parse_issue_severity(severity),
+        ) {
+            (Some(issue), Some(severity)) => {
+                overrides.insert(issue, severity);
+                continue;
+            }
+            (None, _) => format!(
+                "\"{name}\" in experimental.appDirIssueSeverity is not an app directory issue. \
+                 Valid names are {}.",
+                AppStructureIssue::ALL
+                    .iter()
+                    .map(|issue| issue.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Some(_), None) => format!(
+                "The severity \"{severity}\" of \"{name}\" in experimental.appDirIssueSeverity is \
+                 not valid. Valid severities are error, warning, hint and info."
+            ),
+        };
+        DirectoryTreeIssue {
+            app_dir: project_path,
+            message: Vc::cell(message),
+            severity: IssueSeverity::Error.cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(overrides)
+}
+
+impl AppStructureOptions {
+    /// Returns the severity of `issue`, taking configured overrides into
+    /// account.
+    fn issue_severity(&self, issue: AppStructureIssue) -> IssueSeverity {
+        self.issue_severity_overrides
+            .get(&issue)
+            .copied()
+            .unwrap_or_else(|| issue.default_severity())
+    }
+
+    fn max_depth(&self) -> u32 {
+        self.max_depth.unwrap_or(DEFAULT_MAX_DIRECTORY_DEPTH)
+    }
+
+    fn max_metadata_file_size(&self) -> u64 {
+        self.max_metadata_file_size
+            .unwrap_or(DEFAULT_MAX_METADATA_FILE_SIZE)
     }
 }
 
@@ -213,9 +639,14 @@ impl OptionAppDir {
     pub async fn routes_changed(
         self: Vc<Self>,
         next_config: Vc<NextConfig>,
+        project_path: Vc<FileSystemPath>,
     ) -> Result<Vc<Completion>> {
         if let Some(app_dir) = *self.await? {
-            let directory_tree = get_directory_tree(app_dir, next_config.page_extensions());
+            let directory_tree = get_directory_tree(
+                app_dir,
+                next_config.resolved_page_extensions(),
+                AppStructureOptions::from_next_config(next_config, project_path),
+            );
             directory_tree.routes_changed().await?;
         }
         Ok(Completion::new())
@@ -223,6 +654,7 @@ impl OptionAppDir {
 }
 
 /// Finds and returns the [DirectoryTree] of the app directory if existing.
+/// The returned path has all symlinks resolved.
 #[turbo_tasks::function]
 pub async fn find_app_dir(project_path: Vc<FileSystemPath>) -> Result<Vc<OptionAppDir>> {
     let app = project_path.join("app".to_string());
@@ -234,6 +666,7 @@ pub async fn find_app_dir(project_path: Vc<FileSystemPath>) -> Result<Vc<OptionA
     } else {
         return Ok(Vc::cell(None));
     }
+    .realpath()
     .resolve()
     .await?;
 
@@ -277,33 +710,331 @@ static STATIC_GLOBAL_METADATA: Lazy<HashMap<&'static str, &'static [&'static str
         ])
     });
 
+/// Image formats which can be configured as additional static metadata file
+/// extensions.
+const SUPPORTED_METADATA_IMAGE_EXTENSIONS: &[&str] =
+    &["avif", "gif", "ico", "jpeg", "jpg", "png", "svg", "webp"];
+
+/// Returns true if `ext` can be configured as an additional extension for the
+/// static metadata type `metadata_type`.
+fn is_supported_metadata_extension(metadata_type: &str, ext: &str) -> bool {
+    matches!(
+        metadata_type,
+        "icon" | "apple-icon" | "opengraph-image" | "twitter-image"
+    ) && SUPPORTED_METADATA_IMAGE_EXTENSIONS.contains(&ext)
+}
+
+type ComponentField = fn(&mut Components) -> &mut Option<Vc<FileSystemPath>>;
+
+/// The component files of a segment with the field of [Components] they're
+/// stored in. The directory scan matches files against this table, and
+/// [get_special_files] lists them in this order.
+const COMPONENT_FILES: &[(&str, ComponentField)] = &[
+    ("page", |components| &mut components.page),
+    ("layout", |components| &mut components.layout),
+    ("error", |components| &mut components.error),
+    ("loading", |components| &mut components.loading),
+    ("template", |components| &mut components.template),
+    ("not-found", |components| &mut components.not_found),
+    ("global-error", |components| &mut components.global_error),
+    ("default", |components| &mut components.default),
+    ("route", |components| &mut components.route),
+];
+
+/// What a special file of the app directory is used for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub enum SpecialFileKind {
+    /// A component of a segment, like `page` or `layout`.
+    Component,
+    /// A metadata file which can be placed in any segment, like `icon`.
+    Metadata,
+    /// A metadata file which is only picked up in the root of the app
+    /// directory, like `robots`.
+    GlobalMetadata,
+}
+
+/// A file name recognized in the app directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecialFile {
+    pub name: String,
+    pub kind: SpecialFileKind,
+    /// The extensions the file can have. Page extensions make a metadata file
+    /// dynamic.
+    pub extensions: Vec<String>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct SpecialFiles(Vec<SpecialFile>);
+
+/// Returns the file names recognized in the app directory with their allowed
+/// extensions, e.g. for editor integrations offering completions.
+#[turbo_tasks::function]
+pub async fn get_special_files(
+    page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<SpecialFiles>> {
+    let page_extensions = page_extensions.await?;
+    let options = options.await?;
+
+    let mut special_files: Vec<_> = COMPONENT_FILES
+        .iter()
+        .map(|(name, _)| SpecialFile {
+            name: name.to_string(),
+            kind: SpecialFileKind::Component,
+            extensions: page_extensions.clone_value(),
+        })
+        .collect();
+    if options.disable_metadata_files {
+        return Ok(Vc::cell(special_files));
+    }
+
+    for (metadata, kind) in [
+        (&*STATIC_LOCAL_METADATA, SpecialFileKind::Metadata),
+        (&*STATIC_GLOBAL_METADATA, SpecialFileKind::GlobalMetadata),
+    ] {
+        let mut names: Vec<_> = metadata.keys().collect();
+        names.sort();
+        for &name in names {
+            // The root `favicon.ico` is the only one which can't be dynamic.
+            let mut extensions = if name == "favicon" {
+                vec![]
+            } else {
+                page_extensions.clone_value()
+            };
+            let additional = options
+                .metadata_extensions
+                .get(name)
+                .into_iter()
+                .flatten()
+                .filter(|ext| is_supported_metadata_extension(name, ext));
+            for ext in metadata[name]
+                .iter()
+                .copied()
+                .chain(additional.map(|ext| &**ext))
+            {
+                if !extensions.iter().any(|existing| existing == ext) {
+                    extensions.push(ext.to_string());
+                }
+            }
+            special_files.push(SpecialFile {
+                name: name.to_string(),
+                kind,
+                extensions,
+            });
+        }
+    }
+
+    Ok(Vc::cell(special_files))
+}
+
+/// Matches a metadata file name, returning its metadata type, its order among
+/// files of the same type and whether it is dynamic.
+///
+/// The order is taken from a leading number (`01-icon.png`) or, without one,
+/// from trailing digits (`icon1.png`). Files without either come first. Static
+/// files may have a variant before their extension, like `icon.dark.png`.
+/// Dynamic files can't, so `icon.dark.tsx` isn't a metadata file.
 fn match_metadata_file<'a>(
     basename: &'a str,
     page_extensions: &[String],
+    metadata_extensions: &IndexMap<String, Vec<String>>,
 ) -> Option<(&'a str, i32, bool)> {
-    let (stem, ext) = basename.split_once('.')?;
-    static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new("^(.*?)(\\d*)$").unwrap());
+    let (name, ext) = split_extension(basename, page_extensions)?;
+    let stem = if page_extensions.iter().any(|e| e == ext) {
+        if name.contains('.') {
+            return None;
+        }
+        name
+    } else {
+        name.split_once('.').map_or(name, |(stem, _)| stem)
+    };
+    static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new("^(?:(\\d+)-)?(.*?)(\\d*)$").unwrap());
     let captures = REGEX.captures(stem).expect("the regex will always match");
-    let stem = captures.get(1).unwrap().as_str();
-    let num: i32 = captures.get(2).unwrap().as_str().parse().unwrap_or(-1);
+    let stem = captures.get(2).unwrap().as_str();
+    let num: i32 = captures
+        .get(1)
+        .map_or_else(|| captures.get(3).unwrap().as_str(), |m| m.as_str())
+        .parse()
+        .unwrap_or(-1);
     if page_extensions.iter().any(|e| e == ext) {
         return Some((stem, num, true));
     }
-    let exts = STATIC_LOCAL_METADATA.get(stem)?;
-    exts.contains(&ext).then_some((stem, num, false))
+    if STATIC_LOCAL_METADATA
+        .get(stem)
+        .is_some_and(|exts| exts.contains(&ext))
+    {
+        return Some((stem, num, false));
+    }
+    let exts = metadata_extensions.get(stem)?;
+    (exts.iter().any(|e| e == ext) && is_supported_metadata_extension(stem, ext))
+        .then_some((stem, num, false))
+}
+
+/// Splits a file name into its name and extension. The extension is taken
+/// from the last dot, unless the file name ends with one of the page
+/// extensions, which may contain dots themselves (e.g. `page.tsx`).
+fn split_extension<'a>(
+    basename: &'a str,
+    page_extensions: &[String],
+) -> Option<(&'a str, &'a str)> {
+    page_extensions
+        .iter()
+        .filter_map(|ext| {
+            let name = basename.strip_suffix(ext.as_str())?.strip_suffix('.')?;
+            Some((name, &basename[name.len() + 1..]))
+        })
+        .min_by_key(|(name, _)| name.len())
+        .or_else(|| basename.rsplit_once('.'))
 }
 
 #[turbo_tasks::function]
 async fn get_directory_tree(
     dir: Vc<FileSystemPath>,
     page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<DirectoryTree>> {
+    let tree = get_directory_tree_internal(dir, page_extensions, options, 0, Vc::cell(Vec::new()));
+    let Some(default_metadata_dir) = options.await?.default_metadata_dir else {
+        return Ok(tree);
+    };
+    if *default_metadata_dir.get_type().await? != FileSystemEntryType::Directory {
+        DirectoryTreeIssue {
+            app_dir: dir,
+            message: Vc::cell(format!(
+                "The default metadata directory {} doesn't exist",
+                default_metadata_dir.to_string().await?
+            )),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::MissingDefaultMetadataDir)
+                .cell(),
+        }
+        .cell()
+        .emit();
+        return Ok(tree);
+    }
+
+    let defaults = get_directory_tree_internal(
+        default_metadata_dir,
+        page_extensions,
+        options,
+        0,
+        Vc::cell(Vec::new()),
+    )
+    .await?
+    .components
+    .await?
+    .metadata
+    .clone();
+    let tree = tree.await?;
+    let mut components = tree.components.await?.clone_value();
+    components.metadata = Metadata::merge(
+        &components.metadata,
+        &defaults.defaults_for(&components.metadata),
+    );
+    Ok(DirectoryTree {
+        subdirectories: tree.subdirectories.clone(),
+        components: components.cell(),
+    }
+    .cell())
+}
+
+/// Resolves the symlink `link` to the file or directory it points to,
+/// following chained links. `visited_dirs` are the paths of the directories
+/// scanned to reach the link, as resolved through any previous links.
+/// Returns `None` for dangling links and for links which point to themselves
+/// or to a directory containing one of `visited_dirs` (e.g. two directories
+/// linking to each other), which would make the app directory infinitely
+/// deep. The latter are reported.
+async fn resolve_symlink(
+    link: Vc<FileSystemPath>,
+    visited_dirs: &[String],
+    options: &AppStructureOptions,
+) -> Result<Option<DirectoryEntry>> {
+    let mut path = link;
+    let mut visited = HashSet::new();
+    loop {
+        let path_value = path.await?;
+        if !visited.insert(path_value.path.clone()) {
+            emit_symlink_cycle_issue(link, options).await?;
+            return Ok(None);
+        }
+        match *path.get_type().await? {
+            FileSystemEntryType::File => return Ok(Some(DirectoryEntry::File(path))),
+            FileSystemEntryType::Directory => {
+                let target = &path_value.path;
+                let is_cycle = visited_dirs.iter().any(|visited_dir| {
+                    target.is_empty()
+                        || visited_dir
+                            .strip_prefix(target.as_str())
+                            .map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+                });
+                if is_cycle {
+                    emit_symlink_cycle_issue(link, options).await?;
+                    return Ok(None);
+                }
+                return Ok(Some(DirectoryEntry::Directory(path)));
+            }
+            FileSystemEntryType::Symlink => {}
+            _ => return Ok(None),
+        }
+        let LinkContent::Link { target, link_type } = &*path.read_link().await? else {
+            return Ok(None);
+        };
+        path = if link_type.contains(LinkType::ABSOLUTE) {
+            path.root().join(target.clone())
+        } else {
+            path.parent().join(target.clone())
+        };
+    }
+}
+
+async fn emit_symlink_cycle_issue(
+    link: Vc<FileSystemPath>,
+    options: &AppStructureOptions,
+) -> Result<()> {
+    DirectoryTreeIssue {
+        app_dir: link,
+        message: Vc::cell(format!(
+            "The symlink {} points to itself or to a directory containing it and is ignored",
+            link.to_string().await?
+        )),
+        severity: options
+            .issue_severity(AppStructureIssue::SymlinkCycle)
+            .cell(),
+    }
+    .cell()
+    .emit();
+    Ok(())
+}
+
+#[turbo_tasks::function]
+async fn get_directory_tree_internal(
+    dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+    depth: u32,
+    visited_dirs: Vc<Vec<String>>,
 ) -> Result<Vc<DirectoryTree>> {
     let DirectoryContent::Entries(entries) = &*dir.read_dir().await? else {
         bail!("{} must be a directory", dir.to_string().await?);
     };
+    // Subdirectories reached through symlinks are scanned at their resolved
+    // path, so these are the real directories leading to this one.
+    let mut visited_dirs = visited_dirs.await?.clone_value();
+    visited_dirs.push(dir.await?.path.clone());
     let page_extensions_value = page_extensions.await?;
+    let options_value = options.await?;
+    let ignored_directories = options_value
+        .ignored_directories
+        .iter()
+        .map(|pattern| Glob::new(pattern.clone()))
+        .try_join()
+        .await?;
 
-    let mut subdirectories = BTreeMap::new();
+    let mut subdirectory_paths = BTreeMap::new();
     let mut components = Components::default();
 
     let mut metadata_icon = Vec::new();
@@ -312,37 +1043,51 @@ async fn get_directory_tree(
     let mut metadata_twitter = Vec::new();
     let mut metadata_favicon = Vec::new();
 
+    let mut paired_alt_files = HashSet::new();
+    let mut slot_directories = HashMap::new();
+
     for (basename, entry) in entries {
-        match *entry {
+        let entry = match *entry {
+            DirectoryEntry::Symlink(link) => {
+                match resolve_symlink(link, &visited_dirs, &options_value).await? {
+                    Some(entry) => entry,
+                    None => continue,
+                }
+            }
+            _ => entry.clone(),
+        };
+        match entry {
             DirectoryEntry::File(file) => {
-                if let Some((stem, ext)) = basename.split_once('.') {
+                if let Some((stem, ext)) = split_extension(basename, &page_extensions_value) {
                     if page_extensions_value.iter().any(|e| e == ext) {
-                        match stem {
-                            "page" => components.page = Some(file),
-                            "layout" => components.layout = Some(file),
-                            "error" => components.error = Some(file),
-                            "loading" => components.loading = Some(file),
-                            "template" => components.template = Some(file),
-                            "not-found" => components.not_found = Some(file),
-                            "default" => components.default = Some(file),
-                            "route" => components.route = Some(file),
-                            "manifest" => {
-                                components.metadata.manifest =
-                                    Some(MetadataItem::Dynamic { path: file });
-                                continue;
-                            }
-                            _ => {}
+                        if let Some((_, component)) =
+                            COMPONENT_FILES.iter().find(|(name, _)| *name == stem)
+                        {
+                            *component(&mut components) = Some(file);
+                        } else if stem == "manifest" && !options_value.disable_metadata_files {
+                            components.metadata.manifest =
+                                Some(MetadataItem::Dynamic { path: file });
+                            continue;
                         }
                     }
                 }
 
-                if let Some((metadata_type, num, dynamic)) =
-                    match_metadata_file(basename.as_str(), &page_extensions_value)
-                {
+                if options_value.disable_metadata_files {
+                    continue;
+                }
+
+                if let Some((metadata_type, num, dynamic)) = match_metadata_file(
+                    basename.as_str(),
+                    &page_extensions_value,
+                    &options_value.metadata_extensions,
+                ) {
                     if metadata_type == "manifest" {
                         if num == -1 {
                             components.metadata.manifest =
                                 Some(MetadataItem::Static { path: file });
+                            if options_value.validate_metadata_manifest {
+                                validate_metadata_manifest(dir, file, options).await?;
+                            }
                         }
                         continue;
                     }
@@ -356,6 +1101,13 @@ async fn get_directory_tree(
                         _ => None,
                     };
 
+                    if metadata_type == "opengraph-image"
+                        && !dynamic
+                        && options_value.validate_metadata_images
+                    {
+                        validate_open_graph_image_size(dir, file, options).await?;
+                    }
+
                     if let Some(entry) = entry {
                         if dynamic {
                             entry.push((num, MetadataWithAltItem::Dynamic { path: file }));
@@ -365,10 +1117,18 @@ async fn get_directory_tree(
                             let basename = file_name
                                 .rsplit_once('.')
                                 .map_or(file_name, |(basename, _)| basename);
-                            let alt_path = file.parent().join(format!("{}.alt.txt", basename));
-                            let alt_path =
-                                matches!(&*alt_path.get_type().await?, FileSystemEntryType::File)
-                                    .then_some(alt_path);
+                            let alt_file_name = format!("{}.alt.txt", basename);
+                            let alt_path = file.parent().join(alt_file_name.clone());
+                            paired_alt_files.insert(alt_file_name);
+                            let (alt_path_type, _) =
+                                try_join!(async { alt_path.get_type().await }, async {
+                                    if options_value.validate_metadata_file_size {
+                                        validate_metadata_file_size(dir, file, options).await?;
+                                    }
+                                    anyhow::Ok(())
+                                })?;
+                            let alt_path = matches!(&*alt_path_type, FileSystemEntryType::File)
+                                .then_some(alt_path);
                             entry.push((
                                 num,
                                 MetadataWithAltItem::Static {
@@ -381,17 +1141,84 @@ async fn get_directory_tree(
                 }
             }
             DirectoryEntry::Directory(dir) => {
-                // appDir ignores paths starting with an underscore
-                if !basename.starts_with('_') {
-                    let result = get_directory_tree(dir, page_extensions);
-                    subdirectories.insert(get_underscore_normalized_path(basename), result);
+                // appDir ignores paths starting with an underscore, unless
+                // configured otherwise
+                if (options_value.scan_underscore_directories || !basename.starts_with('_'))
+                    && !ignored_directories
+                        .iter()
+                        .any(|glob| glob.execute(basename))
+                {
+                    if depth >= options_value.max_depth() {
+                        DirectoryTreeIssue {
+                            app_dir: dir,
+                            message: Vc::cell(format!(
+                                "The app directory is nested deeper than the maximum depth of {}. \
+                                 {} and its subdirectories are ignored.",
+                                options_value.max_depth(),
+                                dir.to_string().await?
+                            )),
+                            severity: options_value
+                                .issue_severity(AppStructureIssue::MaxDepthExceeded)
+                                .cell(),
+                        }
+                        .cell()
+                        .emit();
+                        continue;
+                    }
+                    let key = get_underscore_normalized_path(basename);
+                    // Slots are looked up by their normalized name, so two
+                    // directories resolving to the same slot would silently
+                    // replace each other.
+                    if is_parallel_route(&key) {
+                        if let Some(existing) = slot_directories.insert(key.clone(), basename) {
+                            DirectoryTreeIssue {
+                                app_dir: dir,
+                                message: Vc::cell(format!(
+                                    "The directories {existing} and {basename} in {} both resolve \
+                                     to the parallel route slot {key}. Only {basename} is used, \
+                                     rename one of them.",
+                                    dir.parent().to_string().await?
+                                )),
+                                severity: options_value
+                                    .issue_severity(AppStructureIssue::DuplicateParallelRouteSlot)
+                                    .cell(),
+                            }
+                            .cell()
+                            .emit();
+                        }
+                    }
+                    subdirectory_paths.insert(key, dir);
                 }
             }
-            // TODO(WEB-952) handle symlinks in app dir
             _ => {}
         }
     }
 
+    // An alt text file is only picked up next to a static metadata image, so
+    // any remaining one is most likely a typo or a leftover of a removed image.
+    for (basename, entry) in entries {
+        if let DirectoryEntry::File(_) = entry {
+            if basename.ends_with(".alt.txt")
+                && !paired_alt_files.contains(basename)
+                && !options_value.disable_metadata_files
+            {
+                DirectoryTreeIssue {
+                    app_dir: dir,
+                    message: Vc::cell(format!(
+                        "Found alt text file {basename} in {} without a corresponding metadata \
+                         image",
+                        dir.to_string().await?
+                    )),
+                    severity: options_value
+                        .issue_severity(AppStructureIssue::OrphanedAltText)
+                        .cell(),
+                }
+                .cell()
+                .emit();
+            }
+        }
+    }
+
     fn sort<T>(mut list: Vec<(i32, T)>) -> Vec<T> {
         list.sort_by_key(|(num, _)| *num);
         list.into_iter().map(|(_, item)| item).collect()
@@ -403,6 +1230,42 @@ async fn get_directory_tree(
     components.metadata.open_graph = sort(metadata_open_graph);
     components.metadata.favicon = sort(metadata_favicon);
 
+    // Calling `get_directory_tree_internal` schedules the scan right away, so
+    // with a limit, the next batch is only created once the previous one has
+    // been scanned.
+    let subdirectory_paths = subdirectory_paths.into_iter().collect::<Vec<_>>();
+    let visited_dirs = Vc::cell(visited_dirs);
+    let batch_size = match options_value.scan_concurrency {
+        Some(scan_concurrency) => scan_concurrency.max(1) as usize,
+        None => subdirectory_paths.len().max(1),
+    };
+    let mut subdirectories = BTreeMap::new();
+    for batch in subdirectory_paths.chunks(batch_size) {
+        let batch = batch
+            .iter()
+            .map(|(key, dir)| {
+                (
+                    key.clone(),
+                    get_directory_tree_internal(
+                        *dir,
+                        page_extensions,
+                        options,
+                        depth + 1,
+                        visited_dirs,
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        if options_value.scan_concurrency.is_some() {
+            batch
+                .iter()
+                .map(|&(_, subdirectory)| async move { subdirectory.await.map(|_| ()) })
+                .try_join()
+                .await?;
+        }
+        subdirectories.extend(batch);
+    }
+
     Ok(DirectoryTree {
         subdirectories,
         components: components.cell(),
@@ -418,15 +1281,166 @@ pub struct LoaderTree {
     pub components: Vc<Components>,
 }
 
+/// Returns a stable hash of the structure of a [LoaderTree], covering its
+/// segments, parallel routes and component files. Parallel routes are hashed
+/// independently of their insertion order.
+#[turbo_tasks::function]
+pub async fn loader_tree_hash(tree: Vc<LoaderTree>) -> Result<Vc<String>> {
+    let description = loader_tree_description(tree).await?;
+    Ok(Vc::cell(format!(
+        "{:016x}",
+        hash_xxh3_hash64(&*description)
+    )))
+}
+
+#[turbo_tasks::function]
+async fn loader_tree_description(tree: Vc<LoaderTree>) -> Result<Vc<String>> {
+    let LoaderTree {
+        segment,
+        parallel_routes,
+        components,
+    } = &*tree.await?;
+    let parallel_routes = parallel_routes
+        .iter()
+        .map(|(key, tree)| async move {
+            Ok((
+                key.clone(),
+                loader_tree_description(*tree).await?.clone_value(),
+            ))
+        })
+        .try_join()
+        .await?;
+    Ok(Vc::cell(describe_loader_tree(
+        segment,
+        &components_description(&*components.await?).await?,
+        parallel_routes,
+    )))
+}
+
+/// Describes all files of the components in a fixed order.
+async fn components_description(components: &Components) -> Result<String> {
+    let mut description = String::new();
+    for (name, path) in component_files(components) {
+        description.push_str(&format!("{name}={};", path.to_string().await?));
+    }
+    Ok(description)
+}
+
+/// Lists all files of the components in a fixed order, by their kind.
+pub(crate) fn component_files(components: &Components) -> Vec<(&'static str, Vc<FileSystemPath>)> {
+    let Components {
+        page,
+        layout,
+        error,
+        loading,
+        template,
+        not_found,
+        global_error,
+        default,
+        route,
+        metadata,
+    } = components;
+    let Metadata {
+        icon,
+        apple,
+        twitter,
+        open_graph,
+        favicon,
+        manifest,
+    } = metadata;
+
+    let mut files: Vec<(&'static str, Option<Vc<FileSystemPath>>)> = vec![
+        ("page", *page),
+        ("layout", *layout),
+        ("error", *error),
+        ("loading", *loading),
+        ("template", *template),
+        ("not-found", *not_found),
+        ("global-error", *global_error),
+        ("default", *default),
+        ("route", *route),
+    ];
+    for (name, items) in [
+        ("icon", icon),
+        ("apple", apple),
+        ("twitter", twitter),
+        ("open-graph", open_graph),
+        ("favicon", favicon),
+    ] {
+        for item in items {
+            match item {
+                MetadataWithAltItem::Static { path, alt_path } => {
+                    files.push((name, Some(*path)));
+                    files.push((name, *alt_path));
+                }
+                MetadataWithAltItem::Dynamic { path } => files.push((name, Some(*path))),
+            }
+        }
+    }
+    if let Some(MetadataItem::Static { path } | MetadataItem::Dynamic { path }) = manifest {
+        files.push(("manifest", Some(*path)));
+    }
+
+    files
+        .into_iter()
+        .filter_map(|(name, path)| Some((name, path?)))
+        .collect()
+}
+
+/// Combines the description of a tree node with the descriptions of its
+/// parallel routes, ordered by their key.
+fn describe_loader_tree(
+    segment: &str,
+    components: &str,
+    mut parallel_routes: Vec<(String, String)>,
+) -> String {
+    parallel_routes.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut description = format!("{segment:?}[{components}]{{");
+    for (key, tree) in parallel_routes {
+        description.push_str(&format!("{key:?}:{tree},"));
+    }
+    description.push('}');
+    description
+}
+
 #[turbo_tasks::function]
 async fn merge_loader_trees(
     app_dir: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
     tree1: Vc<LoaderTree>,
     tree2: Vc<LoaderTree>,
 ) -> Result<Vc<LoaderTree>> {
     let tree1 = tree1.await?;
     let tree2 = tree2.await?;
 
+    // A `default` of a parallel route slot is merged with a page at the same
+    // path from another directory (e.g. `@modal/default.tsx` and
+    // `@modal/(group)/page.tsx`). Only one of the segments can be kept. A page
+    // next to the default is fine, as the page takes precedence.
+    for (default_tree, other_tree) in [(&tree1, &tree2), (&tree2, &tree1)] {
+        if default_tree.segment == "__DEFAULT__"
+            && !matches!(other_tree.segment.as_str(), "" | "__DEFAULT__" | "__PAGE__")
+        {
+            if let Some(default) = default_tree.components.await?.default {
+                DirectoryTreeIssue {
+                    app_dir,
+                    message: Vc::cell(format!(
+                        "The parallel route default {} conflicts with the page in segment {:?}, \
+                         which resolves to the same path",
+                        default.to_string().await?,
+                        other_tree.segment
+                    )),
+                    severity: options
+                        .await?
+                        .issue_severity(AppStructureIssue::ConflictingParallelRouteDefault)
+                        .cell(),
+                }
+                .cell()
+                .emit();
+            }
+        }
+    }
+
     let segment = if !tree1.segment.is_empty() {
         tree1.segment.to_string()
     } else {
@@ -435,7 +1449,14 @@ async fn merge_loader_trees(
 
     let mut parallel_routes = tree1.parallel_routes.clone();
     for (key, &tree2_route) in tree2.parallel_routes.iter() {
-        add_parallel_route(app_dir, &mut parallel_routes, key.clone(), tree2_route).await?
+        add_parallel_route(
+            app_dir,
+            options,
+            &mut parallel_routes,
+            key.clone(),
+            tree2_route,
+        )
+        .await?
     }
 
     let components = Components::merge(&*tree1.components.await?, &*tree2.components.await?).cell();
@@ -465,6 +1486,129 @@ pub enum Entrypoint {
 #[turbo_tasks::value(transparent)]
 pub struct Entrypoints(IndexMap<String, Entrypoint>);
 
+/// Returns the pathnames of the entrypoints which use `changed_file`, either
+/// as the route handler or as a component or metadata file of any segment of
+/// their loader tree (e.g. a shared layout). Only these need to be rebuilt
+/// after the file is edited.
+///
+/// Files imported by these modules aren't part of the app structure. Edits to
+/// them are picked up by turbo-tasks invalidating the affected modules.
+#[turbo_tasks::function]
+pub async fn get_affected_entrypoints(
+    entrypoints: Vc<Entrypoints>,
+    changed_file: Vc<FileSystemPath>,
+) -> Result<Vc<Vec<String>>> {
+    let changed_file = &*changed_file.await?;
+    let mut affected = Vec::new();
+    for (pathname, entrypoint) in entrypoints.await?.iter() {
+        let is_affected = match *entrypoint {
+            Entrypoint::AppPage { loader_tree, .. } => {
+                loader_tree_uses_file(loader_tree, changed_file).await?
+            }
+            Entrypoint::AppRoute { path, .. } => *path.await? == *changed_file,
+        };
+        if is_affected {
+            affected.push(pathname.clone());
+        }
+    }
+    Ok(Vc::cell(affected))
+}
+
+#[async_recursion]
+async fn loader_tree_uses_file(tree: Vc<LoaderTree>, file: &FileSystemPath) -> Result<bool> {
+    let tree = tree.await?;
+    for (_, path) in component_files(&*tree.components.await?) {
+        if *path.await? == *file {
+            return Ok(true);
+        }
+    }
+    for &parallel_route in tree.parallel_routes.values() {
+        if loader_tree_uses_file(parallel_route, file).await? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn is_route_group(name: &str) -> bool {
+    name.starts_with('(') && name.ends_with(')') && match_interception_route(name).is_none()
+}
+
+/// Removes the route group segments from an entrypoint key, e.g.
+/// `/(shop)/_not-found` becomes `/_not-found`.
+fn without_route_groups(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !is_route_group(segment))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The marker of an intercepting route segment, e.g. `(..)` in `(..)photo`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InterceptionMarker {
+    /// `(.)`, intercepting a segment on the same level.
+    SameLevel,
+    /// `(..)`, intercepting a segment one level above.
+    OneLevelUp,
+    /// `(..)(..)`, intercepting a segment two levels above.
+    TwoLevelsUp,
+    /// `(...)`, intercepting a segment of the root.
+    Root,
+}
+
+/// Matches an intercepting route segment like `(..)photo`, returning its
+/// marker and the segment it intercepts.
+pub fn match_interception_route(name: &str) -> Option<(InterceptionMarker, &str)> {
+    // `(..)` is a prefix of `(..)(..)`, so the longer marker is matched first.
+    [
+        ("(..)(..)", InterceptionMarker::TwoLevelsUp),
+        ("(...)", InterceptionMarker::Root),
+        ("(..)", InterceptionMarker::OneLevelUp),
+        ("(.)", InterceptionMarker::SameLevel),
+    ]
+    .into_iter()
+    .find_map(|(prefix, marker)| Some((marker, name.strip_prefix(prefix)?)))
+    .filter(|(_, segment)| !segment.is_empty())
+}
+
+/// Returns the path an intercepting route intercepts, e.g. `/photo/[id]` for
+/// `/feed/(..)photo/[id]`, or `None` for other routes. Like in Next.js, the
+/// markers count route segments, so route groups and parallel routes are
+/// skipped. Fails when a marker goes above the root of the app directory.
+pub fn get_intercepted_path(pathname: &str) -> Result<Option<String>> {
+    let segments = pathname
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let Some((index, marker, intercepted_segment)) =
+        segments.iter().enumerate().find_map(|(index, segment)| {
+            let (marker, intercepted_segment) = match_interception_route(segment)?;
+            Some((index, marker, intercepted_segment))
+        })
+    else {
+        return Ok(None);
+    };
+    let levels_up = match marker {
+        InterceptionMarker::SameLevel => 0,
+        InterceptionMarker::OneLevelUp => 1,
+        InterceptionMarker::TwoLevelsUp => 2,
+        InterceptionMarker::Root => index,
+    };
+    let Some(base) = index.checked_sub(levels_up) else {
+        bail!(
+            "The interception route {pathname} intercepts a path above the root of the app \
+             directory"
+        );
+    };
+    let intercepted_segments = segments[..base]
+        .iter()
+        .copied()
+        .chain([intercepted_segment])
+        .chain(segments[index + 1..].iter().copied())
+        .collect::<Vec<_>>();
+    Ok(Some(format!("/{}", intercepted_segments.join("/"))))
+}
+
 fn is_parallel_route(name: &str) -> bool {
     name.starts_with('@')
 }
@@ -475,6 +1619,7 @@ fn match_parallel_route(name: &str) -> Option<&str> {
 
 async fn add_parallel_route(
     app_dir: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
     result: &mut IndexMap<String, Vc<LoaderTree>>,
     key: String,
     loader_tree: Vc<LoaderTree>,
@@ -482,7 +1627,7 @@ async fn add_parallel_route(
     match result.entry(key) {
         Entry::Occupied(mut e) => {
             let value = e.get_mut();
-            *value = merge_loader_trees(app_dir, *value, loader_tree)
+            *value = merge_loader_trees(app_dir, options, *value, loader_tree)
                 .resolve()
                 .await?;
         }
@@ -495,6 +1640,7 @@ async fn add_parallel_route(
 
 async fn add_app_page(
     app_dir: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
     result: &mut IndexMap<String, Entrypoint>,
     key: String,
     original_name: String,
@@ -516,7 +1662,10 @@ async fn add_app_page(
                                  {original_name}",
                                 e.key()
                             )),
-                            severity: IssueSeverity::Error.cell(),
+                            severity: options
+                                .await?
+                                .issue_severity(AppStructureIssue::ConflictingRoutes)
+                                .cell(),
                         }
                         .cell()
                         .emit();
@@ -526,7 +1675,7 @@ async fn add_app_page(
                         loader_tree: value, ..
                     } = e.get_mut()
                     {
-                        *value = merge_loader_trees(app_dir, *value, loader_tree)
+                        *value = merge_loader_trees(app_dir, options, *value, loader_tree)
                             .resolve()
                             .await?;
                     }
@@ -537,12 +1686,15 @@ async fn add_app_page(
                 } => {
                     DirectoryTreeIssue {
                         app_dir,
-                        message: Vc::cell(format!(
-                            "Conflicting page and route at {}: route at {existing_original_name} \
-                             and page at {original_name}",
-                            e.key()
+                        message: Vc::cell(page_and_route_conflict_message(
+                            e.key(),
+                            existing_original_name,
+                            &original_name,
                         )),
-                        severity: IssueSeverity::Error.cell(),
+                        severity: options
+                            .await?
+                            .issue_severity(AppStructureIssue::ConflictingRoutes)
+                            .cell(),
                     }
                     .cell()
                     .emit();
@@ -562,6 +1714,7 @@ async fn add_app_page(
 
 async fn add_app_route(
     app_dir: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
     result: &mut IndexMap<String, Entrypoint>,
     key: String,
     original_name: String,
@@ -577,12 +1730,15 @@ async fn add_app_route(
                 } => {
                     DirectoryTreeIssue {
                         app_dir,
-                        message: Vc::cell(format!(
-                            "Conflicting route and page at {}: route at {original_name} and page \
-                             at {existing_original_name}",
-                            e.key()
+                        message: Vc::cell(page_and_route_conflict_message(
+                            e.key(),
+                            &original_name,
+                            existing_original_name,
                         )),
-                        severity: IssueSeverity::Error.cell(),
+                        severity: options
+                            .await?
+                            .issue_severity(AppStructureIssue::ConflictingRoutes)
+                            .cell(),
                     }
                     .cell()
                     .emit();
@@ -598,7 +1754,10 @@ async fn add_app_route(
                              {original_name}",
                             e.key()
                         )),
-                        severity: IssueSeverity::Error.cell(),
+                        severity: options
+                            .await?
+                            .issue_severity(AppStructureIssue::ConflictingRoutes)
+                            .cell(),
                     }
                     .cell()
                     .emit();
@@ -620,25 +1779,824 @@ async fn add_app_route(
     Ok(())
 }
 
-#[turbo_tasks::function]
-pub fn get_entrypoints(
-    app_dir: Vc<FileSystemPath>,
-    page_extensions: Vc<Vec<String>>,
-) -> Vc<Entrypoints> {
-    directory_tree_to_entrypoints(app_dir, get_directory_tree(app_dir, page_extensions))
+/// Describes a page and a route handler resolving to the same pathname. At the
+/// root, both are usually files next to each other (`app/page.tsx` and
+/// `app/route.ts`), possibly in route groups, so their names don't tell them
+/// apart.
+fn page_and_route_conflict_message(
+    pathname: &str,
+    route_original_name: &str,
+    page_original_name: &str,
+) -> String {
+    if pathname == "/" {
+        return "Conflicting page and route at the root /: the app directory or one of its route \
+                groups has both a page and a route file. Only one of them can handle /, remove \
+                the other one."
+            .to_string();
+    }
+    format!(
+        "Conflicting page and route at {pathname}: route at {route_original_name} and page at \
+         {page_original_name}"
+    )
+}
+
+/// The minimum size of Open Graph images recommended by social platforms.
+const MIN_OPEN_GRAPH_IMAGE_SIZE: (u32, u32) = (1200, 630);
+
+fn is_undersized_open_graph_image(width: u32, height: u32) -> bool {
+    let (min_width, min_height) = MIN_OPEN_GRAPH_IMAGE_SIZE;
+    width < min_width || height < min_height
 }
 
 #[turbo_tasks::function]
-fn directory_tree_to_entrypoints(
+async fn validate_open_graph_image_size(
     app_dir: Vc<FileSystemPath>,
-    directory_tree: Vc<DirectoryTree>,
-) -> Vc<Entrypoints> {
-    directory_tree_to_entrypoints_internal(
-        app_dir,
-        "".to_string(),
+    image: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let source: Vc<Box<dyn Source>> = Vc::upcast(FileSource::new(image));
+    let AssetContent::File(content) = *source.content().await? else {
+        return Ok(Completion::new());
+    };
+    let info = match get_meta_data(source.ident(), content, None).await {
+        Ok(info) => info,
+        // A broken image shouldn't fail the whole app directory, it's only
+        // reported like an undersized one.
+        Err(err) => {
+            DirectoryTreeIssue {
+                app_dir,
+                message: Vc::cell(format!(
+                    "The size of the Open Graph image {} couldn't be read: {err}",
+                    image.to_string().await?,
+                )),
+                severity: options
+                    .await?
+                    .issue_severity(AppStructureIssue::UndersizedOpenGraphImage)
+                    .cell(),
+            }
+            .cell()
+            .emit();
+            return Ok(Completion::new());
+        }
+    };
+    if is_undersized_open_graph_image(info.width, info.height) {
+        let (min_width, min_height) = MIN_OPEN_GRAPH_IMAGE_SIZE;
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(format!(
+                "The Open Graph image {} is {}x{} pixels, which is smaller than the recommended \
+                 size of {min_width}x{min_height} pixels",
+                image.to_string().await?,
+                info.width,
+                info.height,
+            )),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::UndersizedOpenGraphImage)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+/// Emits a warning when the static metadata file `file` is larger than
+/// [AppStructureOptions::max_metadata_file_size].
+#[turbo_tasks::function]
+async fn validate_metadata_file_size(
+    app_dir: Vc<FileSystemPath>,
+    file: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let FileContent::Content(content) = &*file.read().await? else {
+        return Ok(Completion::new());
+    };
+    let size = content.content().len() as u64;
+    let options = options.await?;
+    let max_size = options.max_metadata_file_size();
+    if size > max_size {
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(format!(
+                "The metadata file {} is {size} bytes, which is larger than the maximum of \
+                 {max_size} bytes. It is downloaded with every page that links to it, consider \
+                 compressing it.",
+                file.to_string().await?,
+            )),
+            severity: options
+                .issue_severity(AppStructureIssue::OversizedMetadataFile)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+/// Emits an error when the static manifest `file` isn't valid JSON, and an
+/// issue when it doesn't look like a web app manifest, see
+/// [AppStructureIssue::InvalidManifestSchema].
+#[turbo_tasks::function]
+async fn validate_metadata_manifest(
+    app_dir: Vc<FileSystemPath>,
+    file: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let FileContent::Content(content) = &*file.read().await? else {
+        return Ok(Completion::new());
+    };
+    let manifest: serde_json::Value = match parse_json_rope_with_source_context(content.content()) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            DirectoryTreeIssue {
+                app_dir,
+                message: Vc::cell(format!(
+                    "The manifest {} isn't valid JSON: {err}",
+                    file.to_string().await?,
+                )),
+                severity: options
+                    .await?
+                    .issue_severity(AppStructureIssue::InvalidManifest)
+                    .cell(),
+            }
+            .cell()
+            .emit();
+            return Ok(Completion::new());
+        }
+    };
+
+    let problem = match &manifest {
+        serde_json::Value::Object(manifest) => {
+            if !manifest.contains_key("name") && !manifest.contains_key("short_name") {
+                Some("it has neither a \"name\" nor a \"short_name\"")
+            } else if manifest.get("icons").is_some_and(|icons| !icons.is_array()) {
+                Some("\"icons\" isn't an array")
+            } else {
+                None
+            }
+        }
+        _ => Some("it isn't a JSON object"),
+    };
+    if let Some(problem) = problem {
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(format!(
+                "The manifest {} isn't a valid web app manifest, as {problem}",
+                file.to_string().await?,
+            )),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::InvalidManifestSchema)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+#[turbo_tasks::function]
+pub async fn get_entrypoints(
+    app_dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Entrypoints>> {
+    validate_metadata_extensions(app_dir, options).await?;
+    let directory_tree = get_directory_tree(app_dir, page_extensions, options);
+    validate_parallel_routes(app_dir, String::new(), directory_tree, options).await?;
+    let entrypoints = directory_tree_to_entrypoints(app_dir, directory_tree, options);
+    if !options.await?.force_case_sensitive_routes {
+        validate_case_conflicts(app_dir, entrypoints, options).await?;
+    }
+    validate_root_layouts(app_dir, entrypoints, options).await?;
+    validate_reachable_pages(app_dir, entrypoints, options).await?;
+    validate_interception_routes(app_dir, entrypoints, options).await?;
+    Ok(entrypoints)
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionEntrypoint(Option<Entrypoint>);
+
+/// Returns the entrypoint of a single route, e.g. `/blog/[slug]`, like it's
+/// keyed in [get_entrypoints]. The whole app directory is still scanned (the
+/// scan is shared with [get_entrypoints]), but only the directories which can
+/// contribute to the route (its segments, route groups and parallel routes)
+/// are turned into entrypoints, e.g. for focused tests or compiling a single
+/// route on demand.
+///
+/// Unlike [get_entrypoints], this doesn't report issues of the whole app
+/// directory, such as routes conflicting with other routes.
+#[turbo_tasks::function]
+pub async fn get_entrypoint_for_path(
+    app_dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    path: String,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<OptionEntrypoint>> {
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.to_string())
+        .collect::<Vec<_>>();
+    let directory_tree = prune_directory_tree(
+        get_directory_tree(app_dir, page_extensions, options),
+        &segments,
+    )
+    .await?;
+    let entrypoints = directory_tree_to_entrypoints(app_dir, directory_tree, options).await?;
+    Ok(Vc::cell(entrypoints.get(&path).cloned()))
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionMetadata(Option<Metadata>);
+
+/// Returns the metadata files which apply to the page at `path`, e.g. for
+/// tooling showing which icons and images a route uses. Returns `None` when
+/// there is no page at `path`.
+///
+/// The metadata of the segments is merged from the root down to the page. A
+/// segment defining a kind of metadata overrides the items its parents define
+/// for that kind, the other kinds are inherited.
+#[turbo_tasks::function]
+pub async fn get_route_metadata(
+    app_dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    path: String,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<OptionMetadata>> {
+    let entrypoint = get_entrypoint_for_path(app_dir, page_extensions, path, options).await?;
+    let Some(Entrypoint::AppPage { loader_tree, .. }) = &*entrypoint else {
+        return Ok(Vc::cell(None));
+    };
+
+    let mut metadata = Metadata::default();
+    let mut tree = Some(*loader_tree);
+    while let Some(current) = tree {
+        let current = current.await?;
+        let segment_metadata = &current.components.await?.metadata;
+        metadata = Metadata::merge(segment_metadata, &metadata.defaults_for(segment_metadata));
+        tree = current.parallel_routes.get("children").copied();
+    }
+    Ok(Vc::cell(Some(metadata)))
+}
+
+/// Removes the subdirectories which can't contribute to the route with the
+/// remaining `segments`. Subdirectories are keyed by their normalized name,
+/// so `%5Fblog` matches the segment `_blog`.
+#[async_recursion]
+async fn prune_directory_tree(
+    directory_tree: Vc<DirectoryTree>,
+    segments: &[String],
+) -> Result<Vc<DirectoryTree>> {
+    let directory_tree = directory_tree.await?;
+    let mut subdirectories = BTreeMap::new();
+    for (name, &subdirectory) in directory_tree.subdirectories.iter() {
+        let remaining = if is_route_group(name) || is_parallel_route(name) {
+            segments
+        } else if segments.first() == Some(name) {
+            &segments[1..]
+        } else {
+            continue;
+        };
+        subdirectories.insert(
+            name.clone(),
+            prune_directory_tree(subdirectory, remaining).await?,
+        );
+    }
+    Ok(DirectoryTree {
+        subdirectories,
+        components: directory_tree.components,
+    }
+    .cell())
+}
+
+/// Returns the module rendered for parallel route slots without a `default`,
+/// see [AppStructureOptions::parallel_route_default].
+async fn parallel_route_default(
+    app_dir: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<FileSystemPath>> {
+    if let Some(default) = options.await?.parallel_route_default {
+        if *default.get_type().await? == FileSystemEntryType::File {
+            return Ok(default);
+        }
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(format!(
+                "The parallel route default {} doesn't exist, the default of the next package is \
+                 used instead",
+                default.to_string().await?
+            )),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::MissingParallelRouteDefault)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(get_next_package(app_dir)
+        .join("dist/client/components/parallel-route-default.js".to_string()))
+}
+
+/// Emits a warning for routes which only differ in case. They are kept as
+/// separate entrypoints, but only one of them can be checked out on
+/// case-insensitive filesystems.
+#[turbo_tasks::function]
+async fn validate_case_conflicts(
+    app_dir: Vc<FileSystemPath>,
+    entrypoints: Vc<Entrypoints>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let mut pathnames_by_folded_key = HashMap::new();
+    for pathname in entrypoints.await?.keys() {
+        let folded_key = pathname.to_lowercase();
+        if let Some(existing_pathname) = pathnames_by_folded_key.get(&folded_key) {
+            DirectoryTreeIssue {
+                app_dir,
+                message: Vc::cell(format!(
+                    "Routes {existing_pathname} and {pathname} only differ in case and can't both \
+                     exist on case-insensitive filesystems."
+                )),
+                severity: options
+                    .await?
+                    .issue_severity(AppStructureIssue::CaseConflictingRoutes)
+                    .cell(),
+            }
+            .cell()
+            .emit();
+            continue;
+        }
+        pathnames_by_folded_key.insert(folded_key, pathname.clone());
+    }
+    Ok(Completion::new())
+}
+
+/// Emits an error naming the pages which aren't rendered inside any layout.
+/// Every page needs a root layout, which is either `app/layout` or, with
+/// multiple root layouts, the top-most layout of its route group.
+#[turbo_tasks::function]
+async fn validate_root_layouts(
+    app_dir: Vc<FileSystemPath>,
+    entrypoints: Vc<Entrypoints>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let mut pages_without_layout = Vec::new();
+    for (pathname, entrypoint) in entrypoints.await?.iter() {
+        if let Entrypoint::AppPage { loader_tree, .. } = *entrypoint {
+            if page_has_layout(loader_tree, false).await? == Some(false) {
+                pages_without_layout.push(pathname.clone());
+            }
+        }
+    }
+    if !pages_without_layout.is_empty() {
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(format!(
+                "The following pages don't have a root layout: {}. Add a layout to the app \
+                 directory, or to each route group with its own root layout.",
+                pages_without_layout.join(", ")
+            )),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::MissingRootLayout)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+/// Emits a warning naming the pages which can't be reached by their URL. This
+/// happens when a page only exists in a parallel route slot (e.g.
+/// `app/@modal/photo/page.tsx`) and there is neither a page nor a `default`
+/// for the `children` of its segments, so visiting the URL renders a 404.
+#[turbo_tasks::function]
+async fn validate_reachable_pages(
+    app_dir: Vc<FileSystemPath>,
+    entrypoints: Vc<Entrypoints>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let mut unreachable_pages = Vec::new();
+    for (pathname, entrypoint) in entrypoints.await?.iter() {
+        if let Entrypoint::AppPage { loader_tree, .. } = *entrypoint {
+            if !renders_children(loader_tree).await? {
+                unreachable_pages.push(pathname.clone());
+            }
+        }
+    }
+    if !unreachable_pages.is_empty() {
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(format!(
+                "The following pages are only rendered in parallel route slots and can't be \
+                 reached by their URL: {}. Add a page or a default.tsx next to the slots.",
+                unreachable_pages.join(", ")
+            )),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::UnreachablePage)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+/// Emits errors for intercepting routes which intercept a path above the root
+/// of the app directory, or which conflict with the route at the path they
+/// intercept: route handlers can't be intercepted, and the dynamic segments of
+/// an intercepted page need the same names in the intercepting route.
+#[turbo_tasks::function]
+async fn validate_interception_routes(
+    app_dir: Vc<FileSystemPath>,
+    entrypoints: Vc<Entrypoints>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let entrypoints = entrypoints.await?;
+    let routes_by_shape = entrypoints
+        .iter()
+        .filter(|(pathname, _)| matches!(get_intercepted_path(pathname), Ok(None)))
+        .map(|(pathname, entrypoint)| (route_shape(pathname), (pathname, entrypoint)))
+        .collect::<HashMap<_, _>>();
+    for pathname in entrypoints.keys() {
+        let message = match get_intercepted_path(pathname) {
+            Ok(None) => continue,
+            Ok(Some(intercepted_path)) => {
+                match routes_by_shape.get(&route_shape(&intercepted_path)) {
+                    Some((route, Entrypoint::AppRoute { .. })) => format!(
+                        "The interception route {pathname} intercepts the route handler at \
+                         {route}. Only pages can be intercepted."
+                    ),
+                    Some((page, Entrypoint::AppPage { .. })) if **page != intercepted_path => {
+                        format!(
+                            "The interception route {pathname} intercepts the page at {page}, but \
+                             names its dynamic segments differently. Use the same names as the \
+                             intercepted page."
+                        )
+                    }
+                    _ => continue,
+                }
+            }
+            Err(err) => err.to_string(),
+        };
+        DirectoryTreeIssue {
+            app_dir,
+            message: Vc::cell(message),
+            severity: options
+                .await?
+                .issue_severity(AppStructureIssue::InvalidInterceptionRoute)
+                .cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+/// Returns a route path with the names of its dynamic segments removed, so
+/// that `/photo/[id]` and `/photo/[slug]` have the same shape.
+fn route_shape(pathname: &str) -> String {
+    pathname
+        .split('/')
+        .map(|segment| match parse_dynamic_segment(segment) {
+            Some(DynamicParam {
+                kind: DynamicParamKind::Dynamic,
+                ..
+            }) => "[]",
+            Some(DynamicParam {
+                kind: DynamicParamKind::CatchAll,
+                ..
+            }) => "[...]",
+            Some(DynamicParam {
+                kind: DynamicParamKind::OptionalCatchAll,
+                ..
+            }) => "[[...]]",
+            None => segment,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Whether the `children` of every segment of the loader tree lead to a page
+/// or default, i.e. whether its URL renders anything.
+async fn renders_children(mut tree: Vc<LoaderTree>) -> Result<bool> {
+    loop {
+        let tree_value = tree.await?;
+        if tree_value.parallel_routes.is_empty() {
+            return Ok(true);
+        }
+        let Some(&children) = tree_value.parallel_routes.get("children") else {
+            return Ok(false);
+        };
+        tree = children;
+    }
+}
+
+/// Whether a layout is rendered above the page of the loader tree, or `None`
+/// for trees without a page (e.g. the root not-found page).
+#[async_recursion]
+async fn page_has_layout(tree: Vc<LoaderTree>, has_layout: bool) -> Result<Option<bool>> {
+    let tree = tree.await?;
+    let components = tree.components.await?;
+    let has_layout = has_layout || components.layout.is_some();
+    if components.page.is_some() {
+        return Ok(Some(has_layout));
+    }
+    for &child in tree.parallel_routes.values() {
+        if let Some(has_layout) = page_has_layout(child, has_layout).await? {
+            return Ok(Some(has_layout));
+        }
+    }
+    Ok(None)
+}
+
+/// The routes contained in each route group of the app directory, keyed by
+/// the group's directory name (e.g. `(marketing)`). Groups with the same name
+/// in different places are merged.
+#[turbo_tasks::value(transparent)]
+pub struct RouteGroups(BTreeMap<String, Vec<String>>);
+
+/// Returns the pages and route handlers within each route group. Routes inside
+/// nested route groups are attributed to every enclosing group.
+#[turbo_tasks::function]
+pub async fn get_route_groups(
+    app_dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<RouteGroups>> {
+    let directory_tree = get_directory_tree(app_dir, page_extensions, options);
+    let mut route_groups = BTreeMap::new();
+    collect_route_groups(directory_tree, "/", &mut Vec::new(), &mut route_groups).await?;
+    Ok(Vc::cell(route_groups))
+}
+
+#[async_recursion]
+async fn collect_route_groups(
+    directory_tree: Vc<DirectoryTree>,
+    path_prefix: &str,
+    groups: &mut Vec<String>,
+    route_groups: &mut BTreeMap<String, Vec<String>>,
+) -> Result<()> {
+    let directory_tree = directory_tree.await?;
+    let components = directory_tree.components.await?;
+    if components.page.is_some() || components.route.is_some() {
+        for group in groups.iter() {
+            let routes: &mut Vec<String> = route_groups.entry(group.clone()).or_default();
+            if !routes.iter().any(|route| route == path_prefix) {
+                routes.push(path_prefix.to_string());
+            }
+        }
+    }
+
+    for (subdir_name, &subdirectory) in directory_tree.subdirectories.iter() {
+        if is_route_group(subdir_name) {
+            route_groups.entry(subdir_name.clone()).or_default();
+            groups.push(subdir_name.clone());
+            collect_route_groups(subdirectory, path_prefix, groups, route_groups).await?;
+            groups.pop();
+        } else if is_parallel_route(subdir_name) {
+            collect_route_groups(subdirectory, path_prefix, groups, route_groups).await?;
+        } else {
+            let path = if path_prefix == "/" {
+                format!("/{subdir_name}")
+            } else {
+                format!("{path_prefix}/{subdir_name}")
+            };
+            collect_route_groups(subdirectory, &path, groups, route_groups).await?;
+        }
+    }
+    Ok(())
+}
+
+/// A `layout` file of the app directory with the route segment it applies to.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, ValueDebugFormat, Debug)]
+pub struct Layout {
+    pub path: Vc<FileSystemPath>,
+    /// The path of the layout's directory in the app directory, including
+    /// route groups and parallel route slots (e.g. `/(shop)/@modal/cart`).
+    pub segment_path: String,
+    /// The pathname of the routes rendered inside the layout (e.g. `/cart`).
+    pub pathname: String,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct Layouts(Vec<Layout>);
+
+/// Returns all layouts of the app directory, parents before their children,
+/// e.g. to visualize the layout hierarchy. This includes the layouts of route
+/// groups and parallel route slots.
+#[turbo_tasks::function]
+pub async fn get_layouts(
+    app_dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Layouts>> {
+    let directory_tree = get_directory_tree(app_dir, page_extensions, options);
+    let mut layouts = Vec::new();
+    collect_layouts(directory_tree, "/", "/", &mut layouts).await?;
+    Ok(Vc::cell(layouts))
+}
+
+#[async_recursion]
+async fn collect_layouts(
+    directory_tree: Vc<DirectoryTree>,
+    segment_path: &str,
+    pathname: &str,
+    layouts: &mut Vec<Layout>,
+) -> Result<()> {
+    let directory_tree = directory_tree.await?;
+    if let Some(layout) = directory_tree.components.await?.layout {
+        layouts.push(Layout {
+            path: layout,
+            segment_path: segment_path.to_string(),
+            pathname: pathname.to_string(),
+        });
+    }
+
+    let join = |prefix: &str, name: &str| {
+        if prefix == "/" {
+            format!("/{name}")
+        } else {
+            format!("{prefix}/{name}")
+        }
+    };
+    for (subdir_name, &subdirectory) in directory_tree.subdirectories.iter() {
+        let subdir_pathname = if is_route_group(subdir_name) || is_parallel_route(subdir_name) {
+            pathname.to_string()
+        } else {
+            join(pathname, subdir_name)
+        };
+        collect_layouts(
+            subdirectory,
+            &join(segment_path, subdir_name),
+            &subdir_pathname,
+            layouts,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Emits an issue for each parallel route slot nested directly inside another
+/// slot (e.g. `@team/@modal`). A slot doesn't add a segment of its own, so a
+/// slot inside it has no layout it could be rendered into. Slots have to be
+/// siblings, or be nested below a regular segment of another slot.
+#[turbo_tasks::function]
+async fn validate_parallel_routes(
+    app_dir: Vc<FileSystemPath>,
+    path: String,
+    directory_tree: Vc<DirectoryTree>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    let directory_name = path.rsplit('/').next().unwrap_or_default();
+    let mut children = Vec::new();
+    for (subdir_name, &subdirectory) in directory_tree.await?.subdirectories.iter() {
+        let subdir_path = if path.is_empty() {
+            subdir_name.clone()
+        } else {
+            format!("{path}/{subdir_name}")
+        };
+        if let (Some(parent_slot), Some(slot)) = (
+            match_parallel_route(directory_name),
+            match_parallel_route(subdir_name),
+        ) {
+            DirectoryTreeIssue {
+                app_dir,
+                message: Vc::cell(format!(
+                    "The parallel route slot @{slot} at {subdir_path} is nested directly inside \
+                     the slot @{parent_slot} and can't be resolved. Parallel route slots must be \
+                     siblings."
+                )),
+                severity: options
+                    .await?
+                    .issue_severity(AppStructureIssue::NestedParallelRouteSlot)
+                    .cell(),
+            }
+            .cell()
+            .emit();
+            continue;
+        }
+        children.push(validate_parallel_routes(
+            app_dir,
+            subdir_path,
+            subdirectory,
+            options,
+        ));
+    }
+    Ok(Vc::<Completions>::cell(children).completed())
+}
+
+/// Emits an issue for each configured metadata extension which isn't
+/// supported. These extensions are ignored when scanning the app directory.
+#[turbo_tasks::function]
+async fn validate_metadata_extensions(
+    app_dir: Vc<FileSystemPath>,
+    options: Vc<AppStructureOptions>,
+) -> Result<Vc<Completion>> {
+    for (metadata_type, exts) in &options.await?.metadata_extensions {
+        for ext in exts {
+            if !is_supported_metadata_extension(metadata_type, ext) {
+                DirectoryTreeIssue {
+                    app_dir,
+                    message: Vc::cell(format!(
+                        "The extension \"{ext}\" is not supported for {metadata_type} metadata \
+                         files and will be ignored"
+                    )),
+                    severity: options
+                        .await?
+                        .issue_severity(AppStructureIssue::UnsupportedMetadataExtension)
+                        .cell(),
+                }
+                .cell()
+                .emit();
+            }
+        }
+    }
+    Ok(Completion::new())
+}
+
+/// An optional layout file, see [get_nearest_layout].
+#[turbo_tasks::value(transparent)]
+pub struct OptionLayout(Option<Vc<FileSystemPath>>);
+
+/// Returns the closest `layout` file responsible for the route at `path`
+/// (e.g. `/blog/[slug]`). Route groups don't add URL segments, but their
+/// layouts are taken into account. Returns `None` when there is no page or
+/// route handler at `path`.
+#[turbo_tasks::function]
+pub async fn get_nearest_layout(
+    app_dir: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
+    path: String,
+) -> Result<Vc<OptionLayout>> {
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.to_string())
+        .collect::<Vec<_>>();
+    let directory_tree = get_directory_tree(app_dir, page_extensions, options);
+    Ok(Vc::cell(
+        find_nearest_layout(directory_tree, &segments, None)
+            .await?
+            .flatten(),
+    ))
+}
+
+/// Looks up the route at `segments` in `tree`. Returns `None` if there is no
+/// such route, otherwise the nearest layout on the way to it.
+#[async_recursion]
+async fn find_nearest_layout(
+    tree: Vc<DirectoryTree>,
+    segments: &[String],
+    parent_layout: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Option<Vc<FileSystemPath>>>> {
+    let DirectoryTree {
+        subdirectories,
+        components,
+    } = &*tree.await?;
+    let components = components.await?;
+    let layout = components.layout.or(parent_layout);
+
+    if let Some((segment, rest)) = segments.split_first() {
+        if let Some(&subtree) = subdirectories.get(segment) {
+            if let Some(layout) = find_nearest_layout(subtree, rest, layout).await? {
+                return Ok(Some(layout));
+            }
+        }
+    } else if components.page.is_some() || components.route.is_some() {
+        return Ok(Some(layout));
+    }
+
+    for (name, &subtree) in subdirectories {
+        if is_route_group(name) {
+            if let Some(layout) = find_nearest_layout(subtree, segments, layout).await? {
+                return Ok(Some(layout));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+#[turbo_tasks::function]
+fn directory_tree_to_entrypoints(
+    app_dir: Vc<FileSystemPath>,
+    directory_tree: Vc<DirectoryTree>,
+    options: Vc<AppStructureOptions>,
+) -> Vc<Entrypoints> {
+    directory_tree_to_entrypoints_internal(
+        app_dir,
+        "".to_string(),
         directory_tree,
         "/".to_string(),
         "/".to_string(),
+        false,
+        options,
     )
 }
 
@@ -649,19 +2607,43 @@ async fn directory_tree_to_entrypoints_internal(
     directory_tree: Vc<DirectoryTree>,
     path_prefix: String,
     original_name_prefix: String,
+    // Whether a parent directory already defines the root layout.
+    has_parent_layout: bool,
+    options: Vc<AppStructureOptions>,
 ) -> Result<Vc<Entrypoints>> {
     let mut result = IndexMap::new();
 
     let directory_tree = &*directory_tree.await?;
 
     let subdirectories = &directory_tree.subdirectories;
-    let components = directory_tree.components.await?;
+    let mut components = (*directory_tree.components.await?).clone();
+    // Like in Next.js, `global-error` is only honored at the root, where it
+    // wraps the root layout.
+    if path_prefix != "/" {
+        components.global_error = None;
+    }
 
     let current_level_is_parallel_route = is_parallel_route(&directory_name);
 
-    if let Some(page) = components.page {
+    // A leaf segment with only a layout gets the configured default page, so
+    // it can be rendered.
+    let is_leaf = subdirectories
+        .keys()
+        .all(|subdir_name| is_parallel_route(subdir_name));
+    let default_page = if is_leaf
+        && !current_level_is_parallel_route
+        && components.layout.is_some()
+        && components.route.is_none()
+    {
+        options.await?.default_page
+    } else {
+        None
+    };
+
+    if let Some(page) = components.page.or(default_page) {
         add_app_page(
             app_dir,
+            options,
             &mut result,
             path_prefix.to_string(),
             original_name_prefix.to_string(),
@@ -702,6 +2684,7 @@ async fn directory_tree_to_entrypoints_internal(
     if let Some(default) = components.default {
         add_app_page(
             app_dir,
+            options,
             &mut result,
             path_prefix.to_string(),
             original_name_prefix.to_string(),
@@ -742,6 +2725,7 @@ async fn directory_tree_to_entrypoints_internal(
     if let Some(route) = components.route {
         add_app_route(
             app_dir,
+            options,
             &mut result,
             path_prefix.to_string(),
             original_name_prefix.to_string(),
@@ -753,46 +2737,116 @@ async fn directory_tree_to_entrypoints_internal(
     if path_prefix == "/" {
         // Next.js has this logic in "collect-app-paths", where the root not-found page
         // is considered as its own entry point.
-        if let Some(_not_found) = components.not_found {
-            let tree = LoaderTree {
-                segment: directory_name.to_string(),
-                parallel_routes: indexmap! {
-                    "children".to_string() => LoaderTree {
-                        segment: "__DEFAULT__".to_string(),
-                        parallel_routes: IndexMap::new(),
-                        components: Components {
-                            default: Some(get_next_package(app_dir).join("dist/client/components/parallel-route-default.js".to_string())),
-                            ..Default::default()
-                        }
-                        .cell(),
+        //
+        // With multiple root layouts, every route group with its own root layout can
+        // define a not-found page as well. Those are keyed by the route group. A
+        // layout in a route group below the root layout is not a root layout.
+        let is_root = directory_name.is_empty();
+        let is_root_layout_group =
+            is_route_group(&directory_name) && components.layout.is_some() && !has_parent_layout;
+        if components.not_found.is_some() && (is_root || is_root_layout_group) {
+            let default_tree = |default: Vc<FileSystemPath>| {
+                LoaderTree {
+                    segment: "__DEFAULT__".to_string(),
+                    parallel_routes: IndexMap::new(),
+                    components: Components {
+                        default: Some(default),
+                        ..Default::default()
                     }
                     .cell(),
-                },
+                }
+                .cell()
+            };
+            // A `default` next to the root layout takes precedence over the
+            // default of the package.
+            let root_default = match components.default {
+                Some(default) => default,
+                None => parallel_route_default(app_dir, options).await?,
+            };
+            let mut parallel_routes = indexmap! {
+                "children".to_string() => default_tree(root_default),
+            };
+            // Every root slot needs to be wired up as well, using its own
+            // default when it has one.
+            for (subdir_name, &subdirectory) in subdirectories.iter() {
+                if let Some(key) = match_parallel_route(subdir_name) {
+                    let slot_default = subdirectory
+                        .await?
+                        .components
+                        .await?
+                        .default
+                        .unwrap_or(root_default);
+                    parallel_routes.insert(key.to_string(), default_tree(slot_default));
+                }
+            }
+            let tree = LoaderTree {
+                segment: directory_name.to_string(),
+                parallel_routes,
                 components: components.without_leafs().cell(),
             }
             .cell();
-            add_app_page(
-                app_dir,
-                &mut result,
-                "/not-found".to_string(),
-                "/not-found".to_string(),
-                tree,
-            )
-            .await?;
-            add_app_page(
-                app_dir,
-                &mut result,
-                "/_not-found".to_string(),
-                "/_not-found".to_string(),
-                tree,
-            )
-            .await?;
+            if is_root {
+                add_app_page(
+                    app_dir,
+                    options,
+                    &mut result,
+                    "/not-found".to_string(),
+                    "/not-found".to_string(),
+                    tree,
+                )
+                .await?;
+            }
+            let key = if is_root {
+                "/_not-found".to_string()
+            } else {
+                format!("/{directory_name}/_not-found")
+            };
+            add_app_page(app_dir, options, &mut result, key.clone(), key, tree).await?;
         }
     }
 
     for (subdir_name, &subdirectory) in subdirectories.iter() {
-        let is_route_group = subdir_name.starts_with('(') && subdir_name.ends_with(')');
+        let is_route_group = is_route_group(subdir_name);
         let parallel_route_key = match_parallel_route(subdir_name);
+        if let Some(name) = duplicate_dynamic_param(&original_name_prefix, subdir_name) {
+            DirectoryTreeIssue {
+                app_dir,
+                message: Vc::cell(format!(
+                    "You cannot have the same slug name \"{name}\" repeat within a single dynamic \
+                     path, found {subdir_name} below {original_name_prefix}"
+                )),
+                severity: options
+                    .await?
+                    .issue_severity(AppStructureIssue::DuplicateDynamicParam)
+                    .cell(),
+            }
+            .cell()
+            .emit();
+        }
+        // Unlike `pages/blog/index.tsx`, `app/blog/index/page.tsx` is served at
+        // `/blog/index`, matching Next.js. Warn when this leaves the parent
+        // route without a page, as it was most likely meant to be served there.
+        if subdir_name == "index"
+            && components.page.is_none()
+            && subdirectory.await?.components.await?.page.is_some()
+        {
+            let index_route = format!("{}/index", path_prefix.trim_end_matches('/'));
+            let parent_route = &path_prefix;
+            DirectoryTreeIssue {
+                app_dir,
+                message: Vc::cell(format!(
+                    "The page in {index_route} is served at {index_route}, not at {parent_route}, \
+                     as \"index\" is a literal segment in the app directory. Move it to the \
+                     parent directory to serve it at {parent_route}."
+                )),
+                severity: options
+                    .await?
+                    .issue_severity(AppStructureIssue::LiteralIndexSegment)
+                    .cell(),
+            }
+            .cell()
+            .emit();
+        }
         let map = directory_tree_to_entrypoints_internal(
             app_dir,
             subdir_name.to_string(),
@@ -811,6 +2865,8 @@ async fn directory_tree_to_entrypoints_internal(
             } else {
                 format!("{path_prefix}/{subdir_name}")
             },
+            has_parent_layout || components.layout.is_some(),
+            options,
         )
         .await?;
         for (full_path, entrypoint) in map.iter() {
@@ -819,9 +2875,38 @@ async fn directory_tree_to_entrypoints_internal(
                     ref original_name,
                     loader_tree,
                 } => {
+                    // Root not-found pages of different route groups are keyed by their
+                    // group, but they all serve the same path.
+                    let existing_not_found = full_path
+                        .ends_with("/_not-found")
+                        .then(|| without_route_groups(full_path))
+                        .and_then(|pathname| {
+                            result.keys().find(|existing| {
+                                existing.ends_with("/_not-found")
+                                    && without_route_groups(existing) == pathname
+                            })
+                        });
+                    if let Some(existing) = existing_not_found {
+                        DirectoryTreeIssue {
+                            app_dir,
+                            message: Vc::cell(format!(
+                                "Multiple route groups define a root not-found page at {pathname} \
+                                 ({existing} and {full_path}), only {existing} is used",
+                                pathname = without_route_groups(full_path),
+                            )),
+                            severity: options
+                                .await?
+                                .issue_severity(AppStructureIssue::DuplicateRootNotFound)
+                                .cell(),
+                        }
+                        .cell()
+                        .emit();
+                        continue;
+                    }
                     if current_level_is_parallel_route {
                         add_app_page(
                             app_dir,
+                            options,
                             &mut result,
                             full_path.clone(),
                             original_name.clone(),
@@ -830,16 +2915,47 @@ async fn directory_tree_to_entrypoints_internal(
                         .await?;
                     } else {
                         let key = parallel_route_key.unwrap_or("children").to_string();
+                        let mut parent_components = components.without_leafs();
+                        if has_route_group_favicon(loader_tree).await? {
+                            parent_components.metadata.favicon.clear();
+                        }
+                        let mut parallel_routes = indexmap! {
+                            key => loader_tree,
+                        };
+                        // An intercepting route in a slot (e.g.
+                        // `@modal/(..)photo`) renders the `default` of the
+                        // `children` next to it, as there's no page at its
+                        // own path.
+                        if parallel_route_key.is_some()
+                            && !matches!(get_intercepted_path(full_path), Ok(None))
+                        {
+                            let default = match components.default {
+                                Some(default) => default,
+                                None => parallel_route_default(app_dir, options).await?,
+                            };
+                            parallel_routes.insert(
+                                "children".to_string(),
+                                LoaderTree {
+                                    segment: "__DEFAULT__".to_string(),
+                                    parallel_routes: IndexMap::new(),
+                                    components: Components {
+                                        default: Some(default),
+                                        ..Default::default()
+                                    }
+                                    .cell(),
+                                }
+                                .cell(),
+                            );
+                        }
                         let child_loader_tree = LoaderTree {
                             segment: directory_name.to_string(),
-                            parallel_routes: indexmap! {
-                                key => loader_tree,
-                            },
-                            components: components.without_leafs().cell(),
+                            parallel_routes,
+                            components: parent_components.cell(),
                         }
                         .cell();
                         add_app_page(
                             app_dir,
+                            options,
                             &mut result,
                             full_path.clone(),
                             original_name.clone(),
@@ -854,6 +2970,7 @@ async fn directory_tree_to_entrypoints_internal(
                 } => {
                     add_app_route(
                         app_dir,
+                        options,
                         &mut result,
                         full_path.clone(),
                         original_name.clone(),
@@ -867,6 +2984,83 @@ async fn directory_tree_to_entrypoints_internal(
     Ok(Vc::cell(result))
 }
 
+/// Whether a route group on the `children` path of the loader tree has its own
+/// favicon, which then replaces the favicons of all parent segments.
+#[async_recursion]
+async fn has_route_group_favicon(loader_tree: Vc<LoaderTree>) -> Result<bool> {
+    let loader_tree = loader_tree.await?;
+    let segment = &loader_tree.segment;
+    if is_route_group(segment) && !loader_tree.components.await?.metadata.favicon.is_empty() {
+        return Ok(true);
+    }
+    match loader_tree.parallel_routes.get("children") {
+        Some(&children) => has_route_group_favicon(children).await,
+        None => Ok(false),
+    }
+}
+
+/// The kind of a dynamic route segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+pub enum DynamicParamKind {
+    /// `[param]`
+    Dynamic,
+    /// `[...param]`
+    CatchAll,
+    /// `[[...param]]`
+    OptionalCatchAll,
+}
+
+/// A dynamic parameter of a route.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+pub struct DynamicParam {
+    pub name: String,
+    pub kind: DynamicParamKind,
+}
+
+/// Returns the dynamic parameters of a route path (e.g. `/shop/[cat]/[id]`)
+/// in order of appearance. Route groups and parallel route segments are
+/// skipped, so both pathnames and original names of entrypoints are accepted.
+pub fn get_dynamic_params(path: &str) -> Vec<DynamicParam> {
+    path.split('/')
+        .filter(|segment| !is_parallel_route(segment) && !is_route_group(segment))
+        .filter_map(parse_dynamic_segment)
+        .collect()
+}
+
+pub(crate) fn parse_dynamic_segment(segment: &str) -> Option<DynamicParam> {
+    let (name, kind) = if let Some(name) = segment
+        .strip_prefix("[[...")
+        .and_then(|s| s.strip_suffix("]]"))
+    {
+        (name, DynamicParamKind::OptionalCatchAll)
+    } else if let Some(name) = segment
+        .strip_prefix("[...")
+        .and_then(|s| s.strip_suffix(']'))
+    {
+        (name, DynamicParamKind::CatchAll)
+    } else {
+        (
+            segment.strip_prefix('[')?.strip_suffix(']')?,
+            DynamicParamKind::Dynamic,
+        )
+    };
+    Some(DynamicParam {
+        name: name.to_string(),
+        kind,
+    })
+}
+
+/// Returns the name of the dynamic parameter of `segment` if a parent segment
+/// in `path` already uses that name, regardless of whether either of them is a
+/// catch-all segment (e.g. `[id]` below `/[...id]/x`).
+fn duplicate_dynamic_param(path: &str, segment: &str) -> Option<String> {
+    let param = parse_dynamic_segment(segment)?;
+    get_dynamic_params(path)
+        .into_iter()
+        .any(|parent| parent.name == param.name)
+        .then_some(param.name)
+}
+
 /// ref: https://github.com/vercel/next.js/blob/c390c1662bc79e12cf7c037dcb382ef5ead6e492/packages/next/src/build/entries.ts#L119
 /// if path contains %5F, replace it with _.
 fn get_underscore_normalized_path(path: &str) -> String {
@@ -878,15 +3072,49 @@ fn get_underscore_normalized_path(path: &str) -> String {
 pub async fn get_global_metadata(
     app_dir: Vc<FileSystemPath>,
     page_extensions: Vc<Vec<String>>,
+    options: Vc<AppStructureOptions>,
 ) -> Result<Vc<GlobalMetadata>> {
+    let mut metadata = GlobalMetadata::default();
+    let options = options.await?;
+    if options.disable_metadata_files {
+        return Ok(metadata.cell());
+    }
+    let page_extensions = page_extensions.await?;
+
     let DirectoryContent::Entries(entries) = &*app_dir.read_dir().await? else {
         bail!("app_dir must be a directory")
     };
-    let mut metadata = GlobalMetadata::default();
 
+    let visited_dirs = [app_dir.await?.path.clone()];
+    let mut root_icon: Option<(i32, MetadataItem)> = None;
     for (basename, entry) in entries {
-        if let DirectoryEntry::File(file) = *entry {
-            if let Some((stem, ext)) = basename.split_once('.') {
+        let entry = match *entry {
+            DirectoryEntry::Symlink(link) => {
+                match resolve_symlink(link, &visited_dirs, &options).await? {
+                    Some(entry) => entry,
+                    None => continue,
+                }
+            }
+            _ => entry.clone(),
+        };
+        if let DirectoryEntry::File(file) = entry {
+            if let Some(("icon", num, dynamic)) =
+                match_metadata_file(basename, &page_extensions, &options.metadata_extensions)
+            {
+                // Icons are ordered by their number, like in the metadata of
+                // the root segment.
+                if root_icon.map_or(true, |(root_num, _)| num < root_num) {
+                    root_icon = Some((
+                        num,
+                        if dynamic {
+                            MetadataItem::Dynamic { path: file }
+                        } else {
+                            MetadataItem::Static { path: file }
+                        },
+                    ));
+                }
+            }
+            if let Some((stem, ext)) = split_extension(basename, &page_extensions) {
                 let list = match stem {
                     "favicon" => Some(&mut metadata.favicon),
                     "sitemap" => Some(&mut metadata.sitemap),
@@ -894,7 +3122,7 @@ pub async fn get_global_metadata(
                     _ => None,
                 };
                 if let Some(list) = list {
-                    if page_extensions.await?.iter().any(|e| e == ext) {
+                    if page_extensions.iter().any(|e| e == ext) {
                         *list = Some(MetadataItem::Dynamic { path: file });
                     }
                     if STATIC_GLOBAL_METADATA.get(stem).unwrap().contains(&ext) {
@@ -903,12 +3131,37 @@ pub async fn get_global_metadata(
                 }
             }
         }
-        // TODO(WEB-952) handle symlinks in app dir
     }
 
+    if metadata.favicon.is_none() && root_icon.is_none() {
+        let public_dir = match options.public_dir {
+            Some(public_dir) => public_dir,
+            None => default_public_dir(app_dir).await?,
+        };
+        let favicon = public_dir.join("favicon.ico".to_string());
+        if *favicon.get_type().await? == FileSystemEntryType::File {
+            metadata.favicon = Some(MetadataItem::Static { path: favicon });
+        }
+    }
+
+    // Having both is the expected setup, so it isn't reported.
+    metadata.site_icon = root_icon.map(|(_, icon)| icon).or(metadata.favicon);
+
     Ok(metadata.cell())
 }
 
+/// Returns the `public` directory of the project containing the app
+/// directory, which is either `<project>/app` or `<project>/src/app`.
+async fn default_public_dir(app_dir: Vc<FileSystemPath>) -> Result<Vc<FileSystemPath>> {
+    let parent = app_dir.parent();
+    let project_dir = if parent.await?.file_name() == "src" {
+        parent.parent()
+    } else {
+        parent
+    };
+    Ok(project_dir.join("public".to_string()))
+}
+
 #[turbo_tasks::value(shared)]
 struct DirectoryTreeIssue {
     pub severity: Vc<IssueSeverity>,
@@ -945,3 +3198,1659 @@ impl Issue for DirectoryTreeIssue {
         self.message
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, Result};
+    use indexmap::indexmap;
+    use turbo_tasks::{TryJoinIterExt, Vc};
+    use turbopack_binding::turbopack::core::issue::IssueSeverity;
+
+    use super::{
+        describe_loader_tree, duplicate_dynamic_param, find_app_dir, get_affected_entrypoints,
+        get_directory_tree, get_dynamic_params, get_entrypoint_for_path, get_entrypoints,
+        get_global_metadata, get_intercepted_path, get_layouts, get_nearest_layout,
+        get_route_groups, get_route_metadata, get_special_files, is_route_group,
+        is_undersized_open_graph_image, loader_tree_hash, match_interception_route,
+        match_metadata_file, split_extension, validate_parallel_routes, AppStructureIssue,
+        AppStructureOptions, DynamicParam, DynamicParamKind, Entrypoint, InterceptionMarker,
+        MetadataItem, MetadataWithAltItem, SpecialFileKind, DEFAULT_MAX_DIRECTORY_DEPTH,
+    };
+    use crate::{
+        next_config::{ExperimentalConfig, NextConfig},
+        test_util::{issues_of, with_app_fixture, with_memory_fs, Fixture},
+    };
+
+    #[test]
+    fn test_issue_severity_defaults() {
+        let options = AppStructureOptions::default();
+        assert_eq!(
+            options.issue_severity(AppStructureIssue::OrphanedAltText),
+            IssueSeverity::Warning
+        );
+        assert_eq!(
+            options.issue_severity(AppStructureIssue::ConflictingRoutes),
+            IssueSeverity::Error
+        );
+        for &issue in AppStructureIssue::ALL {
+            assert_eq!(AppStructureIssue::from_name(issue.name()), Some(issue));
+        }
+    }
+
+    #[test]
+    fn test_issue_severity_override() {
+        let options = AppStructureOptions {
+            issue_severity_overrides: indexmap! {
+                AppStructureIssue::OrphanedAltText => IssueSeverity::Error,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            options.issue_severity(AppStructureIssue::OrphanedAltText),
+            IssueSeverity::Error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issue_severity_config() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let next_config = NextConfig {
+                experimental: ExperimentalConfig {
+                    app_dir_issue_severity: Some(indexmap! {
+                        "unreachable-page".to_string() => "error".to_string(),
+                        "orphaned-alt-txt".to_string() => "error".to_string(),
+                        "orphaned-alt-text".to_string() => "fatal".to_string(),
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .cell();
+            let options = AppStructureOptions::from_next_config(next_config, root);
+
+            assert_eq!(
+                options.await?.issue_severity_overrides,
+                indexmap! { AppStructureIssue::UnreachablePage => IssueSeverity::Error }
+            );
+
+            let issues = issues_of(options).await?;
+            assert_eq!(issues.len(), 2);
+            assert!(issues
+                .iter()
+                .all(|issue| issue.severity == IssueSeverity::Error));
+            assert!(issues.iter().any(|issue| issue
+                .description
+                .contains("\"orphaned-alt-txt\" in experimental.appDirIssueSeverity is not")));
+            assert!(issues.iter().any(|issue| issue
+                .description
+                .contains("The severity \"fatal\" of \"orphaned-alt-text\"")));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_max_depth() {
+        assert_eq!(
+            AppStructureOptions::default().max_depth(),
+            DEFAULT_MAX_DIRECTORY_DEPTH
+        );
+        let options = AppStructureOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(options.max_depth(), 2);
+    }
+
+    #[test]
+    fn test_loader_tree_description_ignores_parallel_route_order() {
+        let children = (
+            "children".to_string(),
+            describe_loader_tree("__PAGE__", "", vec![]),
+        );
+        let modal = (
+            "modal".to_string(),
+            describe_loader_tree("__DEFAULT__", "", vec![]),
+        );
+        assert_eq!(
+            describe_loader_tree(
+                "",
+                "layout=[project]/app/layout.tsx;",
+                vec![children.clone(), modal.clone()]
+            ),
+            describe_loader_tree(
+                "",
+                "layout=[project]/app/layout.tsx;",
+                vec![modal, children]
+            )
+        );
+    }
+
+    #[test]
+    fn test_loader_tree_description_distinguishes_segments() {
+        assert_ne!(
+            describe_loader_tree("blog", "", vec![]),
+            describe_loader_tree("docs", "", vec![])
+        );
+    }
+
+    #[test]
+    fn test_match_metadata_file_with_extra_extensions() {
+        let page_extensions = vec!["tsx".to_string()];
+        assert_eq!(
+            match_metadata_file("apple-icon.gif", &page_extensions, &Default::default()),
+            None
+        );
+
+        let metadata_extensions = indexmap! {
+            "apple-icon".to_string() => vec!["gif".to_string()],
+            "favicon".to_string() => vec!["png".to_string()],
+        };
+        assert_eq!(
+            match_metadata_file("apple-icon.gif", &page_extensions, &metadata_extensions),
+            Some(("apple-icon", -1, false))
+        );
+        assert_eq!(
+            match_metadata_file("apple-icon.png", &page_extensions, &metadata_extensions),
+            Some(("apple-icon", -1, false))
+        );
+        // favicons only support `.ico`
+        assert_eq!(
+            match_metadata_file("favicon.png", &page_extensions, &metadata_extensions),
+            None
+        );
+    }
+
+    #[test]
+    fn test_match_metadata_file_order() {
+        let page_extensions = vec!["tsx".to_string()];
+        let order = |basename| {
+            match_metadata_file(basename, &page_extensions, &Default::default())
+                .map(|(stem, num, _)| (stem, num))
+        };
+        assert_eq!(order("icon.png"), Some(("icon", -1)));
+        assert_eq!(order("icon2.png"), Some(("icon", 2)));
+        assert_eq!(order("01-icon.png"), Some(("icon", 1)));
+        assert_eq!(order("2-apple-icon.png"), Some(("apple-icon", 2)));
+        assert_eq!(
+            order("10-opengraph-image.tsx"),
+            Some(("opengraph-image", 10))
+        );
+        // A leading order takes precedence over trailing digits.
+        assert_eq!(order("03-icon1.png"), Some(("icon", 3)));
+    }
+
+    #[test]
+    fn test_match_metadata_file_with_multiple_dots() {
+        let page_extensions = vec!["tsx".to_string()];
+        let matches = |basename| {
+            match_metadata_file(basename, &page_extensions, &Default::default())
+                .map(|(stem, num, _)| (stem, num))
+        };
+        assert_eq!(matches("icon.png"), Some(("icon", -1)));
+        assert_eq!(matches("icon2.png"), Some(("icon", 2)));
+        assert_eq!(matches("icon.dark.png"), Some(("icon", -1)));
+        assert_eq!(matches("icon2.dark.png"), Some(("icon", 2)));
+        assert_eq!(matches("opengraph-image.alt.txt"), None);
+        assert_eq!(matches("icon.tsx"), Some(("icon", -1)));
+        assert_eq!(matches("icon2.tsx"), Some(("icon", 2)));
+        assert_eq!(matches("icon.dark.tsx"), None);
+
+        // Page extensions can contain dots themselves.
+        let page_extensions = vec!["page.tsx".to_string(), "tsx".to_string()];
+        assert_eq!(
+            split_extension("layout.page.tsx", &page_extensions),
+            Some(("layout", "page.tsx"))
+        );
+        assert_eq!(
+            split_extension("icon.dark.png", &page_extensions),
+            Some(("icon.dark", "png"))
+        );
+    }
+
+    #[test]
+    fn test_undersized_open_graph_image() {
+        assert!(is_undersized_open_graph_image(114, 114));
+        assert!(is_undersized_open_graph_image(1200, 600));
+        assert!(!is_undersized_open_graph_image(1200, 630));
+        assert!(!is_undersized_open_graph_image(2400, 1260));
+    }
+
+    #[test]
+    fn test_dynamic_params() {
+        let param = |name: &str, kind| DynamicParam {
+            name: name.to_string(),
+            kind,
+        };
+        assert!(get_dynamic_params("/").is_empty());
+        assert_eq!(
+            get_dynamic_params("/shop/[cat]/[id]"),
+            vec![
+                param("cat", DynamicParamKind::Dynamic),
+                param("id", DynamicParamKind::Dynamic),
+            ]
+        );
+        assert_eq!(
+            get_dynamic_params("/(shop)/[cat]/@modal/items/[...slug]"),
+            vec![
+                param("cat", DynamicParamKind::Dynamic),
+                param("slug", DynamicParamKind::CatchAll),
+            ]
+        );
+        assert_eq!(
+            get_dynamic_params("/docs/[version]/[[...path]]/page"),
+            vec![
+                param("version", DynamicParamKind::Dynamic),
+                param("path", DynamicParamKind::OptionalCatchAll),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disable_metadata_files() -> Result<()> {
+        with_app_fixture(
+            [
+                "page.tsx",
+                "layout.tsx",
+                "icon.png",
+                "favicon.ico",
+                "robots.txt",
+                "manifest.ts",
+                "opengraph-image.alt.txt",
+            ],
+            |app_dir| async move {
+                let page_extensions = Vc::cell(vec!["tsx".to_string(), "ts".to_string()]);
+                let options = AppStructureOptions {
+                    disable_metadata_files: true,
+                    ..Default::default()
+                }
+                .cell();
+
+                let tree = get_directory_tree(app_dir, page_extensions, options).await?;
+                let components = tree.components.await?;
+                assert!(components.page.is_some());
+                assert!(components.layout.is_some());
+                assert!(components.metadata.is_empty());
+
+                let global_metadata =
+                    get_global_metadata(app_dir, page_extensions, options).await?;
+                assert!(global_metadata.is_empty());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_default_page() -> Result<()> {
+        with_app_fixture(
+            [
+                "index-redirect.tsx",
+                "app/layout.tsx",
+                "app/page.tsx",
+                "app/dashboard/layout.tsx",
+                "app/dashboard/@modal/default.tsx",
+            ],
+            |root| async move {
+                let app_dir = root.join("app".to_string());
+                let default_page = root.join("index-redirect.tsx".to_string());
+                let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+
+                let entrypoints = get_entrypoints(
+                    app_dir,
+                    page_extensions,
+                    AppStructureOptions::default().cell(),
+                )
+                .await?;
+                assert!(entrypoints.get("/dashboard").is_none());
+
+                let entrypoints = get_entrypoints(
+                    app_dir,
+                    page_extensions,
+                    AppStructureOptions {
+                        default_page: Some(default_page),
+                        ..Default::default()
+                    }
+                    .cell(),
+                )
+                .await?;
+                let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get("/dashboard")
+                else {
+                    panic!("expected a page at /dashboard");
+                };
+                // "" -> "dashboard" -> "__PAGE__"
+                let dashboard = loader_tree.await?.parallel_routes["children"].await?;
+                assert_eq!(dashboard.segment, "dashboard");
+                let page = dashboard.parallel_routes["children"].await?;
+                assert_eq!(page.segment, "__PAGE__");
+                assert_eq!(
+                    page.components.await?.page.unwrap().await?.path,
+                    "index-redirect.tsx"
+                );
+                // Segments with a page of their own keep it.
+                let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get("/") else {
+                    panic!("expected a page at /");
+                };
+                let page = loader_tree.await?.parallel_routes["children"].await?;
+                assert_eq!(
+                    page.components.await?.page.unwrap().await?.path,
+                    "app/page.tsx"
+                );
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_public_favicon_fallback() -> Result<()> {
+        with_app_fixture(
+            [
+                "src/app/layout.tsx",
+                "src/app/page.tsx",
+                "public/favicon.ico",
+                "static/favicon.ico",
+            ],
+            |root| async move {
+                let app_dir = root.join("src/app".to_string());
+                let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+
+                let favicon_path = |options: AppStructureOptions| async move {
+                    let metadata =
+                        get_global_metadata(app_dir, page_extensions, options.cell()).await?;
+                    let Some(MetadataItem::Static { path }) = metadata.favicon else {
+                        panic!("expected a static favicon");
+                    };
+                    anyhow::Ok(path.await?.path.clone())
+                };
+
+                assert_eq!(
+                    favicon_path(AppStructureOptions::default()).await?,
+                    "public/favicon.ico"
+                );
+                assert_eq!(
+                    favicon_path(AppStructureOptions {
+                        public_dir: Some(root.join("static".to_string())),
+                        ..Default::default()
+                    })
+                    .await?,
+                    "static/favicon.ico"
+                );
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_nested_parallel_route_slots() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "@team/page.tsx",
+                "@team/@modal/page.tsx",
+                "@analytics/page.tsx",
+                "@analytics/settings/layout.tsx",
+                "@analytics/settings/@tabs/page.tsx",
+            ],
+            |app_dir| async move {
+                let tree = get_directory_tree(
+                    app_dir,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+
+                let issues = issues_of(validate_parallel_routes(
+                    app_dir,
+                    String::new(),
+                    tree,
+                    AppStructureOptions::default().cell(),
+                ))
+                .await?;
+                // Slots below a regular segment of another slot are fine.
+                assert_eq!(issues.len(), 1);
+                assert!(issues[0].description.contains("@modal at @team/@modal"));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_route_groups() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "(marketing)/about/page.tsx",
+                "(marketing)/(legal)/terms/page.tsx",
+                "(shop)/cart/page.tsx",
+                "(shop)/cart/@summary/page.tsx",
+                "(shop)/api/cart/route.tsx",
+                "dashboard/page.tsx",
+            ],
+            |root| async move {
+                let route_groups = get_route_groups(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                )
+                .await?;
+
+                let routes = |group: &str| route_groups.get(group).cloned();
+                assert_eq!(
+                    routes("(marketing)"),
+                    Some(vec!["/terms".to_string(), "/about".to_string()])
+                );
+                assert_eq!(routes("(legal)"), Some(vec!["/terms".to_string()]));
+                assert_eq!(
+                    routes("(shop)"),
+                    Some(vec!["/api/cart".to_string(), "/cart".to_string()])
+                );
+                assert_eq!(route_groups.len(), 3);
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[test]
+    fn test_duplicate_dynamic_param() {
+        assert_eq!(
+            duplicate_dynamic_param("/[id]/x", "[id]"),
+            Some("id".to_string())
+        );
+        assert_eq!(
+            duplicate_dynamic_param("/[...slug]", "[slug]"),
+            Some("slug".to_string())
+        );
+        assert_eq!(
+            duplicate_dynamic_param("/(group)/[id]", "[[...id]]"),
+            Some("id".to_string())
+        );
+        assert_eq!(duplicate_dynamic_param("/[id]/x", "[slug]"), None);
+        assert_eq!(duplicate_dynamic_param("/[id]", "id"), None);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_dynamic_param_in_route() -> Result<()> {
+        with_app_fixture(
+            ["layout.tsx", "[id]/x/[id]/page.tsx", "[slug]/page.tsx"],
+            |root| async move {
+                let issues = issues_of(get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                ))
+                .await?;
+
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Error);
+                assert!(issues[0].description.contains("\"id\""));
+                assert!(issues[0].description.contains("/[id]/x"));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_literal_index_segment() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "blog/index/page.tsx",
+                "docs/page.tsx",
+                "docs/index/page.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+
+                // `index` is a literal segment, it isn't normalized to its parent.
+                let pathnames = entrypoints.await?.keys().cloned().collect::<Vec<_>>();
+                assert!(pathnames.contains(&"/blog/index".to_string()));
+                assert!(!pathnames.contains(&"/blog".to_string()));
+                assert!(pathnames.contains(&"/docs".to_string()));
+                assert!(pathnames.contains(&"/docs/index".to_string()));
+
+                // Only `blog/index` leaves its parent route without a page.
+                let issues = issues_of(entrypoints).await?;
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Warning);
+                assert!(issues[0]
+                    .description
+                    .contains("served at /blog/index, not at /blog"));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_page_without_root_layout() -> Result<()> {
+        with_app_fixture(
+            [
+                "(shop)/layout.tsx",
+                "(shop)/cart/page.tsx",
+                "(marketing)/about/page.tsx",
+            ],
+            |root| async move {
+                let issues = issues_of(get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                ))
+                .await?;
+
+                // `/cart` gets the root layout of its route group.
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Error);
+                assert!(issues[0]
+                    .description
+                    .contains("don't have a root layout: /about."));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_case_sensitive_routes() -> Result<()> {
+        with_app_fixture(
+            ["layout.tsx", "about/page.tsx", "About/page.tsx"],
+            |root| async move {
+                let entrypoints = |force_case_sensitive_routes| {
+                    get_entrypoints(
+                        root,
+                        Vc::cell(vec!["tsx".to_string()]),
+                        AppStructureOptions {
+                            force_case_sensitive_routes,
+                            ..Default::default()
+                        }
+                        .cell(),
+                    )
+                };
+
+                // Both routes are kept by default, the conflict is only a warning.
+                let detected = entrypoints(false);
+                let mut pathnames = detected.await?.keys().cloned().collect::<Vec<_>>();
+                pathnames.sort();
+                assert_eq!(pathnames, vec!["/About", "/about"]);
+                let issues = issues_of(detected).await?;
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Warning);
+                assert!(issues[0].description.contains("only differ in case"));
+
+                let case_sensitive = entrypoints(true);
+                let mut pathnames = case_sensitive.await?.keys().cloned().collect::<Vec<_>>();
+                pathnames.sort();
+                assert_eq!(pathnames, vec!["/About", "/about"]);
+                assert!(issues_of(case_sensitive).await?.is_empty());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_scan_concurrency() -> Result<()> {
+        let pages = ["a", "b", "c", "d", "e"].into_iter().flat_map(|section| {
+            ["x", "y", "z"]
+                .into_iter()
+                .map(move |page| format!("{section}/{page}/page.tsx"))
+        });
+        with_app_fixture(pages.chain(["layout.tsx".to_string()]), |root| async move {
+            let pathnames = |scan_concurrency| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions {
+                        scan_concurrency,
+                        ..Default::default()
+                    }
+                    .cell(),
+                )
+                .await?;
+                let mut pathnames = entrypoints.keys().cloned().collect::<Vec<_>>();
+                pathnames.sort();
+                anyhow::Ok(pathnames)
+            };
+
+            let unbounded = pathnames(None).await?;
+            assert_eq!(unbounded.len(), 15);
+            assert_eq!(pathnames(Some(2)).await?, unbounded);
+            assert_eq!(pathnames(Some(1)).await?, unbounded);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_oversized_metadata_file() -> Result<()> {
+        Fixture::new([
+            ("layout.tsx", vec![]),
+            ("page.tsx", vec![]),
+            ("icon.png", vec![0u8; 2048]),
+            ("apple-icon.png", vec![0u8; 512]),
+            // Not an image, which would be reported when validating images.
+            ("opengraph-image.png", vec![0u8; 16]),
+        ])?
+        .run(|root| async move {
+            let issues = issues_of(get_entrypoints(
+                root,
+                Vc::cell(vec!["tsx".to_string()]),
+                AppStructureOptions {
+                    validate_metadata_file_size: true,
+                    max_metadata_file_size: Some(1024),
+                    ..Default::default()
+                }
+                .cell(),
+            ))
+            .await?;
+
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].severity, IssueSeverity::Warning);
+            assert!(issues[0].description.contains("icon.png is 2048 bytes"));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_broken_open_graph_image() -> Result<()> {
+        Fixture::new([
+            ("layout.tsx", ""),
+            ("page.tsx", ""),
+            ("opengraph-image.png", "not an image"),
+        ])?
+        .run(|root| async move {
+            let entrypoints = get_entrypoints(
+                root,
+                Vc::cell(vec!["tsx".to_string()]),
+                AppStructureOptions {
+                    validate_metadata_images: true,
+                    // Without validating file sizes, the image isn't reported
+                    // as oversized.
+                    max_metadata_file_size: Some(1),
+                    ..Default::default()
+                }
+                .cell(),
+            );
+            assert!(entrypoints.await?.contains_key("/"));
+            let issues = issues_of(entrypoints).await?;
+
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].severity, IssueSeverity::Warning);
+            assert!(issues[0]
+                .description
+                .contains("opengraph-image.png couldn't be read"));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_root_default_for_not_found() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "not-found.tsx",
+                "default.tsx",
+                "@modal/page.tsx",
+                "@team/page.tsx",
+                "@team/default.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                )
+                .await?;
+
+                let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get("/_not-found")
+                else {
+                    panic!("expected a root not-found page");
+                };
+                let loader_tree = loader_tree.await?;
+                let default = |slot: &str| {
+                    let tree = loader_tree.parallel_routes[slot];
+                    async move {
+                        let tree = tree.await?;
+                        assert_eq!(tree.segment, "__DEFAULT__");
+                        Ok::<_, anyhow::Error>(
+                            tree.components.await?.default.unwrap().await?.path.clone(),
+                        )
+                    }
+                };
+                assert_eq!(default("children").await?, "default.tsx");
+                assert_eq!(default("modal").await?, "default.tsx");
+                // Slots with a default of their own keep it.
+                assert_eq!(default("team").await?, "@team/default.tsx");
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_custom_parallel_route_default() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "not-found.tsx",
+                "@modal/page.tsx",
+                "slot-default.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions {
+                        parallel_route_default: Some(root.join("slot-default.tsx".to_string())),
+                        ..Default::default()
+                    }
+                    .cell(),
+                )
+                .await?;
+
+                let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get("/_not-found")
+                else {
+                    panic!("expected a root not-found page");
+                };
+                let loader_tree = loader_tree.await?;
+                for slot in ["children", "modal"] {
+                    let tree = loader_tree.parallel_routes[slot].await?;
+                    let default = tree.components.await?.default.unwrap();
+                    assert_eq!(default.await?.path, "slot-default.tsx");
+                }
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_layouts() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "(shop)/layout.tsx",
+                "(shop)/cart/page.tsx",
+                "dashboard/layout.tsx",
+                "dashboard/page.tsx",
+                "dashboard/@analytics/layout.tsx",
+                "dashboard/@analytics/page.tsx",
+            ],
+            |root| async move {
+                let layouts = get_layouts(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                )
+                .await?;
+
+                let mut layouts = layouts
+                    .iter()
+                    .map(|layout| async move {
+                        Ok((
+                            layout.path.await?.path.clone(),
+                            layout.segment_path.clone(),
+                            layout.pathname.clone(),
+                        ))
+                    })
+                    .try_join()
+                    .await?;
+                layouts.sort();
+                let expected = [
+                    ("(shop)/layout.tsx", "/(shop)", "/"),
+                    (
+                        "dashboard/@analytics/layout.tsx",
+                        "/dashboard/@analytics",
+                        "/dashboard",
+                    ),
+                    ("dashboard/layout.tsx", "/dashboard", "/dashboard"),
+                    ("layout.tsx", "/", "/"),
+                ];
+                assert_eq!(
+                    layouts,
+                    expected.map(|(path, segment_path, pathname)| (
+                        path.to_string(),
+                        segment_path.to_string(),
+                        pathname.to_string()
+                    ))
+                );
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_nearest_layout() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "blog/page.tsx",
+                "(shop)/layout.tsx",
+                "(shop)/cart/checkout/page.tsx",
+                "(shop)/products/[id]/layout.tsx",
+                "(shop)/products/[id]/page.tsx",
+            ],
+            |root| async move {
+                let nearest_layout = |path: &str| {
+                    let layout = get_nearest_layout(
+                        root,
+                        Vc::cell(vec!["tsx".to_string()]),
+                        AppStructureOptions::default().cell(),
+                        path.to_string(),
+                    );
+                    async move {
+                        anyhow::Ok(match *layout.await? {
+                            Some(layout) => Some(layout.await?.path.clone()),
+                            None => None,
+                        })
+                    }
+                };
+
+                assert_eq!(nearest_layout("/").await?.as_deref(), Some("layout.tsx"));
+                assert_eq!(
+                    nearest_layout("/blog").await?.as_deref(),
+                    Some("layout.tsx")
+                );
+                // The route group's layout is used although the group isn't
+                // part of the path.
+                assert_eq!(
+                    nearest_layout("/cart/checkout").await?.as_deref(),
+                    Some("(shop)/layout.tsx")
+                );
+                assert_eq!(
+                    nearest_layout("/products/[id]").await?.as_deref(),
+                    Some("(shop)/products/[id]/layout.tsx")
+                );
+                // Directories without a page aren't routes.
+                assert_eq!(nearest_layout("/cart").await?, None);
+                assert_eq!(nearest_layout("/missing").await?, None);
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_default_metadata_dir() -> Result<()> {
+        with_app_fixture(
+            [
+                "app/layout.tsx",
+                "app/page.tsx",
+                "app/icon.png",
+                "app/about/page.tsx",
+                "app/blog/page.tsx",
+                "app/blog/opengraph-image.png",
+                "metadata/opengraph-image.png",
+                "metadata/icon.png",
+            ],
+            |root| async move {
+                // The directory is configured relative to the project directory.
+                let next_config = NextConfig {
+                    experimental: ExperimentalConfig {
+                        default_metadata_dir: Some("metadata".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+                .cell();
+                let entrypoints = get_entrypoints(
+                    root.join("app".to_string()),
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::from_next_config(next_config, root),
+                )
+                .await?;
+
+                let metadata_paths = |items: Vec<MetadataWithAltItem>| async move {
+                    items
+                        .into_iter()
+                        .map(|item| async move {
+                            let (MetadataWithAltItem::Static { path, .. }
+                            | MetadataWithAltItem::Dynamic { path }) = item;
+                            Ok(path.await?.path.clone())
+                        })
+                        .try_join()
+                        .await
+                };
+
+                for pathname in ["/about", "/blog"] {
+                    let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get(pathname)
+                    else {
+                        panic!("expected a page for {pathname}");
+                    };
+                    let root_metadata = loader_tree.await?.components.await?.metadata.clone();
+                    // The root's own icon takes precedence over the default one.
+                    assert_eq!(
+                        metadata_paths(root_metadata.icon).await?,
+                        vec!["app/icon.png".to_string()]
+                    );
+                    assert_eq!(
+                        metadata_paths(root_metadata.open_graph).await?,
+                        vec!["metadata/opengraph-image.png".to_string()]
+                    );
+                }
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_affected_entrypoints() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "dashboard/layout.tsx",
+                "dashboard/page.tsx",
+                "dashboard/settings/page.tsx",
+                "api/health/route.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+                let affected =
+                    |file: &str| get_affected_entrypoints(entrypoints, root.join(file.to_string()));
+
+                assert_eq!(
+                    *affected("dashboard/layout.tsx").await?,
+                    vec!["/dashboard".to_string(), "/dashboard/settings".to_string()]
+                );
+                assert_eq!(
+                    *affected("dashboard/settings/page.tsx").await?,
+                    vec!["/dashboard/settings".to_string()]
+                );
+                assert_eq!(
+                    *affected("api/health/route.tsx").await?,
+                    vec!["/api/health".to_string()]
+                );
+                assert!(affected("unrelated.tsx").await?.is_empty());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_parallel_route_default() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "@modal/default.tsx",
+                "@modal/(group)/page.tsx",
+            ],
+            |root| async move {
+                let issues = issues_of(get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                ))
+                .await?;
+
+                assert_eq!(issues.len(), 1);
+                assert!(issues[0]
+                    .description
+                    .contains("@modal/default.tsx conflicts with the page in segment \"(group)\""));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_site_icon_precedence() -> Result<()> {
+        with_app_fixture(
+            ["layout.tsx", "page.tsx", "favicon.ico", "icon.png"],
+            |root| async move {
+                let metadata = get_global_metadata(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+
+                let Some(MetadataItem::Static { path: site_icon }) = metadata.await?.site_icon
+                else {
+                    panic!("expected a static site icon");
+                };
+                assert_eq!(site_icon.await?.path, "icon.png");
+                assert!(metadata.await?.favicon.is_some());
+                assert!(issues_of(metadata).await?.is_empty());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_scan_underscore_directories() -> Result<()> {
+        with_app_fixture(
+            ["layout.tsx", "page.tsx", "_utils/page.tsx"],
+            |root| async move {
+                let entrypoints = |scan_underscore_directories| {
+                    get_entrypoints(
+                        root,
+                        Vc::cell(vec!["tsx".to_string()]),
+                        AppStructureOptions {
+                            scan_underscore_directories,
+                            ..Default::default()
+                        }
+                        .cell(),
+                    )
+                };
+
+                assert!(!entrypoints(false).await?.contains_key("/_utils"));
+                assert!(entrypoints(true).await?.contains_key("/_utils"));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_parallel_route_slots() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "@my_slot/page.tsx",
+                "@my%5Fslot/page.tsx",
+            ],
+            |root| async move {
+                let issues = issues_of(get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                ))
+                .await?;
+
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Error);
+                assert!(issues[0]
+                    .description
+                    .contains("resolve to the parallel route slot @my_slot"));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_special_files() -> Result<()> {
+        with_memory_fs(|_| async move {
+            let special_files = get_special_files(
+                Vc::cell(vec!["tsx".to_string(), "ts".to_string()]),
+                AppStructureOptions::default().cell(),
+            )
+            .await?;
+            let find = |name: &str| {
+                special_files
+                    .iter()
+                    .find(|file| file.name == name)
+                    .unwrap_or_else(|| panic!("{name} should be a special file"))
+            };
+
+            assert_eq!(find("page").kind, SpecialFileKind::Component);
+            assert_eq!(find("page").extensions, vec!["tsx", "ts"]);
+            assert_eq!(find("layout").kind, SpecialFileKind::Component);
+            assert_eq!(find("global-error").kind, SpecialFileKind::Component);
+            let open_graph_image = find("opengraph-image");
+            assert_eq!(open_graph_image.kind, SpecialFileKind::Metadata);
+            assert_eq!(
+                open_graph_image.extensions,
+                vec!["tsx", "ts", "jpg", "jpeg", "png", "gif"]
+            );
+            assert_eq!(find("robots").kind, SpecialFileKind::GlobalMetadata);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_route_metadata() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "favicon.ico",
+                "opengraph-image.png",
+                "blog/opengraph-image.png",
+                "blog/[slug]/page.tsx",
+            ],
+            |app_dir| async move {
+                let metadata = get_route_metadata(
+                    app_dir,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    "/blog/[slug]".to_string(),
+                    AppStructureOptions::default().cell(),
+                )
+                .await?;
+                let metadata = metadata.as_ref().expect("/blog/[slug] should be a page");
+
+                let path = |item: &MetadataWithAltItem| match *item {
+                    MetadataWithAltItem::Static { path, .. }
+                    | MetadataWithAltItem::Dynamic { path } => path,
+                };
+                assert_eq!(metadata.favicon.len(), 1);
+                assert_eq!(path(&metadata.favicon[0]).await?.path, "favicon.ico");
+                assert_eq!(metadata.open_graph.len(), 1);
+                assert_eq!(
+                    path(&metadata.open_graph[0]).await?.path,
+                    "blog/opengraph-image.png"
+                );
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_parallel_route_page() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "@modal/page.tsx",
+                "@modal/photo/page.tsx",
+                "@modal/login/page.tsx",
+                "login/page.tsx",
+            ],
+            |root| async move {
+                let issues = issues_of(get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                ))
+                .await?;
+
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Warning);
+                assert!(issues[0]
+                    .description
+                    .contains("can't be reached by their URL: /photo."));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_global_error() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "global-error.tsx",
+                "blog/page.tsx",
+                "blog/global-error.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                )
+                .await?;
+
+                let Some(Entrypoint::AppPage { loader_tree, .. }) =
+                    entrypoints.get("/blog").cloned()
+                else {
+                    panic!("/blog should be a page");
+                };
+                let root_tree = loader_tree.await?;
+                let global_error = root_tree.components.await?.global_error;
+                assert_eq!(
+                    global_error.unwrap().await?.path,
+                    root.join("global-error.tsx".to_string()).await?.path
+                );
+                let blog_tree = root_tree.parallel_routes["children"].await?;
+                assert_eq!(blog_tree.segment, "blog");
+                assert!(blog_tree.components.await?.global_error.is_none());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_route_group_not_found_pages() -> Result<()> {
+        with_app_fixture(
+            [
+                "(marketing)/layout.tsx",
+                "(marketing)/page.tsx",
+                "(marketing)/not-found.tsx",
+                "(shop)/layout.tsx",
+                "(shop)/cart/page.tsx",
+                "(shop)/not-found.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+
+                let not_found_pages = entrypoints
+                    .await?
+                    .keys()
+                    .filter(|pathname| pathname.ends_with("/_not-found"))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                assert_eq!(not_found_pages, vec!["/(marketing)/_not-found".to_string()]);
+
+                let issues = issues_of(entrypoints).await?;
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].severity, IssueSeverity::Error);
+                assert!(issues[0].description.contains(
+                    "Multiple route groups define a root not-found page at /_not-found \
+                     (/(marketing)/_not-found and /(shop)/_not-found)"
+                ));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_route_group_not_found_below_root_layout() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "not-found.tsx",
+                "(marketing)/layout.tsx",
+                "(marketing)/page.tsx",
+                "(marketing)/not-found.tsx",
+            ],
+            |root| async move {
+                let entrypoints = get_entrypoints(
+                    root,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+
+                let not_found_pages = entrypoints
+                    .await?
+                    .keys()
+                    .filter(|pathname| pathname.ends_with("/_not-found"))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                assert_eq!(not_found_pages, vec!["/_not-found".to_string()]);
+                assert!(issues_of(entrypoints).await?.is_empty());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_root_page_and_route() -> Result<()> {
+        with_app_fixture(["layout.tsx", "page.tsx", "route.ts"], |root| async move {
+            let issues = issues_of(get_entrypoints(
+                root,
+                Vc::cell(vec!["ts".to_string(), "tsx".to_string()]),
+                AppStructureOptions::default().cell(),
+            ))
+            .await?;
+
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].severity, IssueSeverity::Error);
+            assert!(issues[0]
+                .description
+                .contains("Conflicting page and route at the root /"));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_intercepted_path() -> Result<()> {
+        assert_eq!(
+            match_interception_route("(..)(..)feed"),
+            Some((InterceptionMarker::TwoLevelsUp, "feed"))
+        );
+        assert_eq!(
+            match_interception_route("(...)dashboard"),
+            Some((InterceptionMarker::Root, "dashboard"))
+        );
+        assert_eq!(match_interception_route("(marketing)"), None);
+        assert_eq!(match_interception_route("(.)"), None);
+        assert!(!is_route_group("(..)(photo)"));
+
+        assert_eq!(
+            get_intercepted_path("/feed/(.)photo/[id]")?.as_deref(),
+            Some("/feed/photo/[id]")
+        );
+        assert_eq!(
+            get_intercepted_path("/feed/(..)photo/[id]")?.as_deref(),
+            Some("/photo/[id]")
+        );
+        assert_eq!(
+            get_intercepted_path("/a/b/(..)(..)photo")?.as_deref(),
+            Some("/photo")
+        );
+        assert_eq!(
+            get_intercepted_path("/a/b/(...)photo")?.as_deref(),
+            Some("/photo")
+        );
+        assert_eq!(get_intercepted_path("/feed/photo")?, None);
+        assert!(get_intercepted_path("/(..)photo").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_interception_routes() -> Result<()> {
+        let files = [
+            "app/layout.tsx",
+            "app/photo/[id]/page.tsx",
+            "app/feed/page.tsx",
+            "app/feed/@modal/default.tsx",
+            "app/feed/@modal/(..)photo/[id]/page.tsx",
+            "conflicts/layout.tsx",
+            "conflicts/photo/[id]/page.tsx",
+            "conflicts/api/route.tsx",
+            "conflicts/@modal/(.)photo/[slug]/page.tsx",
+            "conflicts/@modal/(.)api/page.tsx",
+            "conflicts/@modal/(..)about/page.tsx",
+        ];
+        with_app_fixture(files, |root| async move {
+            let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+            let options = AppStructureOptions::default().cell();
+
+            let entrypoints =
+                get_entrypoints(root.join("app".to_string()), page_extensions, options);
+            let issues = issues_of(entrypoints).await?;
+            assert!(issues.is_empty());
+            let Some(Entrypoint::AppPage { loader_tree, .. }) =
+                entrypoints.await?.get("/feed/(..)photo/[id]").cloned()
+            else {
+                panic!("the interception route should be a page");
+            };
+            let feed = loader_tree.await?.parallel_routes["children"].await?;
+            assert_eq!(feed.segment, "feed");
+            assert_eq!(
+                feed.parallel_routes.keys().collect::<Vec<_>>(),
+                ["modal", "children"]
+            );
+            assert_eq!(feed.parallel_routes["modal"].await?.segment, "(..)photo");
+            assert_eq!(
+                feed.parallel_routes["children"].await?.segment,
+                "__DEFAULT__"
+            );
+
+            let issues = issues_of(get_entrypoints(
+                root.join("conflicts".to_string()),
+                page_extensions,
+                options,
+            ))
+            .await?;
+            let errors = issues
+                .iter()
+                .filter(|issue| issue.severity == IssueSeverity::Error)
+                .map(|issue| issue.description.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(errors.len(), 3);
+            for expected in [
+                "/(..)about intercepts a path above the root",
+                "/(.)api intercepts the route handler at /api",
+                "/(.)photo/[slug] intercepts the page at /photo/[id], but names its dynamic \
+                 segments differently",
+            ] {
+                assert!(errors.iter().any(|error| error.contains(expected)));
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlinks() -> Result<()> {
+        use std::{fs, os::unix::fs::symlink};
+
+        let fixture = Fixture::new(
+            [
+                "app/page.tsx",
+                "shared/layout.tsx",
+                "shared/robots.txt",
+                "shared/blog/page.tsx",
+            ]
+            .map(|file| (file, "")),
+        )?;
+        let dir = fixture.path();
+        symlink("../shared/layout.tsx", dir.join("app/layout.tsx"))?;
+        symlink("../shared/robots.txt", dir.join("app/robots.txt"))?;
+        symlink("../shared/blog", dir.join("app/blog"))?;
+        symlink(".", dir.join("app/loop"))?;
+        // Two directories linking to each other.
+        fs::create_dir_all(dir.join("app/a"))?;
+        fs::create_dir_all(dir.join("app/b"))?;
+        symlink("../b", dir.join("app/a/to-b"))?;
+        symlink("../a", dir.join("app/b/to-a"))?;
+
+        fixture
+            .run(|root| async move {
+                let app_dir = root.join("app".to_string());
+                let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+                let options = AppStructureOptions::default().cell();
+
+                let entrypoints = get_entrypoints(app_dir, page_extensions, options);
+                assert!(entrypoints.await?.contains_key("/"));
+                assert!(entrypoints.await?.contains_key("/blog"));
+                assert!(!entrypoints.await?.contains_key("/loop"));
+                let mut issues = issues_of(entrypoints).await?;
+                issues.sort_by(|a, b| a.description.cmp(&b.description));
+                // The root layout is found through the symlink. Each of the
+                // mutual links is reported when it's reached through the other.
+                assert_eq!(issues.len(), 3);
+                assert!(issues[0]
+                    .description
+                    .contains("/app/a/to-b points to itself"));
+                assert!(issues[1]
+                    .description
+                    .contains("/app/b/to-a points to itself"));
+                assert!(issues[2].description.contains("/app/loop points to itself"));
+
+                let global_metadata =
+                    get_global_metadata(app_dir, page_extensions, options).await?;
+                assert!(matches!(
+                    global_metadata.robots,
+                    Some(MetadataItem::Static { .. })
+                ));
+
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_metadata_manifest() -> Result<()> {
+        Fixture::new([
+            ("layout.tsx", ""),
+            ("page.tsx", ""),
+            ("manifest.json", "{ \"name\": \"App\", }"),
+            ("docs/page.tsx", ""),
+            (
+                "docs/manifest.webmanifest",
+                "{ \"display\": \"standalone\" }",
+            ),
+        ])?
+        .run(|root| async move {
+            let mut issues = issues_of(get_entrypoints(
+                root,
+                Vc::cell(vec!["tsx".to_string()]),
+                AppStructureOptions {
+                    validate_metadata_manifest: true,
+                    ..Default::default()
+                }
+                .cell(),
+            ))
+            .await?;
+            issues.sort_by_key(|issue| issue.severity);
+
+            assert_eq!(issues.len(), 2);
+            assert_eq!(issues[0].severity, IssueSeverity::Error);
+            assert!(issues[0]
+                .description
+                .contains("manifest.json isn't valid JSON"));
+            assert_eq!(issues[1].severity, IssueSeverity::Hint);
+            assert!(issues[1]
+                .description
+                .contains("neither a \"name\" nor a \"short_name\""));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_entrypoint_for_path() -> Result<()> {
+        with_app_fixture(
+            [
+                "layout.tsx",
+                "page.tsx",
+                "(marketing)/layout.tsx",
+                "(marketing)/about/page.tsx",
+                "blog/layout.tsx",
+                "blog/[slug]/page.tsx",
+                "blog/[slug]/@comments/page.tsx",
+                "dashboard/page.tsx",
+                "api/health/route.tsx",
+                "%5Fprivate/page.tsx",
+            ],
+            |root| async move {
+                let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+                let options = AppStructureOptions::default().cell();
+                let entrypoints = get_entrypoints(root, page_extensions, options).await?;
+
+                for path in ["/", "/about", "/blog/[slug]", "/api/health", "/_private"] {
+                    let entrypoint =
+                        get_entrypoint_for_path(root, page_extensions, path.to_string(), options)
+                            .await?;
+                    match (entrypoint.as_ref(), entrypoints.get(path)) {
+                        (
+                            Some(Entrypoint::AppPage {
+                                original_name,
+                                loader_tree,
+                            }),
+                            Some(Entrypoint::AppPage {
+                                original_name: expected_original_name,
+                                loader_tree: expected_loader_tree,
+                            }),
+                        ) => {
+                            assert_eq!(original_name, expected_original_name);
+                            assert_eq!(
+                                *loader_tree_hash(*loader_tree).await?,
+                                *loader_tree_hash(*expected_loader_tree).await?
+                            );
+                        }
+                        (
+                            Some(Entrypoint::AppRoute { path, .. }),
+                            Some(Entrypoint::AppRoute {
+                                path: expected_path,
+                                ..
+                            }),
+                        ) => {
+                            assert_eq!(*path.await?, *expected_path.await?);
+                        }
+                        _ => panic!("expected the same entrypoint for {path}"),
+                    }
+                }
+                assert!(get_entrypoint_for_path(
+                    root,
+                    page_extensions,
+                    "/blog".to_string(),
+                    options
+                )
+                .await?
+                .is_none());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_find_app_dir_through_workspace_symlink() -> Result<()> {
+        let fixture = Fixture::new([
+            ("packages/ui/app/layout.tsx", ""),
+            ("packages/ui/app/page.tsx", ""),
+            ("apps/web/package.json", "{}"),
+        ])?;
+        let root = fixture.path().canonicalize()?;
+        // Like a workspace linking the app directory of another package.
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("../../packages/ui/app", root.join("apps/web/app"))?;
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_dir(root.join("packages/ui/app"), root.join("apps/web/app"))?;
+
+        fixture
+            .run(|root| async move {
+                let app_dir = (*find_app_dir(root.join("apps/web".to_string())).await?)
+                    .context("expected an app directory")?;
+                assert_eq!(app_dir.await?.path, "packages/ui/app");
+
+                let entrypoints = get_entrypoints(
+                    app_dir,
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+                assert!(entrypoints.await?.contains_key("/"));
+
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+}
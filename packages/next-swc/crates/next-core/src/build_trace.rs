@@ -0,0 +1,154 @@
+use std::{future::Future, time::Instant};
+
+use anyhow::Result;
+use serde::Serialize;
+use turbo_tasks::{Completion, Vc};
+use turbo_tasks_fs::{FileContent, FileSystemPath};
+use turbopack_binding::turbopack::core::output::OutputAssets;
+
+/// A high-level breakdown of the time spent in the major phases of a build
+/// (e.g. reading the app structure, chunking and emitting), written as JSON
+/// for quick performance analysis without a trace viewer.
+///
+/// Phases are recorded in the order they ran. As turbo tasks are cached and
+/// run concurrently, a phase only includes the work which was awaited within
+/// it.
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTrace {
+    pub phases: Vec<BuildTracePhase>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTracePhase {
+    pub name: String,
+    pub duration_ms: u64,
+    /// The number of assets produced by the phase, for phases producing
+    /// assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_count: Option<usize>,
+}
+
+impl BuildTrace {
+    /// Runs `phase` and records its duration under `name`.
+    pub async fn record<T>(
+        &mut self,
+        name: &str,
+        phase: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let start = Instant::now();
+        let result = phase.await?;
+        self.phases.push(BuildTracePhase {
+            name: name.to_string(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            asset_count: None,
+        });
+        Ok(result)
+    }
+
+    /// Like [BuildTrace::record], but for a phase producing assets, which
+    /// are counted.
+    pub async fn record_assets(
+        &mut self,
+        name: &str,
+        phase: impl Future<Output = Result<Vc<OutputAssets>>>,
+    ) -> Result<Vc<OutputAssets>> {
+        let start = Instant::now();
+        let assets = phase.await?;
+        let asset_count = assets.await?.len();
+        self.phases.push(BuildTracePhase {
+            name: name.to_string(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            asset_count: Some(asset_count),
+        });
+        Ok(assets)
+    }
+
+    /// Writes the trace as JSON to `path`.
+    pub fn write(&self, path: Vc<FileSystemPath>) -> Result<Vc<Completion>> {
+        Ok(path.write(FileContent::Content(serde_json::to_string_pretty(self)?.into()).cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+    use turbo_tasks_fs::{File, FileContent, FileSystem};
+    use turbopack_binding::turbopack::core::{
+        asset::AssetContent, virtual_output::VirtualOutputAsset,
+    };
+
+    use super::BuildTrace;
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions},
+        emit::{all_assets_from_entries, emit_all_assets, EmitOptions},
+        memory_fs::MemoryFileSystem,
+        test_util::with_app_fixture,
+    };
+
+    #[tokio::test]
+    async fn test_build_trace_phases() -> Result<()> {
+        with_app_fixture(["layout.tsx", "page.tsx"], |app_dir| async move {
+            let output_fs =
+                Vc::upcast::<Box<dyn FileSystem>>(MemoryFileSystem::new("output".to_string()));
+            let node_root = output_fs.root().join(".next".to_string());
+            let client_root = output_fs.root().join("client".to_string());
+
+            let mut trace = BuildTrace::default();
+            trace
+                .record("app-structure", async {
+                    Ok(get_entrypoints(
+                        app_dir,
+                        Vc::cell(vec!["tsx".to_string()]),
+                        AppStructureOptions::default().cell(),
+                    )
+                    .await?)
+                })
+                .await?;
+            let assets = trace
+                .record_assets("chunking", async {
+                    Ok(all_assets_from_entries(Vc::cell(vec![Vc::upcast(
+                        VirtualOutputAsset::new(
+                            node_root.join("server/page.js".to_string()),
+                            AssetContent::file(File::from("module.exports = 1").into()),
+                        ),
+                    )])))
+                })
+                .await?;
+            trace
+                .record("emit", async {
+                    Ok(emit_all_assets(
+                        assets,
+                        node_root,
+                        client_root.join("_next".to_string()),
+                        client_root,
+                        EmitOptions::default().cell(),
+                    )
+                    .await?)
+                })
+                .await?;
+
+            let trace_path = node_root.join("trace-build.json".to_string());
+            trace.write(trace_path)?.await?;
+
+            let FileContent::Content(file) = &*trace_path.read().await? else {
+                panic!("the build trace wasn't written");
+            };
+            let json: serde_json::Value = serde_json::from_str(&file.content().to_str()?)?;
+            let phases = json["phases"].as_array().unwrap();
+            let names: Vec<_> = phases
+                .iter()
+                .map(|phase| phase["name"].as_str().unwrap())
+                .collect();
+            assert_eq!(names, vec!["app-structure", "chunking", "emit"]);
+            assert!(phases.iter().all(|phase| phase["durationMs"].is_u64()));
+            assert_eq!(phases[1]["assetCount"], 1);
+            assert!(phases[0].get("assetCount").is_none());
+
+            Ok(())
+        })
+        .await
+    }
+}
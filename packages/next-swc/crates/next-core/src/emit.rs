@@ -1,14 +1,33 @@
-use anyhow::Result;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use turbo_tasks::{
     graph::{AdjacencyMap, GraphTraversal},
-    Completion, Completions, TryJoinIterExt, Vc,
+    trace::TraceRawVcs,
+    Completion, Completions, TransientInstance, TryJoinIterExt, ValueToString, Vc,
 };
-use turbo_tasks_fs::{rebase, FileSystemPath};
-use turbopack_binding::turbopack::core::{
-    asset::Asset,
-    output::{OutputAsset, OutputAssets},
+use turbo_tasks_fs::{rebase, rope::Rope, DiskFileSystem, File, FileContent, FileSystemPath};
+use turbopack_binding::{
+    turbo::tasks_hash::hash_xxh3_hash64,
+    turbopack::core::{
+        asset::Asset,
+        issue::{Issue, IssueExt, IssueSeverity},
+        output::{OutputAsset, OutputAssets},
+        source_map::{SourceMap, SourceMapSection},
+        source_pos::SourcePos,
+    },
 };
 
+use crate::next_config::NextConfig;
+
 #[turbo_tasks::function]
 pub async fn all_server_paths(
     assets: Vc<OutputAssets>,
@@ -32,6 +51,92 @@ pub async fn all_server_paths(
     ))
 }
 
+/// Options controlling how assets are written to disk.
+#[turbo_tasks::value(shared)]
+#[derive(Default)]
+pub struct EmitOptions {
+    /// When set, emitted files get this fixed modification time (in seconds
+    /// since the Unix epoch) instead of the time they were written at. This
+    /// keeps builds reproducible, e.g. when set to the source commit time.
+    pub mtime: Option<u64>,
+    /// Precompressed sidecar files (e.g. `chunk.js.br`) to write next to
+    /// client assets, by file extension without the leading dot. Files in
+    /// formats which are already compressed (see
+    /// [COMPRESSED_FORMAT_EXTENSIONS]) are never precompressed.
+    pub compression: IndexMap<String, Vec<Compression>>,
+    /// When set, files emitted from another location on disk (e.g. traced
+    /// files copied to the standalone output) get the permissions of their
+    /// source file, so executable scripts stay executable.
+    pub preserve_permissions: bool,
+    /// Only writes assets inside the node root, for deployments which build
+    /// client assets separately. The skipped client assets are listed by
+    /// [skipped_client_paths].
+    pub skip_client_assets: bool,
+    /// When set, paths of assets in the node root longer than this are
+    /// shortened by replacing their directories below the node root with a
+    /// hash of them, e.g. to stay below `MAX_PATH` on Windows. The length of
+    /// paths on disk is the length of their absolute path. Emitting fails
+    /// when a path can't be shortened below the limit. Client assets are
+    /// never shortened, as they are requested by their URL.
+    ///
+    /// The shortened paths are listed in [SHORTENED_PATHS_MANIFEST]. The
+    /// contents of the emitted files aren't rewritten, so anything loading a
+    /// file by its original path (e.g. a deployment adapter or the server
+    /// loading a page, see `getMaybePagePath` in `next/src/server/require.ts`)
+    /// needs to look it up in the manifest.
+    pub max_path_length: Option<usize>,
+}
+
+/// The manifest in the node root mapping the output paths shortened because of
+/// [EmitOptions::max_path_length] to the paths they are written to, both
+/// relative to the node root (e.g. `server/app/blog/page.js` to
+/// `server/_<hash>/page.js`).
+pub const SHORTENED_PATHS_MANIFEST: &str = "shortened-paths-manifest.json";
+
+/// A compression format for precompressed sidecar files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    Gzip,
+    Brotli,
+}
+
+impl Compression {
+    /// The extension appended to the name of the compressed file.
+    fn extension(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Brotli => "br",
+        }
+    }
+
+    fn compress(self, content: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(content)?;
+                Ok(encoder.finish()?)
+            }
+            Compression::Brotli => {
+                let mut compressed = Vec::new();
+                {
+                    let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+                    encoder.write_all(content)?;
+                }
+                Ok(compressed)
+            }
+        }
+    }
+}
+
+/// Extensions of formats which are compressed already, so compressing them
+/// again only wastes space.
+pub const COMPRESSED_FORMAT_EXTENSIONS: &[&str] = &[
+    "avif", "br", "gif", "gz", "ico", "jpeg", "jpg", "mp4", "png", "webm", "webp", "woff", "woff2",
+    "zip",
+];
+
 /// Emits all assets transitively reachable from the given chunks, that are
 /// inside the node root or the client root.
 ///
@@ -43,49 +148,341 @@ pub fn emit_all_assets(
     node_root: Vc<FileSystemPath>,
     client_relative_path: Vc<FileSystemPath>,
     client_output_path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
 ) -> Vc<Completion> {
     emit_assets(
         all_assets_from_entries(assets),
         node_root,
         client_relative_path,
         client_output_path,
+        options,
+        None,
     )
 }
 
+/// Like [emit_all_assets], but calls `on_emit` as soon as each asset has been
+/// written, see [emit_assets].
+#[turbo_tasks::function]
+pub fn emit_all_assets_with_progress(
+    assets: Vc<OutputAssets>,
+    node_root: Vc<FileSystemPath>,
+    client_relative_path: Vc<FileSystemPath>,
+    client_output_path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+    on_emit: TransientInstance<EmitProgressCallback>,
+) -> Vc<Completion> {
+    emit_assets(
+        all_assets_from_entries(assets),
+        node_root,
+        client_relative_path,
+        client_output_path,
+        options,
+        Some(on_emit),
+    )
+}
+
+/// A file written by [emit_assets].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmittedFile {
+    /// The path of the file, relative to the root of its file system.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+/// Receives the files written by [emit_assets], e.g. to forward them to
+/// JavaScript through a threadsafe function.
+pub type EmitProgressCallback = Arc<dyn Fn(EmittedFile) + Send + Sync>;
+
 /// Emits all assets transitively reachable from the given chunks, that are
 /// inside the node root or the client root.
 ///
 /// Assets inside the given client root are rebased to the given client output
-/// path.
+/// path, unless [EmitOptions::skip_client_assets] is set.
+///
+/// When `on_emit` is given, it's called as soon as each asset has been
+/// written. Precompressed sidecar files and the shortened paths manifest
+/// aren't reported. The returned completion is ready once all assets have
+/// been written either way.
 #[turbo_tasks::function]
 pub async fn emit_assets(
     assets: Vc<OutputAssets>,
     node_root: Vc<FileSystemPath>,
     client_relative_path: Vc<FileSystemPath>,
     client_output_path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+    on_emit: Option<TransientInstance<EmitProgressCallback>>,
 ) -> Result<Vc<Completion>> {
+    let on_emit = on_emit.as_deref();
+    let mut completions = assets
+        .await?
+        .iter()
+        .copied()
+        .map(|asset| async move {
+            let Some(emitted) = emit_asset(
+                asset,
+                node_root,
+                client_relative_path,
+                client_output_path,
+                options,
+            )
+            .await?
+            else {
+                return Ok(Completion::immutable());
+            };
+            if let Some(on_emit) = on_emit {
+                emitted.completion.await?;
+                let size = match &*emitted.content.await? {
+                    FileContent::Content(file) => file.content().len() as u64,
+                    FileContent::NotFound => 0,
+                };
+                on_emit(EmittedFile {
+                    path: emitted.path.await?.path.clone(),
+                    size,
+                });
+            }
+            Ok(emitted.completion)
+        })
+        .try_join()
+        .await?;
+    completions.push(emit_shortened_paths_manifest(assets, node_root, options));
+    Ok(Completions::all(completions))
+}
+
+/// An asset written by [emit_asset].
+struct EmittedAsset {
+    /// The path the asset is written to.
+    path: Vc<FileSystemPath>,
+    /// The content written to `path`.
+    content: Vc<FileContent>,
+    completion: Vc<Completion>,
+}
+
+/// Emits an asset inside the node root or the client root, see [emit_assets].
+/// Returns `None` when it isn't emitted.
+async fn emit_asset(
+    asset: Vc<Box<dyn OutputAsset>>,
+    node_root: Vc<FileSystemPath>,
+    client_relative_path: Vc<FileSystemPath>,
+    client_output_path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Option<EmittedAsset>> {
+    let path = asset.ident().path();
+    let content = asset.content().file_content();
+    if path.await?.is_inside_ref(&*node_root.await?) {
+        if let Some(shortened_path) = shortened_output_path(path, node_root, options).await? {
+            return Ok(Some(EmittedAsset {
+                path: shortened_path,
+                content,
+                completion: emit_to(asset, shortened_path, options),
+            }));
+        }
+        return Ok(Some(EmittedAsset {
+            path,
+            content,
+            completion: emit(asset, options),
+        }));
+    } else if path.await?.is_inside_ref(&*client_relative_path.await?) {
+        if options.await?.skip_client_assets {
+            return Ok(None);
+        }
+        // Client assets are emitted to the client output path, which is prefixed with
+        // _next. We need to rebase them to remove that prefix.
+        let output_path = rebase(path, client_relative_path, client_output_path);
+        return Ok(Some(EmittedAsset {
+            path: output_path,
+            content,
+            completion: Completions::all(vec![
+                emit_rebase(asset, client_relative_path, client_output_path, options),
+                emit_precompressed(asset, output_path, options),
+            ]),
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Returns the path an asset written to `path` inside `output_root` is
+/// written to instead when `path` is longer than
+/// [EmitOptions::max_path_length], or `None` when it's kept. Fails when the
+/// path can't be shortened below the limit.
+async fn shortened_output_path(
+    path: Vc<FileSystemPath>,
+    output_root: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Option<Vc<FileSystemPath>>> {
+    let Some(max_path_length) = options.await?.max_path_length else {
+        return Ok(None);
+    };
+    let path_length = match disk_sys_path(path).await? {
+        Some(sys_path) => sys_path.as_os_str().len(),
+        None => path.await?.path.len(),
+    };
+    if path_length <= max_path_length {
+        return Ok(None);
+    }
+    let output_root_value = output_root.await?;
+    let path_value = path.await?;
+    let Some(relative_path) = output_root_value.get_path_to(&path_value) else {
+        return Ok(None);
+    };
+    let shortened = shorten_path(relative_path)
+        .filter(|shortened| path_length - relative_path.len() + shortened.len() <= max_path_length)
+        .with_context(|| {
+            format!(
+                "{} is {path_length} characters long and can't be shortened below the maximum \
+                 path length of {max_path_length}",
+                path_value.path
+            )
+        })?;
+    Ok(Some(output_root.join(shortened)))
+}
+
+/// Replaces the directories between the first directory and the file name of
+/// a relative path with a hash of them, so that e.g. `server/app/a/b/page.js`
+/// becomes `server/_<hash>/page.js`. Paths without such directories can't be
+/// shortened.
+fn shorten_path(path: &str) -> Option<String> {
+    let segments = path.split('/').collect::<Vec<_>>();
+    let [first, middle @ .., file_name] = &segments[..] else {
+        return None;
+    };
+    if middle.is_empty() {
+        return None;
+    }
+    let hash = hash_xxh3_hash64(middle.join("/").as_bytes());
+    Some(format!("{first}/_{hash:016x}/{file_name}"))
+}
+
+/// Writes [SHORTENED_PATHS_MANIFEST] for the assets written by [emit_asset],
+/// when [EmitOptions::max_path_length] is set.
+#[turbo_tasks::function]
+async fn emit_shortened_paths_manifest(
+    assets: Vc<OutputAssets>,
+    node_root: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    if options.await?.max_path_length.is_none() {
+        return Ok(Completion::immutable());
+    }
+    let node_root_value = node_root.await?;
+    let mut shortened_paths = BTreeMap::new();
+    for &asset in assets.await?.iter() {
+        let path = asset.ident().path();
+        let Some(relative_path) = node_root_value.get_path_to(&*path.await?) else {
+            continue;
+        };
+        if let Some(shortened_path) = shortened_output_path(path, node_root, options).await? {
+            if let Some(shortened_relative_path) =
+                node_root_value.get_path_to(&*shortened_path.await?)
+            {
+                shortened_paths.insert(
+                    relative_path.to_string(),
+                    shortened_relative_path.to_string(),
+                );
+            }
+        }
+    }
+    Ok(node_root.join(SHORTENED_PATHS_MANIFEST.to_string()).write(
+        FileContent::Content(File::from(serde_json::to_string_pretty(&shortened_paths)?)).cell(),
+    ))
+}
+
+/// Returns the paths of the client assets which [emit_assets] doesn't write
+/// because of [EmitOptions::skip_client_assets], relative to the client root,
+/// so that they can be built elsewhere. This is empty when client assets are
+/// emitted.
+#[turbo_tasks::function]
+pub async fn skipped_client_paths(
+    assets: Vc<OutputAssets>,
+    client_relative_path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Vec<String>>> {
+    if !options.await?.skip_client_assets {
+        return Ok(Vc::cell(Vec::new()));
+    }
+    let client_relative_path = &client_relative_path.await?;
+    Ok(Vc::cell(
+        assets
+            .await?
+            .iter()
+            .map(|&asset| async move {
+                Ok(client_relative_path
+                    .get_path_to(&*asset.ident().path().await?)
+                    .map(|s| s.to_string()))
+            })
+            .try_join()
+            .await?
+            .into_iter()
+            .flatten()
+            .collect(),
+    ))
+}
+
+/// Returns the directory files are traced from for standalone output, as
+/// configured by `experimental.outputFileTracingRoot`. Defaults to the project
+/// path. A configured root which isn't an ancestor of the project is ignored
+/// with a warning.
+#[turbo_tasks::function]
+pub async fn output_file_tracing_root(
+    project_path: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<FileSystemPath>> {
+    let next_config = next_config.await?;
+    let Some(root) = &next_config.experimental.output_file_tracing_root else {
+        return Ok(project_path);
+    };
+    let Some(disk_fs) =
+        Vc::try_resolve_downcast_type::<DiskFileSystem>(project_path.await?.fs).await?
+    else {
+        return Ok(project_path);
+    };
+    let project_sys_path = disk_fs.await?.to_sys_path(project_path).await?;
+
+    if let Ok(relative) = project_sys_path.strip_prefix(Path::new(root)) {
+        let depth = relative.components().count();
+        if depth == 0 {
+            return Ok(project_path);
+        }
+        let parents = vec![".."; depth].join("/");
+        if let Some(tracing_root) = *project_path.try_join(parents).await? {
+            return Ok(tracing_root);
+        }
+    }
+
+    OutputFileTracingRootIssue {
+        project_path,
+        root: root.clone(),
+    }
+    .cell()
+    .emit();
+    Ok(project_path)
+}
+
+/// Emits traced files (e.g. from `node_modules` or workspace packages) to the
+/// standalone output directory at `<node_root>/standalone`.
+///
+/// Files keep their path relative to the output file tracing root (see
+/// [output_file_tracing_root]), so files above the project but inside the
+/// tracing root are included as well. Files outside the tracing root are
+/// skipped.
+#[turbo_tasks::function]
+pub async fn emit_standalone_assets(
+    assets: Vc<OutputAssets>,
+    tracing_root: Vc<FileSystemPath>,
+    node_root: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    let standalone_path = node_root.join("standalone".to_string());
+    let tracing_root_ref = &*tracing_root.await?;
     Ok(Completions::all(
         assets
             .await?
             .iter()
             .copied()
             .map(|asset| async move {
-                if asset
-                    .ident()
-                    .path()
-                    .await?
-                    .is_inside_ref(&*node_root.await?)
-                {
-                    return Ok(emit(asset));
-                } else if asset
-                    .ident()
-                    .path()
-                    .await?
-                    .is_inside_ref(&*client_relative_path.await?)
-                {
-                    // Client assets are emitted to the client output path, which is prefixed with
-                    // _next. We need to rebase them to remove that prefix.
-                    return Ok(emit_rebase(asset, client_relative_path, client_output_path));
+                if asset.ident().path().await?.is_inside_ref(tracing_root_ref) {
+                    return Ok(emit_rebase(asset, tracing_root, standalone_path, options));
                 }
 
                 Ok(Completion::immutable())
@@ -95,20 +492,460 @@ pub async fn emit_assets(
     ))
 }
 
+/// Emits assets into a content-addressed store for caching across builds.
+///
+/// The content of each asset is written to `store` with the hash of the
+/// content as its file name, so identical assets (e.g. the same chunk emitted
+/// for several routes) share one entry. A JSON manifest mapping the path of
+/// each asset to its hash is written to `manifest_path`. Assets without
+/// content are skipped.
+///
+/// The 64 bit hash can collide, so assets are only deduplicated when their
+/// content is the same. A different content with an already stored hash is
+/// stored as `{hash}-1`, `{hash}-2` and so on.
+#[turbo_tasks::function]
+pub async fn emit_content_addressed_assets(
+    assets: Vc<OutputAssets>,
+    store: Vc<FileSystemPath>,
+    manifest_path: Vc<FileSystemPath>,
+) -> Result<Vc<Completion>> {
+    let mut references = BTreeMap::new();
+    let mut entries = IndexMap::new();
+    for &asset in assets.await?.iter() {
+        let Some(hash) = content_hash(asset).await? else {
+            continue;
+        };
+        let hash = format!("{hash:016x}");
+        let mut key = hash.clone();
+        for collisions in 1.. {
+            match entries.get(&key) {
+                None => {
+                    entries.insert(key.clone(), asset);
+                    break;
+                }
+                Some(&stored) if has_same_content(asset, stored).await? => break,
+                Some(_) => key = format!("{hash}-{collisions}"),
+            }
+        }
+        references.insert(asset.ident().path().await?.path.clone(), key);
+    }
+
+    let mut completions = entries
+        .into_iter()
+        .map(|(hash, asset)| asset.content().write(store.join(hash)))
+        .collect::<Vec<_>>();
+    completions.push(manifest_path.write(
+        FileContent::Content(File::from(serde_json::to_string_pretty(&references)?)).cell(),
+    ));
+    Ok(Completions::all(completions))
+}
+
+#[turbo_tasks::value(shared)]
+struct OutputFileTracingRootIssue {
+    project_path: Vc<FileSystemPath>,
+    root: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for OutputFileTracingRootIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("config".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.project_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("The output file tracing root doesn't contain the project".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "\"experimental.outputFileTracingRoot\" is set to {}, which isn't an ancestor of the \
+             project directory. Files are traced from the project directory instead.",
+            self.root
+        ))
+    }
+}
+
+#[turbo_tasks::function]
+async fn emit(asset: Vc<Box<dyn OutputAsset>>, options: Vc<EmitOptions>) -> Result<Vc<Completion>> {
+    write_with_options(asset, asset.ident().path(), options).await
+}
+
 #[turbo_tasks::function]
-fn emit(asset: Vc<Box<dyn OutputAsset>>) -> Vc<Completion> {
-    asset.content().write(asset.ident().path())
+async fn emit_to(
+    asset: Vc<Box<dyn OutputAsset>>,
+    path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    write_with_options(asset, path, options).await
 }
 
 #[turbo_tasks::function]
-fn emit_rebase(
+async fn emit_rebase(
     asset: Vc<Box<dyn OutputAsset>>,
     from: Vc<FileSystemPath>,
     to: Vc<FileSystemPath>,
-) -> Vc<Completion> {
-    asset
-        .content()
-        .write(rebase(asset.ident().path(), from, to))
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    write_with_options(asset, rebase(asset.ident().path(), from, to), options).await
+}
+
+/// Writes the precompressed sidecar files of an asset written to `path`, as
+/// configured by [EmitOptions::compression].
+#[turbo_tasks::function]
+async fn emit_precompressed(
+    asset: Vc<Box<dyn OutputAsset>>,
+    path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    let options = options.await?;
+    let file_name = path.await?.file_name().to_string();
+    let Some((_, extension)) = file_name.rsplit_once('.') else {
+        return Ok(Completion::immutable());
+    };
+    let Some(compressions) = options.compression.get(extension) else {
+        return Ok(Completion::immutable());
+    };
+    if compressions.is_empty() || COMPRESSED_FORMAT_EXTENSIONS.contains(&extension) {
+        return Ok(Completion::immutable());
+    }
+    let FileContent::Content(file) = &*asset.content().file_content().await? else {
+        return Ok(Completion::immutable());
+    };
+    let content = file.content().to_bytes()?;
+
+    let mut completions = Vec::new();
+    for compression in compressions {
+        let compressed_path = path.append(format!(".{}", compression.extension()));
+        let compressed = Rope::from(compression.compress(&content)?);
+        let completion = compressed_path.write(FileContent::Content(File::from(compressed)).cell());
+        if let Some(mtime) = options.mtime {
+            completion.await?;
+            set_mtime(compressed_path, mtime).await?;
+        }
+        completions.push(completion);
+    }
+    Ok(Completions::all(completions))
+}
+
+async fn write_with_options(
+    asset: Vc<Box<dyn OutputAsset>>,
+    path: Vc<FileSystemPath>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    let completion = asset.content().write(path);
+    let options = options.await?;
+    if let Some(mtime) = options.mtime {
+        // The file needs to be written before its modification time can be
+        // changed.
+        completion.await?;
+        set_mtime(path, mtime).await?;
+    }
+    if options.preserve_permissions {
+        completion.await?;
+        copy_permissions(asset.ident().path(), path).await?;
+    }
+    Ok(completion)
+}
+
+/// A section of a chunk's split source map. The section covers the chunk's
+/// code from `line` onwards, up to the start of the next section.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct SourceMapIndexSection {
+    pub line: usize,
+    pub map: Vc<SourceMap>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct SourceMapIndexSections(Vec<SourceMapIndexSection>);
+
+/// Emits a chunk whose source map has been split into sections.
+///
+/// A source map index referencing the sections is written next to the chunk
+/// as `<chunk>.map`, and the chunk's `sourceMappingURL` comment is rewritten
+/// in place to point at it, so the lines the sections start at don't shift.
+#[turbo_tasks::function]
+pub async fn emit_with_source_map_index(
+    chunk: Vc<Box<dyn OutputAsset>>,
+    sections: Vc<SourceMapIndexSections>,
+    options: Vc<EmitOptions>,
+) -> Result<Vc<Completion>> {
+    let path = chunk.ident().path();
+    let FileContent::Content(file) = &*chunk.content().file_content().await? else {
+        return Ok(Completion::immutable());
+    };
+    let index_path = path.append(".map".to_string());
+    let index_name = index_path.await?.file_name().to_string();
+
+    let source_mapping_url = format!("//# sourceMappingURL={index_name}");
+    let mut code = String::new();
+    let mut rewritten = false;
+    for line in file.content().to_str()?.split_inclusive('\n') {
+        if line.starts_with("//# sourceMappingURL=") {
+            // Keep the line ending (`\n` or `\r\n`) of the replaced comment.
+            let line_ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+            code.push_str(&source_mapping_url);
+            code.push_str(line_ending);
+            rewritten = true;
+        } else {
+            code.push_str(line);
+        }
+    }
+    if !rewritten {
+        if !code.is_empty() && !code.ends_with('\n') {
+            code.push('\n');
+        }
+        code.push_str(&source_mapping_url);
+        code.push('\n');
+    }
+
+    let index = SourceMap::new_sectioned(
+        sections
+            .await?
+            .iter()
+            .map(|section| {
+                SourceMapSection::new(
+                    SourcePos {
+                        line: section.line,
+                        column: 0,
+                    },
+                    section.map,
+                )
+            })
+            .collect(),
+    )
+    .cell();
+
+    let chunk_completion = path.write(FileContent::Content(File::from(code)).cell());
+    let index_rope = (*index.to_rope().await?).clone();
+    let index_completion = index_path.write(FileContent::Content(File::from(index_rope)).cell());
+    if let Some(mtime) = options.await?.mtime {
+        chunk_completion.await?;
+        index_completion.await?;
+        set_mtime(path, mtime).await?;
+        set_mtime(index_path, mtime).await?;
+    }
+    Ok(Completions::all(vec![chunk_completion, index_completion]))
+}
+
+/// Sets the modification time of a file written to disk. Files on other file
+/// systems don't have a modification time and are left untouched.
+async fn set_mtime(path: Vc<FileSystemPath>, mtime: u64) -> Result<()> {
+    let Some(sys_path) = disk_sys_path(path).await? else {
+        return Ok(());
+    };
+    let file = match std::fs::File::options().write(true).open(&sys_path) {
+        Ok(file) => file,
+        // The asset had no content, so there is no file to update.
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to open {}", sys_path.display()))
+        }
+    };
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(mtime))
+        .with_context(|| format!("failed to set mtime of {}", sys_path.display()))
+}
+
+/// Applies the permissions of the file at `source` to the file written to
+/// `path`. Nothing is changed unless both are files on disk.
+async fn copy_permissions(source: Vc<FileSystemPath>, path: Vc<FileSystemPath>) -> Result<()> {
+    let (Some(source_sys_path), Some(sys_path)) =
+        (disk_sys_path(source).await?, disk_sys_path(path).await?)
+    else {
+        return Ok(());
+    };
+    if source_sys_path == sys_path {
+        return Ok(());
+    }
+    let permissions = match std::fs::metadata(&source_sys_path) {
+        Ok(metadata) => metadata.permissions(),
+        // The asset wasn't read from disk (e.g. it's generated).
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read {}", source_sys_path.display()))
+        }
+    };
+    match std::fs::set_permissions(&sys_path, permissions) {
+        Ok(()) => Ok(()),
+        // The asset had no content, so there is no file to update.
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            Err(err).with_context(|| format!("failed to set permissions of {}", sys_path.display()))
+        }
+    }
+}
+
+/// Returns the path of `path` on disk, or `None` for other file systems.
+async fn disk_sys_path(path: Vc<FileSystemPath>) -> Result<Option<PathBuf>> {
+    let Some(disk_fs) = Vc::try_resolve_downcast_type::<DiskFileSystem>(path.await?.fs).await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(disk_fs.await?.to_sys_path(path).await?))
+}
+
+/// The entry assets of several endpoints.
+#[turbo_tasks::value(transparent)]
+pub struct OutputAssetsList(Vec<Vc<OutputAssets>>);
+
+/// The assets of several endpoints, split into the assets which are shared
+/// between endpoints and the assets unique to each endpoint.
+#[turbo_tasks::value(shared)]
+pub struct SharedAssets {
+    /// Assets referenced by more than one endpoint.
+    pub shared: Vc<OutputAssets>,
+    /// The assets only referenced by each endpoint, in the order of the
+    /// endpoints.
+    pub unique: Vec<Vc<OutputAssets>>,
+}
+
+/// Splits the assets transitively reachable from the entries of each endpoint
+/// (see [all_assets_from_entries]) into shared and unique assets. Assets are
+/// identified by their path.
+#[turbo_tasks::function]
+pub async fn split_shared_assets(entries: Vc<OutputAssetsList>) -> Result<Vc<SharedAssets>> {
+    let endpoint_assets = entries
+        .await?
+        .iter()
+        .map(|&entries| async move {
+            all_assets_from_entries(entries)
+                .await?
+                .iter()
+                .map(|&asset| async move {
+                    Ok((asset.ident().path().to_string().await?.clone_value(), asset))
+                })
+                .try_join()
+                .await
+        })
+        .try_join()
+        .await?;
+
+    let mut endpoint_counts: HashMap<&str, usize> = HashMap::new();
+    for assets in &endpoint_assets {
+        let paths: HashSet<&str> = assets.iter().map(|(path, _)| path.as_str()).collect();
+        for path in paths {
+            *endpoint_counts.entry(path).or_default() += 1;
+        }
+    }
+
+    let mut shared = IndexMap::new();
+    let mut unique = Vec::new();
+    for assets in &endpoint_assets {
+        let mut unique_assets = IndexMap::new();
+        for (path, asset) in assets {
+            if endpoint_counts[path.as_str()] > 1 {
+                shared.entry(path.as_str()).or_insert(*asset);
+            } else {
+                unique_assets.entry(path.as_str()).or_insert(*asset);
+            }
+        }
+        unique.push(Vc::cell(unique_assets.into_values().collect()));
+    }
+
+    Ok(SharedAssets {
+        shared: Vc::cell(shared.into_values().collect()),
+        unique,
+    }
+    .cell())
+}
+
+/// The difference between the assets of two builds, by path.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default)]
+pub struct AssetsDiff {
+    /// Paths which are only emitted by the current build.
+    pub added: Vec<String>,
+    /// Paths which are only emitted by the previous build.
+    pub removed: Vec<String>,
+    /// Paths which are emitted by both builds with different content.
+    pub changed: Vec<String>,
+}
+
+/// Compares the assets emitted by two builds (e.g. the results of
+/// [all_assets_from_entries]) by the hashes of their content, so that an
+/// incremental deployment only has to upload what changed.
+///
+/// Paths are sorted and relative to the root of their file system.
+#[turbo_tasks::function]
+pub async fn diff_assets(
+    previous: Vc<OutputAssets>,
+    current: Vc<OutputAssets>,
+) -> Result<Vc<AssetsDiff>> {
+    let previous = asset_content_hashes(previous).await?;
+    let current = asset_content_hashes(current).await?;
+
+    let mut diff = AssetsDiff::default();
+    for (path, hash) in &current {
+        match previous.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(previous_hash) if previous_hash != hash => diff.changed.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .cloned()
+        .collect();
+
+    diff.added.sort();
+    diff.removed.sort();
+    diff.changed.sort();
+    Ok(diff.cell())
+}
+
+/// Returns the content hash of each asset by its path. Assets without content
+/// have no hash.
+async fn asset_content_hashes(assets: Vc<OutputAssets>) -> Result<HashMap<String, Option<u64>>> {
+    Ok(assets
+        .await?
+        .iter()
+        .map(|&asset| async move {
+            let path = asset.ident().path().await?.path.clone();
+            Ok((path, content_hash(asset).await?))
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// Returns the hash of the content of an asset, or `None` if it has no
+/// content.
+async fn content_hash(asset: Vc<Box<dyn OutputAsset>>) -> Result<Option<u64>> {
+    Ok(match &*asset.content().file_content().await? {
+        FileContent::Content(file) => Some(hash_xxh3_hash64(&*file.content().to_bytes()?)),
+        FileContent::NotFound => None,
+    })
+}
+
+/// Whether two assets have the same content, byte for byte.
+async fn has_same_content(
+    a: Vc<Box<dyn OutputAsset>>,
+    b: Vc<Box<dyn OutputAsset>>,
+) -> Result<bool> {
+    let a = a.content().file_content().await?;
+    let b = b.content().file_content().await?;
+    Ok(match (&*a, &*b) {
+        (FileContent::Content(a), FileContent::Content(b)) => {
+            a.content().to_bytes()? == b.content().to_bytes()?
+        }
+        _ => false,
+    })
 }
 
 /// Walks the asset graph from multiple assets and collect all referenced
@@ -139,3 +976,654 @@ async fn get_referenced_assets(
         .collect::<Vec<_>>()
         .into_iter())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        sync::{Arc, Mutex},
+        time::{Duration, SystemTime},
+    };
+
+    use anyhow::Result;
+    use indexmap::{indexmap, IndexMap};
+    use turbo_tasks::{TransientInstance, TryJoinIterExt, Vc};
+    use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+    use turbopack_binding::turbopack::core::{
+        asset::AssetContent,
+        output::{OutputAsset, OutputAssets},
+        source_map::SourceMap,
+        virtual_output::VirtualOutputAsset,
+    };
+
+    use super::{
+        diff_assets, emit_all_assets_with_progress, emit_assets, emit_content_addressed_assets,
+        emit_standalone_assets, emit_with_source_map_index, output_file_tracing_root,
+        skipped_client_paths, split_shared_assets, Compression, EmitOptions, EmitProgressCallback,
+        EmittedFile, SourceMapIndexSection, SourceMapIndexSections, SHORTENED_PATHS_MANIFEST,
+    };
+    use crate::{
+        next_config::{ExperimentalConfig, NextConfig},
+        test_util::{with_memory_fs, Fixture},
+    };
+
+    fn asset(path: Vc<FileSystemPath>, content: &str) -> Vc<Box<dyn OutputAsset>> {
+        Vc::upcast(VirtualOutputAsset::new(
+            path,
+            AssetContent::file(File::from(content).into()),
+        ))
+    }
+
+    async fn read(path: Vc<FileSystemPath>) -> Result<Option<String>> {
+        Ok(match &*path.read().await? {
+            FileContent::Content(file) => Some(file.content().to_str()?.to_string()),
+            FileContent::NotFound => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_emit_to_memory_fs() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let node_root = root.join(".next".to_string());
+            let client_relative_path = root.join("client".to_string());
+            let client_output_path = node_root.join("static".to_string());
+
+            let server_asset = asset(
+                node_root.join("server/page.js".to_string()),
+                "module.exports = 1",
+            );
+            let client_asset = asset(
+                client_relative_path.join("_next/chunk.js".to_string()),
+                "console.log(1)",
+            );
+            let other_asset = asset(root.join("other.js".to_string()), "ignored");
+
+            emit_assets(
+                Vc::cell(vec![server_asset, client_asset, other_asset]),
+                node_root,
+                client_relative_path,
+                client_output_path,
+                EmitOptions::default().cell(),
+                None,
+            )
+            .await?;
+
+            assert_eq!(
+                read(node_root.join("server/page.js".to_string())).await?,
+                Some("module.exports = 1".to_string())
+            );
+            assert_eq!(
+                read(client_output_path.join("_next/chunk.js".to_string())).await?,
+                Some("console.log(1)".to_string())
+            );
+            assert_eq!(read(root.join("other.js".to_string())).await?, None);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_emit_without_client_assets() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let node_root = root.join(".next".to_string());
+            let client_relative_path = root.join("client".to_string());
+            let client_output_path = node_root.join("static".to_string());
+
+            let assets = Vc::<OutputAssets>::cell(vec![
+                asset(
+                    node_root.join("server/page.js".to_string()),
+                    "module.exports = 1",
+                ),
+                asset(
+                    client_relative_path.join("_next/chunk.js".to_string()),
+                    "console.log(1)",
+                ),
+            ]);
+            let options = EmitOptions {
+                skip_client_assets: true,
+                ..Default::default()
+            }
+            .cell();
+            emit_assets(
+                assets,
+                node_root,
+                client_relative_path,
+                client_output_path,
+                options,
+                None,
+            )
+            .await?;
+
+            assert_eq!(
+                read(node_root.join("server/page.js".to_string())).await?,
+                Some("module.exports = 1".to_string())
+            );
+            assert_eq!(
+                read(client_output_path.join("_next/chunk.js".to_string())).await?,
+                None
+            );
+            assert_eq!(
+                *skipped_client_paths(assets, client_relative_path, options).await?,
+                vec!["_next/chunk.js".to_string()]
+            );
+            assert!(skipped_client_paths(
+                assets,
+                client_relative_path,
+                EmitOptions::default().cell()
+            )
+            .await?
+            .is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn read_bytes(path: Vc<FileSystemPath>) -> Result<Option<Vec<u8>>> {
+        Ok(match &*path.read().await? {
+            FileContent::Content(file) => Some(file.content().to_bytes()?.into_owned()),
+            FileContent::NotFound => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_emit_precompressed_client_assets() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let node_root = root.join(".next".to_string());
+            let client_relative_path = root.join("client".to_string());
+            let client_output_path = node_root.join("static".to_string());
+
+            let assets = [
+                ("chunk.js", "console.log(1)"),
+                ("style.css", "body {}"),
+                ("data.json", "{}"),
+                ("logo.png", "png"),
+            ]
+            .into_iter()
+            .map(|(name, content)| {
+                asset(client_relative_path.join(format!("_next/{name}")), content)
+            })
+            .chain([asset(
+                node_root.join("server/page.js".to_string()),
+                "module.exports = 1",
+            )])
+            .collect();
+
+            emit_assets(
+                Vc::cell(assets),
+                node_root,
+                client_relative_path,
+                client_output_path,
+                EmitOptions {
+                    compression: indexmap! {
+                        "js".to_string() => vec![Compression::Brotli, Compression::Gzip],
+                        "json".to_string() => vec![Compression::Gzip],
+                        "png".to_string() => vec![Compression::Gzip],
+                    },
+                    ..Default::default()
+                }
+                .cell(),
+                None,
+            )
+            .await?;
+
+            let output = |name: &str| read_bytes(client_output_path.join(format!("_next/{name}")));
+
+            let mut js = String::new();
+            brotli_decompressor::Decompressor::new(
+                &output("chunk.js.br").await?.unwrap()[..],
+                4096,
+            )
+            .read_to_string(&mut js)?;
+            assert_eq!(js, "console.log(1)");
+
+            let mut js = String::new();
+            flate2::read::GzDecoder::new(&output("chunk.js.gz").await?.unwrap()[..])
+                .read_to_string(&mut js)?;
+            assert_eq!(js, "console.log(1)");
+
+            let mut json = String::new();
+            flate2::read::GzDecoder::new(&output("data.json.gz").await?.unwrap()[..])
+                .read_to_string(&mut json)?;
+            assert_eq!(json, "{}");
+            assert_eq!(output("data.json.br").await?, None);
+
+            // Extensions without a policy and compressed formats are skipped.
+            assert_eq!(output("style.css.gz").await?, None);
+            assert_eq!(output("style.css.br").await?, None);
+            assert_eq!(output("logo.png.gz").await?, None);
+            assert_eq!(output("logo.png").await?, Some(b"png".to_vec()));
+
+            // Server assets aren't precompressed.
+            assert_eq!(
+                read_bytes(node_root.join("server/page.js.gz".to_string())).await?,
+                None
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_emit_with_max_path_length() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let node_root = root.join(".next".to_string());
+            let client_relative_path = root.join("client".to_string());
+            let client_output_path = node_root.join("static".to_string());
+            let long_path = "server/app/some/deeply/nested/route/page.js";
+            let client_path = "chunks/some/deeply/nested/route/chunk.js";
+            let assets = Vc::cell(vec![
+                asset(node_root.join(long_path.to_string()), "long"),
+                asset(node_root.join("server/short.js".to_string()), "short"),
+                asset(client_relative_path.join(client_path.to_string()), "client"),
+            ]);
+            let emit = |max_path_length| {
+                emit_assets(
+                    assets,
+                    node_root,
+                    client_relative_path,
+                    client_output_path,
+                    EmitOptions {
+                        max_path_length: Some(max_path_length),
+                        ..Default::default()
+                    }
+                    .cell(),
+                    None,
+                )
+            };
+            emit(40).await?;
+
+            let manifest: IndexMap<String, String> = serde_json::from_str(
+                &read(node_root.join(SHORTENED_PATHS_MANIFEST.to_string()))
+                    .await?
+                    .unwrap(),
+            )?;
+            assert_eq!(manifest.len(), 1);
+            let shortened_path = &manifest[long_path];
+            assert!(shortened_path.starts_with("server/_"));
+            assert!(shortened_path.ends_with("/page.js"));
+            // The full path, including the node root, fits the limit.
+            let shortened_output_path = node_root.join(shortened_path.clone());
+            assert!(shortened_output_path.await?.path.len() <= 40);
+            assert_eq!(read(shortened_output_path).await?.as_deref(), Some("long"));
+            assert_eq!(read(node_root.join(long_path.to_string())).await?, None);
+            // Client assets are requested by their URL and keep their path.
+            assert_eq!(
+                read(client_output_path.join(client_path.to_string()))
+                    .await?
+                    .as_deref(),
+                Some("client")
+            );
+            assert_eq!(
+                read(node_root.join("server/short.js".to_string()))
+                    .await?
+                    .as_deref(),
+                Some("short")
+            );
+
+            // Paths which are still too long once shortened fail the build.
+            let err = emit(20).await.unwrap_err();
+            assert!(format!("{err:#}").contains("can't be shortened below the maximum"));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_emit_with_fixed_mtime() -> Result<()> {
+        let fixture = Fixture::empty()?;
+        fixture
+            .run(|root| async move {
+                let node_root = root.join(".next".to_string());
+
+                emit_assets(
+                    Vc::cell(vec![asset(
+                        node_root.join("server/page.js".to_string()),
+                        "module.exports = 1",
+                    )]),
+                    node_root,
+                    root.join("client".to_string()),
+                    node_root.join("static".to_string()),
+                    EmitOptions {
+                        mtime: Some(1_000_000_000),
+                        ..Default::default()
+                    }
+                    .cell(),
+                    None,
+                )
+                .await?;
+
+                Ok(())
+            })
+            .await?;
+
+        let modified =
+            std::fs::metadata(fixture.path().join(".next/server/page.js"))?.modified()?;
+        assert_eq!(
+            modified,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000)
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_emit_with_preserved_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fixture = Fixture::new([("bin/run.sh", "#!/bin/sh\n")])?;
+        std::fs::set_permissions(
+            fixture.path().join("bin/run.sh"),
+            std::fs::Permissions::from_mode(0o755),
+        )?;
+
+        fixture
+            .run(|root| async move {
+                emit_standalone_assets(
+                    Vc::cell(vec![asset(
+                        root.join("bin/run.sh".to_string()),
+                        "#!/bin/sh\n",
+                    )]),
+                    root,
+                    root.join(".next".to_string()),
+                    EmitOptions {
+                        preserve_permissions: true,
+                        ..Default::default()
+                    }
+                    .cell(),
+                )
+                .await?;
+
+                Ok(())
+            })
+            .await?;
+
+        let mode = std::fs::metadata(fixture.path().join(".next/standalone/bin/run.sh"))?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emit_source_map_index() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let chunk_path = root.join("chunks/chunk.js".to_string());
+
+            let sections_of = |lines: &[usize]| -> Vc<SourceMapIndexSections> {
+                Vc::cell(
+                    lines
+                        .iter()
+                        .map(|&line| SourceMapIndexSection {
+                            line,
+                            map: SourceMap::new_sectioned(vec![]).cell(),
+                        })
+                        .collect(),
+                )
+            };
+            let sections = sections_of(&[0, 3]);
+            emit_with_source_map_index(
+                asset(
+                    chunk_path,
+                    "a();\r\nb();\r\n//# sourceMappingURL=chunk.js.map.old\r\nc();\r\n",
+                ),
+                sections,
+                EmitOptions::default().cell(),
+            )
+            .await?;
+
+            // The comment is rewritten in place, so `c();` is still at the
+            // start of the second section.
+            let code = read(chunk_path).await?.unwrap();
+            assert_eq!(
+                code,
+                "a();\r\nb();\r\n//# sourceMappingURL=chunk.js.map\r\nc();\r\n"
+            );
+            assert_eq!(code.lines().position(|line| line == "c();"), Some(3));
+
+            let index: serde_json::Value = serde_json::from_str(
+                &read(root.join("chunks/chunk.js.map".to_string()))
+                    .await?
+                    .unwrap(),
+            )?;
+            assert_eq!(index["version"], 3);
+            let sections = index["sections"].as_array().unwrap();
+            assert_eq!(sections.len(), 2);
+            assert_eq!(sections[0]["offset"]["line"], 0);
+            assert_eq!(sections[1]["offset"]["line"], 3);
+            for section in sections {
+                assert_eq!(section["offset"]["column"], 0);
+                assert!(section["map"].is_object());
+            }
+
+            // Without a comment, it's appended after the last line.
+            emit_with_source_map_index(
+                asset(chunk_path, "a();\nb();"),
+                sections_of(&[0]),
+                EmitOptions::default().cell(),
+            )
+            .await?;
+            assert_eq!(
+                read(chunk_path).await?,
+                Some("a();\nb();\n//# sourceMappingURL=chunk.js.map\n".to_string())
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_emit_standalone_with_tracing_root() -> Result<()> {
+        let fixture = Fixture::empty()?;
+        let tracing_root_config = fixture.path().canonicalize()?.to_string_lossy().to_string();
+
+        fixture
+            .run(move |root| async move {
+                let project_path = root.join("apps/web".to_string());
+                let node_root = project_path.join(".next".to_string());
+                let next_config = NextConfig {
+                    experimental: ExperimentalConfig {
+                        output_file_tracing_root: Some(tracing_root_config),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+                .cell();
+
+                let tracing_root = output_file_tracing_root(project_path, next_config);
+                assert_eq!(tracing_root.await?.path, "");
+
+                emit_standalone_assets(
+                    Vc::cell(vec![
+                        asset(
+                            root.join("packages/ui/index.js".to_string()),
+                            "module.exports = 'ui'",
+                        ),
+                        asset(
+                            project_path.join("node_modules/react/index.js".to_string()),
+                            "module.exports = 'react'",
+                        ),
+                    ]),
+                    tracing_root,
+                    node_root,
+                    EmitOptions::default().cell(),
+                )
+                .await?;
+
+                let standalone_path = node_root.join("standalone".to_string());
+                assert_eq!(
+                    read(standalone_path.join("packages/ui/index.js".to_string())).await?,
+                    Some("module.exports = 'ui'".to_string())
+                );
+                assert_eq!(
+                    read(standalone_path.join("apps/web/node_modules/react/index.js".to_string()))
+                        .await?,
+                    Some("module.exports = 'react'".to_string())
+                );
+
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_shared_assets() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let chunks = root.join(".next/static/chunks".to_string());
+            let vendor = asset(chunks.join("vendor.js".to_string()), "vendor");
+            let index = asset(chunks.join("pages/index.js".to_string()), "index");
+            let about = asset(chunks.join("pages/about.js".to_string()), "about");
+
+            let split = split_shared_assets(Vc::cell(vec![
+                Vc::cell(vec![index, vendor]),
+                Vc::cell(vec![about, vendor]),
+            ]))
+            .await?;
+
+            let paths = |assets: Vc<OutputAssets>| async move {
+                assets
+                    .await?
+                    .iter()
+                    .map(|asset| async move { Ok(asset.ident().path().await?.path.clone()) })
+                    .try_join()
+                    .await
+            };
+            assert_eq!(
+                paths(split.shared).await?,
+                vec![".next/static/chunks/vendor.js".to_string()]
+            );
+            assert_eq!(split.unique.len(), 2);
+            assert_eq!(
+                paths(split.unique[0]).await?,
+                vec![".next/static/chunks/pages/index.js".to_string()]
+            );
+            assert_eq!(
+                paths(split.unique[1]).await?,
+                vec![".next/static/chunks/pages/about.js".to_string()]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_diff_assets() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let chunks = root.join(".next/static/chunks".to_string());
+            let previous = Vc::cell(vec![
+                asset(chunks.join("vendor.js".to_string()), "vendor"),
+                asset(chunks.join("index.js".to_string()), "index"),
+                asset(chunks.join("about.js".to_string()), "about"),
+            ]);
+            let current = Vc::cell(vec![
+                asset(chunks.join("vendor.js".to_string()), "vendor"),
+                asset(chunks.join("index.js".to_string()), "index 2"),
+                asset(chunks.join("blog.js".to_string()), "blog"),
+            ]);
+
+            let diff = diff_assets(previous, current).await?;
+            assert_eq!(diff.added, vec![".next/static/chunks/blog.js".to_string()]);
+            assert_eq!(
+                diff.removed,
+                vec![".next/static/chunks/about.js".to_string()]
+            );
+            assert_eq!(
+                diff.changed,
+                vec![".next/static/chunks/index.js".to_string()]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_emit_content_addressed_assets() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let store = root.join("cache/store".to_string());
+            let manifest_path = root.join("cache/references.json".to_string());
+            let assets = Vc::cell(vec![
+                asset(root.join(".next/server/app/a.js".to_string()), "shared"),
+                asset(root.join(".next/server/app/b.js".to_string()), "shared"),
+                asset(root.join(".next/server/app/c.js".to_string()), "unique"),
+            ]);
+            emit_content_addressed_assets(assets, store, manifest_path).await?;
+
+            let references: IndexMap<String, String> =
+                serde_json::from_str(&read(manifest_path).await?.unwrap())?;
+            assert_eq!(references.len(), 3);
+            let shared = &references[".next/server/app/a.js"];
+            assert_eq!(&references[".next/server/app/b.js"], shared);
+            assert_ne!(&references[".next/server/app/c.js"], shared);
+            assert_eq!(
+                read(store.join(shared.clone())).await?.as_deref(),
+                Some("shared")
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_emit_all_assets_with_progress() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let node_root = root.join(".next".to_string());
+            let client_relative_path = root.join("client".to_string());
+            let client_output_path = node_root.join("static".to_string());
+            let assets = Vc::cell(vec![
+                asset(node_root.join("server/a.js".to_string()), "a"),
+                asset(node_root.join("server/b.js".to_string()), "bb"),
+                asset(client_relative_path.join("_next/c.js".to_string()), "ccc"),
+                asset(root.join("outside.js".to_string()), "ignored"),
+            ]);
+
+            let emitted = Arc::new(Mutex::new(Vec::new()));
+            let on_emit: EmitProgressCallback = {
+                let emitted = emitted.clone();
+                Arc::new(move |file| emitted.lock().unwrap().push(file))
+            };
+            emit_all_assets_with_progress(
+                assets,
+                node_root,
+                client_relative_path,
+                client_output_path,
+                EmitOptions::default().cell(),
+                TransientInstance::new(on_emit),
+            )
+            .await?;
+
+            let mut emitted = emitted.lock().unwrap().clone();
+            emitted.sort_by(|a, b| a.path.cmp(&b.path));
+            assert_eq!(
+                emitted,
+                vec![
+                    EmittedFile {
+                        path: ".next/server/a.js".to_string(),
+                        size: 1,
+                    },
+                    EmittedFile {
+                        path: ".next/server/b.js".to_string(),
+                        size: 2,
+                    },
+                    EmittedFile {
+                        path: ".next/static/_next/c.js".to_string(),
+                        size: 3,
+                    },
+                ]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,12 +1,24 @@
+use std::collections::BTreeSet;
+
 use anyhow::Result;
 use indexmap::indexmap;
+use swc_core::ecma::{
+    ast::{Expr, Lit, MemberExpr, MemberProp, Program},
+    visit::{noop_visit_type, Visit, VisitWith},
+};
 use turbo_tasks::Vc;
 use turbopack_binding::{
     turbo::tasks_env::{CustomProcessEnv, EnvMap, FilterProcessEnv, ProcessEnv},
-    turbopack::env::EmbeddableProcessEnv,
+    turbopack::{core::module::Module, ecmascript::parse::ParseResult, env::EmbeddableProcessEnv},
+};
+
+use crate::{
+    next_config::NextConfig,
+    next_shared::module_graph::{parse_ecmascript_module, reachable_modules},
 };
 
-use crate::next_config::NextConfig;
+/// The prefix of env vars which are exposed to client code.
+pub const PUBLIC_ENV_PREFIX: &str = "NEXT_PUBLIC_";
 
 /// Creates a Vc<Box<dyn ProcessEnv>> safe to use in JS, by stringifying and
 /// encoding as regular JS strings. Setting `client` to true will additionally
@@ -28,7 +40,7 @@ pub async fn env_for_js(
         Vc::upcast(FilterProcessEnv::new(
             env,
             vec![
-                "NEXT_PUBLIC_".to_string(),
+                PUBLIC_ENV_PREFIX.to_string(),
                 "NODE_ENV".to_string(),
                 "PORT".to_string(),
             ],
@@ -67,3 +79,122 @@ pub async fn env_for_js(
 
     Ok(Vc::upcast(CustomProcessEnv::new(env, Vc::cell(map))))
 }
+
+/// Returns the public env vars (see [PUBLIC_ENV_PREFIX]) referenced as
+/// `process.env.NEXT_PUBLIC_*` by the modules reachable from the client entry
+/// `entry`, sorted by name. Only these have to be injected into the client
+/// env.
+#[turbo_tasks::function]
+pub async fn referenced_public_env_vars(entry: Vc<Box<dyn Module>>) -> Result<Vc<Vec<String>>> {
+    let mut env_vars = BTreeSet::new();
+    for module in reachable_modules(entry).await? {
+        let Some(parse_result) = parse_ecmascript_module(module).await? else {
+            continue;
+        };
+        if let ParseResult::Ok { program, .. } = &*parse_result {
+            env_vars.extend(find_public_env_vars(program));
+        }
+    }
+    Ok(Vc::cell(env_vars.into_iter().collect()))
+}
+
+fn find_public_env_vars(program: &Program) -> BTreeSet<String> {
+    let mut finder = PublicEnvVarFinder::default();
+    program.visit_with(&mut finder);
+    finder.env_vars
+}
+
+#[derive(Default)]
+struct PublicEnvVarFinder {
+    env_vars: BTreeSet<String>,
+}
+
+impl Visit for PublicEnvVarFinder {
+    noop_visit_type!();
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if is_process_env(&member.obj) {
+            let name = match &member.prop {
+                MemberProp::Ident(ident) => Some(&*ident.sym),
+                MemberProp::Computed(computed) => match &*computed.expr {
+                    Expr::Lit(Lit::Str(str)) => Some(&*str.value),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if let Some(name) = name.filter(|name| name.starts_with(PUBLIC_ENV_PREFIX)) {
+                self.env_vars.insert(name.to_string());
+            }
+        }
+        member.visit_children_with(self);
+    }
+}
+
+/// Whether `expr` is `process.env`.
+fn is_process_env(expr: &Expr) -> bool {
+    let Expr::Member(member) = expr else {
+        return false;
+    };
+    matches!(&*member.obj, Expr::Ident(ident) if &*ident.sym == "process")
+        && matches!(&member.prop, MemberProp::Ident(prop) if &*prop.sym == "env")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::{Value, Vc};
+    use turbopack_binding::turbopack::{
+        core::{
+            context::AssetContext, environment::ServerAddr, file_source::FileSource,
+            reference_type::ReferenceType,
+        },
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::referenced_public_env_vars;
+    use crate::{next_edge::context::get_edge_compile_time_info, test_util::Fixture};
+
+    #[tokio::test]
+    async fn test_referenced_public_env_vars() -> Result<()> {
+        Fixture::new([
+            (
+                "page.js",
+                "import { analytics } from './analytics.js'\nexport default () => [analytics(), \
+                 process.env.NODE_ENV]\n",
+            ),
+            (
+                "analytics.js",
+                "export const analytics = () => process.env.NEXT_PUBLIC_ANALYTICS_ID\n",
+            ),
+            (
+                "unused.js",
+                "export const url = process.env.NEXT_PUBLIC_API_URL\n",
+            ),
+        ])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let entry = context.process(
+                Vc::upcast(FileSource::new(project_path.join("page.js".to_string()))),
+                Value::new(ReferenceType::Undefined),
+            );
+
+            assert_eq!(
+                *referenced_public_env_vars(entry).await?,
+                vec!["NEXT_PUBLIC_ANALYTICS_ID".to_string()]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
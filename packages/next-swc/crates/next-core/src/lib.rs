@@ -5,6 +5,7 @@
 #![feature(impl_trait_in_assoc_type)]
 #![feature(arbitrary_self_types)]
 #![feature(async_fn_in_trait)]
+#![feature(file_set_times)]
 
 mod app_render;
 mod app_segment_config;
@@ -12,12 +13,15 @@ mod app_source;
 pub mod app_structure;
 mod babel;
 mod bootstrap;
+pub mod build_trace;
 pub mod dev_manifest;
 mod embed_js;
 mod emit;
 pub mod env;
 mod fallback;
 pub mod loader_tree;
+pub mod memory_fs;
+pub mod middleware;
 pub mod mode;
 pub mod next_app;
 mod next_build;
@@ -41,21 +45,37 @@ pub mod next_telemetry;
 mod page_loader;
 mod page_source;
 pub mod pages_structure;
+pub mod route_conflicts;
+pub mod route_graph;
+pub mod route_kinds;
 pub mod router;
 pub mod router_source;
 mod runtime;
 mod sass;
+#[cfg(test)]
+mod test_util;
 pub mod tracing_presets;
 mod transform_options;
+pub mod typed_routes;
 pub mod url_node;
 pub mod util;
 mod web_entry_source;
 
 pub use app_segment_config::{
-    parse_segment_config_from_loader_tree, parse_segment_config_from_source,
+    classify_segment, classify_segments_from_loader_tree, get_app_route_runtime,
+    get_route_revalidate, lint_unused_loading_boundaries, lowest_revalidate,
+    parse_app_route_config, parse_segment_config_from_loader_tree,
+    parse_segment_config_from_source, OptionNextRevalidate, SegmentRendering, SegmentRenderingMap,
 };
 pub use app_source::create_app_source;
-pub use emit::{all_assets_from_entries, all_server_paths, emit_all_assets, emit_assets};
+pub use emit::{
+    all_assets_from_entries, all_server_paths, diff_assets, emit_all_assets,
+    emit_all_assets_with_progress, emit_assets, emit_content_addressed_assets,
+    emit_standalone_assets, emit_with_source_map_index, output_file_tracing_root,
+    split_shared_assets, AssetsDiff, Compression, EmitOptions, EmitProgressCallback, EmittedFile,
+    OutputAssetsList, SharedAssets, SourceMapIndexSection, SourceMapIndexSections,
+    COMPRESSED_FORMAT_EXTENSIONS, SHORTENED_PATHS_MANIFEST,
+};
 pub use next_edge::context::{
     get_edge_chunking_context, get_edge_compile_time_info, get_edge_resolve_options_context,
 };
@@ -63,7 +83,9 @@ pub use page_loader::{create_page_loader_entry_module, PageLoaderAsset};
 pub use page_source::create_page_source;
 pub use turbopack_binding::{turbopack::node::source_map, *};
 pub use util::{get_asset_path_from_pathname, pathname_for_path, PathType};
-pub use web_entry_source::create_web_entry_source;
+pub use web_entry_source::{
+    create_web_entry_source, create_web_entry_sources, WebEntryRequests, WebEntrySources,
+};
 
 pub fn register() {
     turbo_tasks::register();
@@ -49,6 +49,7 @@ enum ComponentType {
     Loading,
     Template,
     NotFound,
+    GlobalError,
 }
 
 impl ComponentType {
@@ -61,6 +62,7 @@ impl ComponentType {
             ComponentType::Loading => "loading",
             ComponentType::Template => "template",
             ComponentType::NotFound => "not-found",
+            ComponentType::GlobalError => "global-error",
         }
     }
 }
@@ -319,6 +321,7 @@ impl LoaderTreeBuilder {
             loading,
             template,
             not_found,
+            global_error,
             metadata,
             route: _,
         } = &*components.await?;
@@ -333,6 +336,8 @@ impl LoaderTreeBuilder {
             .await?;
         self.write_component(ComponentType::NotFound, *not_found)
             .await?;
+        self.write_component(ComponentType::GlobalError, *global_error)
+            .await?;
         self.write_metadata(metadata)?;
         write!(self.loader_tree_code, "}}]")?;
         Ok(())
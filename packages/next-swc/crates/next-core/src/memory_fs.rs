@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use turbo_tasks::{Completion, State, ValueToString, Vc};
+use turbo_tasks_fs::{
+    DirectoryContent, FileContent, FileMeta, FileSystem, FileSystemPath, LinkContent,
+};
+
+/// A writable file system which only keeps file contents in memory. This
+/// allows emitting assets (e.g. in tests) without touching the disk.
+///
+/// Only reading and writing files is supported.
+#[turbo_tasks::value]
+pub struct MemoryFileSystem {
+    name: String,
+    files: State<HashMap<String, Vc<FileContent>>>,
+}
+
+impl MemoryFileSystem {
+    // NOTE This must not be a `#[turbo_tasks::function]`, as every call should
+    // create a separate file system.
+    pub fn new(name: String) -> Vc<Self> {
+        MemoryFileSystem {
+            name,
+            files: State::new(HashMap::new()),
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for MemoryFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: Vc<FileSystemPath>) -> Result<Vc<FileContent>> {
+        let path = fs_path.await?;
+        let content = self.files.get().get(&path.path).copied();
+        Ok(content.unwrap_or_else(|| FileContent::NotFound.cell()))
+    }
+
+    #[turbo_tasks::function]
+    fn read_link(&self, _fs_path: Vc<FileSystemPath>) -> Vc<LinkContent> {
+        LinkContent::NotFound.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn read_dir(&self, _fs_path: Vc<FileSystemPath>) -> Result<Vc<DirectoryContent>> {
+        bail!("Reading directories is not supported by the in-memory file system")
+    }
+
+    #[turbo_tasks::function]
+    fn track(&self, _fs_path: Vc<FileSystemPath>) -> Vc<Completion> {
+        Completion::immutable()
+    }
+
+    #[turbo_tasks::function]
+    async fn write(
+        &self,
+        fs_path: Vc<FileSystemPath>,
+        content: Vc<FileContent>,
+    ) -> Result<Vc<Completion>> {
+        let path = fs_path.await?.path.clone();
+        let content = content.resolve().await?;
+        self.files
+            .update_conditionally(|files| files.insert(path, content) != Some(content));
+        Ok(Completion::new())
+    }
+
+    #[turbo_tasks::function]
+    fn write_link(
+        &self,
+        _fs_path: Vc<FileSystemPath>,
+        _target: Vc<LinkContent>,
+    ) -> Result<Vc<Completion>> {
+        bail!("Writing links is not supported by the in-memory file system")
+    }
+
+    #[turbo_tasks::function]
+    fn metadata(&self, _fs_path: Vc<FileSystemPath>) -> Result<Vc<FileMeta>> {
+        bail!("Reading metadata is not supported by the in-memory file system")
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for MemoryFileSystem {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<String> {
+        Vc::cell(self.name.clone())
+    }
+}
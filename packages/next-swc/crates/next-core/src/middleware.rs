@@ -0,0 +1,197 @@
+use anyhow::Result;
+use turbo_tasks::{Value, Vc};
+use turbo_tasks_fs::FileSystemPathOption;
+use turbopack_binding::{
+    turbo::tasks_fs::{FileSystemEntryType, FileSystemPath},
+    turbopack::core::{
+        context::AssetContext,
+        file_source::FileSource,
+        issue::{Issue, IssueExt, IssueSeverity},
+        reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
+    },
+};
+
+use crate::util::{parse_config_from_source, NextSourceConfig};
+
+/// Directories in which middleware is looked up, relative to the project.
+const MIDDLEWARE_DIRS: &[&str] = &["", "src/"];
+
+/// Directories in which a middleware file is most likely misplaced, as it's
+/// ignored there.
+const MISPLACED_MIDDLEWARE_DIRS: &[&str] = &["app/", "pages/", "src/app/", "src/pages/"];
+
+/// The middleware of a project and its parsed `config` export.
+#[turbo_tasks::value(shared)]
+pub struct MiddlewareConfig {
+    pub path: Vc<FileSystemPath>,
+    pub config: Vc<NextSourceConfig>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionMiddlewareConfig(Option<Vc<MiddlewareConfig>>);
+
+/// Returns the middleware file of the project, which is either
+/// `middleware.<ext>` or `src/middleware.<ext>` for any of the page
+/// extensions.
+///
+/// Emits a warning for middleware files in the `app` or `pages` directories,
+/// which are ignored.
+#[turbo_tasks::function]
+pub async fn find_middleware_file(
+    project_path: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+) -> Result<Vc<FileSystemPathOption>> {
+    let page_extensions = page_extensions.await?;
+
+    for dir in MISPLACED_MIDDLEWARE_DIRS {
+        for ext in page_extensions.iter() {
+            let path = project_path.join(format!("{dir}middleware.{ext}"));
+            if *path.get_type().await? == FileSystemEntryType::File {
+                MisplacedMiddlewareIssue { path }.cell().emit();
+            }
+        }
+    }
+
+    for dir in MIDDLEWARE_DIRS {
+        for ext in page_extensions.iter() {
+            let path = project_path.join(format!("{dir}middleware.{ext}"));
+            if *path.get_type().await? == FileSystemEntryType::File {
+                return Ok(Vc::cell(Some(path)));
+            }
+        }
+    }
+
+    Ok(FileSystemPathOption::none())
+}
+
+/// Returns the middleware of the project (see [find_middleware_file]) with its
+/// `config` export (runtime and matchers) parsed in `context`.
+#[turbo_tasks::function]
+pub async fn get_middleware_config(
+    project_path: Vc<FileSystemPath>,
+    page_extensions: Vc<Vec<String>>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<OptionMiddlewareConfig>> {
+    let Some(path) = *find_middleware_file(project_path, page_extensions).await? else {
+        return Ok(Vc::cell(None));
+    };
+    let source = Vc::upcast(FileSource::new(path));
+    let module = context.process(
+        source,
+        Value::new(ReferenceType::EcmaScriptModules(
+            EcmaScriptModulesReferenceSubType::Undefined,
+        )),
+    );
+    Ok(Vc::cell(Some(
+        MiddlewareConfig {
+            path,
+            config: parse_config_from_source(module, source),
+        }
+        .cell(),
+    )))
+}
+
+#[turbo_tasks::value(shared)]
+struct MisplacedMiddlewareIssue {
+    path: Vc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for MisplacedMiddlewareIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("middleware".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("Middleware is in an unexpected location".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(
+            "Middleware is only picked up at the root of the project or in the `src` directory, \
+             next to the `app` and `pages` directories. This file is ignored."
+                .to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+    use turbopack_binding::turbopack::{
+        core::environment::ServerAddr,
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::get_middleware_config;
+    use crate::{
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{issues_of, Fixture},
+        util::MiddlewareMatcherKind,
+    };
+
+    #[tokio::test]
+    async fn test_get_middleware_config() -> Result<()> {
+        Fixture::new([
+            (
+                "middleware.ts",
+                "export const config = { matcher: ['/about/:path*', '/dashboard/:path*'] \
+                 }\nexport function middleware() {}\n",
+            ),
+            ("app/middleware.ts", "export function middleware() {}\n"),
+        ])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let middleware_config = get_middleware_config(
+                project_path,
+                Vc::cell(vec!["ts".to_string(), "js".to_string()]),
+                Vc::upcast(context),
+            );
+
+            let middleware = middleware_config
+                .await?
+                .expect("the root middleware should be found")
+                .await?;
+            assert_eq!(middleware.path.await?.path, "middleware.ts");
+            assert_eq!(
+                middleware.config.await?.matcher,
+                Some(vec![
+                    MiddlewareMatcherKind::Str("/about/:path*".to_string()),
+                    MiddlewareMatcherKind::Str("/dashboard/:path*".to_string()),
+                ])
+            );
+
+            let issues = issues_of(middleware_config).await?;
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].title, "Middleware is in an unexpected location");
+            assert!(issues[0].file_path.ends_with("app/middleware.ts"));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -14,7 +14,7 @@ use turbopack_binding::{
 };
 
 use super::app_route_entry::get_app_route_entry;
-use crate::{app_structure::MetadataItem, next_app::AppEntry};
+use crate::{app_structure::MetadataItem, next_app::AppEntry, next_config::NextConfig};
 
 /// Computes the entry for a Next.js favicon file.
 #[turbo_tasks::function]
@@ -23,6 +23,7 @@ pub async fn get_app_route_favicon_entry(
     edge_context: Vc<ModuleAssetContext>,
     favicon: MetadataItem,
     project_root: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
 ) -> Result<Vc<AppEntry>> {
     let path = match favicon {
         // TODO(alexkirsz) Is there a difference here?
@@ -87,5 +88,6 @@ pub async fn get_app_route_favicon_entry(
         "/favicon.ico".to_string(),
         "/favicon.ico".to_string(),
         project_root,
+        next_config,
     ))
 }
@@ -166,6 +166,7 @@ fn get_original_page_name(pathname: &str) -> String {
         "/" => "/page".to_string(),
         "/_not-found" => "/_not-found".to_string(),
         "/not-found" => "/not-found".to_string(),
+        _ if pathname.ends_with("/_not-found") => pathname.to_string(),
         _ => format!("{}/page", pathname),
     }
 }
@@ -21,12 +21,17 @@ use turbopack_binding::{
 };
 
 use crate::{
-    next_app::AppEntry,
-    parse_segment_config_from_source,
+    app_segment_config::NextSegmentConfig,
+    next_app::{edge_route_node_api_issue::check_edge_route_imports, AppEntry},
+    next_config::NextConfig,
+    parse_app_route_config,
     util::{load_next_js_template, virtual_next_js_template_path, NextRuntime},
 };
 
 /// Computes the entry for a Next.js app route.
+///
+/// Routes which don't export a `runtime` use the default runtime of the
+/// Next.js config, which is recorded in the config of the returned entry.
 #[turbo_tasks::function]
 pub async fn get_app_route_entry(
     nodejs_context: Vc<ModuleAssetContext>,
@@ -35,16 +40,22 @@ pub async fn get_app_route_entry(
     pathname: String,
     original_name: String,
     project_root: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
 ) -> Result<Vc<AppEntry>> {
-    let config = parse_segment_config_from_source(
-        nodejs_context.process(
-            source,
-            Value::new(ReferenceType::Entry(EntryReferenceSubType::AppRoute)),
-        ),
+    let nodejs_module = nodejs_context.process(
         source,
+        Value::new(ReferenceType::Entry(EntryReferenceSubType::AppRoute)),
     );
-    let is_edge = matches!(config.await?.runtime, Some(NextRuntime::Edge));
+    let config = parse_app_route_config(source, Vc::upcast(nodejs_context)).await?;
+    let runtime = next_config.await?.effective_runtime(config.runtime);
+    let config = NextSegmentConfig {
+        runtime: Some(runtime),
+        ..config.clone_value()
+    }
+    .cell();
+    let is_edge = runtime == NextRuntime::Edge;
     let context = if is_edge {
+        check_edge_route_imports(nodejs_module, source).await?;
         edge_context
     } else {
         nodejs_context
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use swc_core::{
+    common::source_map::Pos,
+    ecma::ast::{ModuleDecl, Program},
+};
+use turbo_tasks::{Completion, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_binding::turbopack::{
+    core::{
+        issue::{Issue, IssueExt, IssueSeverity, IssueSource, OptionIssueSource},
+        module::Module,
+        source::Source,
+    },
+    ecmascript::{parse::ParseResult, EcmascriptModuleAsset},
+};
+
+/// Node.js builtin modules which are not available in the edge runtime.
+pub(crate) const NODE_ONLY_MODULES: &[&str] = &[
+    "child_process",
+    "cluster",
+    "dgram",
+    "dns",
+    "fs",
+    "fs/promises",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "readline",
+    "repl",
+    "tls",
+    "tty",
+    "v8",
+    "vm",
+    "worker_threads",
+    "zlib",
+];
+
+/// Returns true if `request` refers to a Node.js builtin which can't be used
+/// in the edge runtime.
+fn is_node_only_module(request: &str) -> bool {
+    let request = request.strip_prefix("node:").unwrap_or(request);
+    NODE_ONLY_MODULES.contains(&request)
+}
+
+/// An issue for an edge route handler which imports a Node.js-only API.
+#[turbo_tasks::value(shared)]
+pub struct EdgeRouteNodeApiIssue {
+    pub path: Vc<FileSystemPath>,
+    pub request: String,
+    pub source: Vc<IssueSource>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for EdgeRouteNodeApiIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("unsupported".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "The Node.js module \"{}\" is not supported in the edge runtime",
+            self.request
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(
+            "This route handler is configured to use the edge runtime, which doesn't provide \
+             Node.js-only APIs. Remove the import or use `export const runtime = 'nodejs'`."
+                .to_string(),
+        )
+    }
+
+    #[turbo_tasks::function]
+    fn documentation_link(&self) -> Vc<String> {
+        Vc::cell(
+            "https://nextjs.org/docs/app/building-your-application/rendering/edge-and-nodejs-runtimes"
+                .to_string(),
+        )
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        OptionIssueSource::some(self.source)
+    }
+}
+
+/// Emits an [EdgeRouteNodeApiIssue] for every import of a Node.js-only
+/// builtin in the given edge route handler module.
+#[turbo_tasks::function]
+pub async fn check_edge_route_imports(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+) -> Result<Vc<Completion>> {
+    let Some(ecmascript_asset) =
+        Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(module).await?
+    else {
+        return Ok(Completion::new());
+    };
+
+    let ParseResult::Ok {
+        program: Program::Module(module_ast),
+        ..
+    } = &*ecmascript_asset.parse().await?
+    else {
+        return Ok(Completion::new());
+    };
+
+    for item in &module_ast.body {
+        let src = match item.as_module_decl() {
+            Some(ModuleDecl::Import(import)) => &import.src,
+            Some(ModuleDecl::ExportAll(export)) => &export.src,
+            Some(ModuleDecl::ExportNamed(export)) => match &export.src {
+                Some(src) => src,
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        if !is_node_only_module(&src.value) {
+            continue;
+        }
+
+        let span = src.span;
+        EdgeRouteNodeApiIssue {
+            path: source.ident().path(),
+            request: src.value.to_string(),
+            source: IssueSource::from_byte_offset(source, span.lo.to_usize(), span.hi.to_usize()),
+        }
+        .cell()
+        .emit();
+    }
+
+    Ok(Completion::new())
+}
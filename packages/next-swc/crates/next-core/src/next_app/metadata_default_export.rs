@@ -0,0 +1,177 @@
+use anyhow::Result;
+use swc_core::ecma::ast::{ExportSpecifier, ModuleDecl, ModuleExportName, ModuleItem, Program};
+use turbo_tasks::{Completion, Value, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_binding::turbopack::{
+    core::{
+        context::AssetContext,
+        file_source::FileSource,
+        issue::{Issue, IssueExt, IssueSeverity},
+        reference_type::{EntryReferenceSubType, ReferenceType},
+    },
+    ecmascript::{parse::ParseResult, EcmascriptModuleAsset},
+};
+
+use crate::app_structure::{GlobalMetadata, MetadataItem};
+
+/// Emits an error for dynamic `robots` and `sitemap` files of the global
+/// metadata which don't have a default export. Such routes silently respond
+/// with an empty document otherwise.
+#[turbo_tasks::function]
+pub async fn validate_metadata_default_exports(
+    global_metadata: Vc<GlobalMetadata>,
+    context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<Completion>> {
+    let GlobalMetadata {
+        robots, sitemap, ..
+    } = &*global_metadata.await?;
+    for item in [robots, sitemap] {
+        let Some(MetadataItem::Dynamic { path }) = *item else {
+            continue;
+        };
+        let source = Vc::upcast(FileSource::new(path));
+        let module = context.process(
+            source,
+            Value::new(ReferenceType::Entry(EntryReferenceSubType::AppRoute)),
+        );
+        let Some(ecmascript_asset) =
+            Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(module).await?
+        else {
+            continue;
+        };
+        let ParseResult::Ok {
+            program: Program::Module(module_ast),
+            ..
+        } = &*ecmascript_asset.parse().await?
+        else {
+            continue;
+        };
+        if !has_default_export(&module_ast.body) {
+            MissingMetadataDefaultExportIssue { path }.cell().emit();
+        }
+    }
+    Ok(Completion::new())
+}
+
+/// Whether the module has a default export, either declared in the module or
+/// re-exported from another one.
+fn has_default_export(body: &[ModuleItem]) -> bool {
+    body.iter().any(|item| match item.as_module_decl() {
+        Some(ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_)) => true,
+        Some(ModuleDecl::ExportNamed(named_export)) => {
+            named_export
+                .specifiers
+                .iter()
+                .any(|specifier| match specifier {
+                    ExportSpecifier::Default(_) => true,
+                    ExportSpecifier::Named(named) => matches!(
+                        named.exported.as_ref().unwrap_or(&named.orig),
+                        ModuleExportName::Ident(ident) if &*ident.sym == "default"
+                    ),
+                    ExportSpecifier::Namespace(_) => false,
+                })
+        }
+        _ => false,
+    })
+}
+
+#[turbo_tasks::value(shared)]
+struct MissingMetadataDefaultExportIssue {
+    path: Vc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for MissingMetadataDefaultExportIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("next app".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("Metadata route is missing a default export".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(
+            "Dynamic `robots` and `sitemap` files need to default export a function returning \
+             their content. Without it, the route responds with an empty document."
+                .to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+    use turbopack_binding::turbopack::{
+        core::environment::ServerAddr,
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::validate_metadata_default_exports;
+    use crate::{
+        app_structure::{get_global_metadata, AppStructureOptions},
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{issues_of, Fixture},
+    };
+
+    #[tokio::test]
+    async fn test_validate_metadata_default_exports() -> Result<()> {
+        Fixture::new([
+            (
+                "robots.ts",
+                "export default function robots() {\n  return { rules: { userAgent: '*' } }\n}\n",
+            ),
+            (
+                "sitemap.ts",
+                "export function sitemap() {\n  return [{ url: 'https://example.com' }]\n}\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let global_metadata = get_global_metadata(
+                app_dir,
+                Vc::cell(vec!["ts".to_string()]),
+                AppStructureOptions::default().cell(),
+            );
+            let issues = issues_of(validate_metadata_default_exports(
+                global_metadata,
+                Vc::upcast(context),
+            ))
+            .await?;
+
+            assert_eq!(issues.len(), 1);
+            assert_eq!(
+                issues[0].title,
+                "Metadata route is missing a default export"
+            );
+            assert!(issues[0].file_path.ends_with("sitemap.ts"));
+
+            Ok(())
+        })
+        .await
+    }
+}
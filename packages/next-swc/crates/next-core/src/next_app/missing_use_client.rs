@@ -0,0 +1,287 @@
+use anyhow::Result;
+use swc_core::{
+    common::{source_map::Pos, Span},
+    ecma::{
+        ast::{CallExpr, Callee, Expr, Lit, MemberProp, ModuleItem, Program, Stmt},
+        visit::{noop_visit_type, Visit, VisitWith},
+    },
+};
+use turbo_tasks::{Completion, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_binding::turbopack::{
+    core::{
+        issue::{Issue, IssueExt, IssueSeverity, IssueSource, OptionIssueSource},
+        module::Module,
+        source::Source,
+    },
+    ecmascript::{parse::ParseResult, EcmascriptModuleAsset},
+};
+
+/// React hooks which depend on state or effects, and are only available in
+/// client components.
+const CLIENT_ONLY_HOOKS: &[&str] = &[
+    "useContext",
+    "useDeferredValue",
+    "useEffect",
+    "useFormState",
+    "useFormStatus",
+    "useImperativeHandle",
+    "useInsertionEffect",
+    "useLayoutEffect",
+    "useOptimistic",
+    "useReducer",
+    "useRef",
+    "useState",
+    "useSyncExternalStore",
+    "useTransition",
+];
+
+/// Emits a warning when a module in the app directory calls client-only React
+/// hooks (e.g. `useState`) without a `"use client"` directive, which fails
+/// once the module is rendered as a server component.
+///
+/// This is an optional lint. Modules outside of `app_dir` are skipped, as they
+/// may only be imported from client components.
+#[turbo_tasks::function]
+pub async fn lint_missing_use_client(
+    app_dir: Vc<FileSystemPath>,
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+) -> Result<Vc<Completion>> {
+    let path = source.ident().path();
+    if !path.await?.is_inside_ref(&*app_dir.await?) {
+        return Ok(Completion::new());
+    }
+    let Some(ecmascript_asset) =
+        Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(module).await?
+    else {
+        return Ok(Completion::new());
+    };
+    let ParseResult::Ok {
+        program: Program::Module(module_ast),
+        ..
+    } = &*ecmascript_asset.parse().await?
+    else {
+        return Ok(Completion::new());
+    };
+
+    if has_use_client_directive(&module_ast.body) {
+        return Ok(Completion::new());
+    }
+    let hook_calls = find_client_only_hook_calls(&module_ast.body);
+    let Some(&(_, span)) = hook_calls.first() else {
+        return Ok(Completion::new());
+    };
+
+    let mut hooks = Vec::new();
+    for (hook, _) in hook_calls {
+        if !hooks.contains(&hook) {
+            hooks.push(hook);
+        }
+    }
+    MissingUseClientIssue {
+        path,
+        hooks,
+        source: IssueSource::from_byte_offset(source, span.lo.to_usize(), span.hi.to_usize()),
+    }
+    .cell()
+    .emit();
+    Ok(Completion::new())
+}
+
+/// Whether the module's directive prologue contains `"use client"`.
+pub(crate) fn has_use_client_directive(body: &[ModuleItem]) -> bool {
+    body.iter()
+        .map_while(|item| match item {
+            ModuleItem::Stmt(Stmt::Expr(expr_stmt)) => match &*expr_stmt.expr {
+                Expr::Lit(Lit::Str(str)) => Some(&*str.value),
+                _ => None,
+            },
+            _ => None,
+        })
+        .any(|directive| directive == "use client")
+}
+
+/// Returns the names and spans of all calls to client-only hooks, either
+/// called directly (`useState()`) or through a namespace (`React.useState()`).
+fn find_client_only_hook_calls(body: &[ModuleItem]) -> Vec<(String, Span)> {
+    let mut finder = HookCallFinder::default();
+    for item in body {
+        item.visit_with(&mut finder);
+    }
+    finder.calls
+}
+
+#[derive(Default)]
+struct HookCallFinder {
+    calls: Vec<(String, Span)>,
+}
+
+impl Visit for HookCallFinder {
+    noop_visit_type!();
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            let name = match &**callee {
+                Expr::Ident(ident) => Some(&*ident.sym),
+                Expr::Member(member) => match &member.prop {
+                    MemberProp::Ident(prop) => Some(&*prop.sym),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if let Some(name) = name.filter(|name| CLIENT_ONLY_HOOKS.contains(name)) {
+                self.calls.push((name.to_string(), call.span));
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct MissingUseClientIssue {
+    path: Vc<FileSystemPath>,
+    hooks: Vec<String>,
+    source: Vc<IssueSource>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for MissingUseClientIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("lint".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("React hooks are used in a server component".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "The module calls {}, which only work in client components. Add the \"use client\" \
+             directive at the top of the file to make it a client component.",
+            self.hooks.join(", ")
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        OptionIssueSource::some(self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use swc_core::{
+        common::{sync::Lrc, FileName, SourceMap},
+        ecma::{
+            ast::Module,
+            parser::{Parser, StringInput, Syntax},
+        },
+    };
+    use turbo_tasks::{Value, Vc};
+    use turbopack_binding::turbopack::{
+        core::{
+            context::AssetContext, environment::ServerAddr, file_source::FileSource,
+            reference_type::ReferenceType,
+        },
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::{find_client_only_hook_calls, has_use_client_directive, lint_missing_use_client};
+    use crate::{
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{issues_of, Fixture},
+    };
+
+    fn parse(code: &str) -> Module {
+        let cm = Lrc::<SourceMap>::default();
+        let fm = cm.new_source_file(FileName::Anon, code.to_string());
+        let mut parser = Parser::new(
+            Syntax::Es(Default::default()),
+            StringInput::from(&*fm),
+            None,
+        );
+        parser.parse_module().unwrap()
+    }
+
+    #[test]
+    fn test_use_client_directive() {
+        assert!(has_use_client_directive(
+            &parse("'use strict'\n'use client'\nexport default 1").body
+        ));
+        assert!(!has_use_client_directive(
+            &parse("export default 1\n'use client'").body
+        ));
+    }
+
+    #[test]
+    fn test_find_client_only_hook_calls() {
+        let module = parse(
+            "export default function Page() {\n  const [a] = React.useState(0)\n  useEffect(() => \
+             {})\n  const id = useId()\n  return a + id\n}",
+        );
+        let hooks = find_client_only_hook_calls(&module.body)
+            .into_iter()
+            .map(|(hook, _)| hook)
+            .collect::<Vec<_>>();
+        assert_eq!(hooks, vec!["useState", "useEffect"]);
+    }
+
+    #[tokio::test]
+    async fn test_lint_missing_use_client() -> Result<()> {
+        let server_component = "import { useState } from 'react'\nexport default function \
+                                Counter() {\n  const [count] = useState(0)\n  return count\n}\n";
+        Fixture::new([
+            ("app/page.js", server_component.to_string()),
+            (
+                "app/counter.js",
+                format!("'use client'\n{server_component}"),
+            ),
+            ("components/counter.js", server_component.to_string()),
+        ])?
+        .run(|project_path| async move {
+            let app_dir = project_path.join("app".to_string());
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let lint = |file: &str| {
+                let source = Vc::upcast(FileSource::new(project_path.join(file.to_string())));
+                let module = context.process(source, Value::new(ReferenceType::Undefined));
+                issues_of(lint_missing_use_client(app_dir, module, source))
+            };
+
+            let issues = lint("app/page.js").await?;
+            assert_eq!(issues.len(), 1);
+            assert!(issues[0].description.contains("useState"));
+            assert!(issues[0].description.contains("\"use client\""));
+
+            assert!(lint("app/counter.js").await?.is_empty());
+            assert!(lint("components/counter.js").await?.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -4,6 +4,10 @@ pub(crate) mod app_entry;
 pub(crate) mod app_favicon_entry;
 pub(crate) mod app_page_entry;
 pub(crate) mod app_route_entry;
+pub(crate) mod edge_route_node_api_issue;
+pub(crate) mod metadata_default_export;
+pub(crate) mod missing_use_client;
+pub(crate) mod root_layout_tags;
 pub(crate) mod unsupported_dynamic_metadata_issue;
 
 pub use app_client_references_chunks::{
@@ -14,4 +18,8 @@ pub use app_entry::AppEntry;
 pub use app_favicon_entry::get_app_route_favicon_entry;
 pub use app_page_entry::get_app_page_entry;
 pub use app_route_entry::get_app_route_entry;
+pub use edge_route_node_api_issue::EdgeRouteNodeApiIssue;
+pub use metadata_default_export::validate_metadata_default_exports;
+pub use missing_use_client::lint_missing_use_client;
+pub use root_layout_tags::lint_root_layout_tags;
 pub use unsupported_dynamic_metadata_issue::UnsupportedDynamicMetadataIssue;
@@ -0,0 +1,188 @@
+use anyhow::Result;
+use swc_core::ecma::{
+    ast::{JSXElementName, JSXOpeningElement, ModuleItem, Program},
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+use turbo_tasks::{Completion, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_binding::turbopack::{
+    core::{
+        issue::{Issue, IssueExt, IssueSeverity},
+        module::Module,
+        source::Source,
+    },
+    ecmascript::{parse::ParseResult, EcmascriptModuleAsset},
+};
+
+/// Tags which the root layout has to render.
+const ROOT_LAYOUT_TAGS: &[&str] = &["html", "body"];
+
+/// Emits a warning when a root layout doesn't render the `<html>` and
+/// `<body>` tags required by Next.js.
+///
+/// This is an optional lint, which only looks for the tags in the JSX of the
+/// module itself. Layouts rendering them through other components (e.g. a
+/// shared `<Document>`) are reported as well, which is why this is only a
+/// warning.
+#[turbo_tasks::function]
+pub async fn lint_root_layout_tags(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+) -> Result<Vc<Completion>> {
+    let Some(ecmascript_asset) =
+        Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(module).await?
+    else {
+        return Ok(Completion::new());
+    };
+    let ParseResult::Ok {
+        program: Program::Module(module_ast),
+        ..
+    } = &*ecmascript_asset.parse().await?
+    else {
+        return Ok(Completion::new());
+    };
+
+    let missing_tags = missing_root_layout_tags(&module_ast.body);
+    if !missing_tags.is_empty() {
+        MissingRootLayoutTagsIssue {
+            path: source.ident().path(),
+            missing_tags,
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Completion::new())
+}
+
+/// Returns the tags of [ROOT_LAYOUT_TAGS] which aren't rendered in the module.
+fn missing_root_layout_tags(body: &[ModuleItem]) -> Vec<String> {
+    let mut finder = TagFinder::default();
+    for item in body {
+        item.visit_with(&mut finder);
+    }
+    ROOT_LAYOUT_TAGS
+        .iter()
+        .filter(|tag| !finder.tags.iter().any(|found| found == *tag))
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+#[derive(Default)]
+struct TagFinder {
+    tags: Vec<String>,
+}
+
+impl Visit for TagFinder {
+    noop_visit_type!();
+
+    fn visit_jsx_opening_element(&mut self, element: &JSXOpeningElement) {
+        if let JSXElementName::Ident(ident) = &element.name {
+            self.tags.push(ident.sym.to_string());
+        }
+        element.visit_children_with(self);
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct MissingRootLayoutTagsIssue {
+    path: Vc<FileSystemPath>,
+    missing_tags: Vec<String>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for MissingRootLayoutTagsIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("lint".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("The root layout doesn't render the required tags".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "Root layouts have to render the <html> and <body> tags, but no {} tag was found in \
+             this layout. This can be ignored if the tags are rendered by another component.",
+            self.missing_tags
+                .iter()
+                .map(|tag| format!("<{tag}>"))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::{Value, Vc};
+    use turbopack_binding::turbopack::{
+        core::{
+            context::AssetContext, environment::ServerAddr, file_source::FileSource,
+            reference_type::ReferenceType,
+        },
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::lint_root_layout_tags;
+    use crate::{
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{issues_of, Fixture},
+    };
+
+    #[tokio::test]
+    async fn test_lint_root_layout_tags() -> Result<()> {
+        Fixture::new([
+            (
+                "layout.js",
+                "export default function RootLayout({ children }) {\n  return \
+                 <body>{children}</body>\n}\n",
+            ),
+            (
+                "(shop)/layout.js",
+                "export default function RootLayout({ children }) {\n  return (\n    <html \
+                 lang=\"en\">\n      <body>{children}</body>\n    </html>\n  )\n}\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let lint = |file: &str| {
+                let source = Vc::upcast(FileSource::new(app_dir.join(file.to_string())));
+                let module = context.process(source, Value::new(ReferenceType::Undefined));
+                issues_of(lint_root_layout_tags(module, source))
+            };
+
+            let issues = lint("layout.js").await?;
+            assert_eq!(issues.len(), 1);
+            assert!(issues[0].description.contains("no <html> tag"));
+
+            assert!(lint("(shop)/layout.js").await?.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -135,7 +135,7 @@ pub async fn get_client_resolve_options_context(
 ) -> Result<Vc<ResolveOptionsContext>> {
     let next_client_import_map =
         get_next_client_import_map(project_path, ty, next_config, execution_context);
-    let next_client_fallback_import_map = get_next_client_fallback_import_map(ty);
+    let next_client_fallback_import_map = get_next_client_fallback_import_map(project_path, ty);
     let next_client_resolved_map = get_next_client_resolved_map(project_path, project_path);
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().resolve().await?),
@@ -334,6 +334,18 @@ pub async fn get_client_runtime_entries(
         );
     }
 
+    // Injected runtime modules are evaluated in the configured order, right
+    // after the process env is available and before any bootstrap code.
+    for module in next_config.runtime_modules().await?.iter() {
+        runtime_entries.push(
+            RuntimeEntry::Request(
+                Request::parse_string(module.clone()),
+                project_root.join("_".to_string()),
+            )
+            .cell(),
+        );
+    }
+
     match mode {
         NextMode::DevServer => {
             let resolve_options_context = get_client_resolve_options_context(
@@ -0,0 +1,256 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use anyhow::Result;
+use swc_core::ecma::ast::{ModuleDecl, ModuleItem, Program};
+use turbo_tasks::{Completion, Vc};
+use turbopack_binding::{
+    turbo::tasks_fs::FileSystemPath,
+    turbopack::{
+        core::{
+            issue::{Issue, IssueExt, IssueSeverity},
+            module::Module,
+        },
+        ecmascript::parse::ParseResult,
+    },
+};
+
+use crate::{
+    next_app::missing_use_client::has_use_client_directive,
+    next_shared::module_graph::{parse_ecmascript_module, referenced_modules},
+};
+
+/// Reports an error for every module reachable from the client entry `entry`
+/// which imports `server-only`.
+#[turbo_tasks::function]
+pub fn validate_client_module_graph(entry: Vc<Box<dyn Module>>) -> Vc<Completion> {
+    validate_module_graph(entry, "server-only".to_string())
+}
+
+/// Reports an error for every module reachable from the server entry `entry`
+/// which imports `client-only`. Client components are not followed, as they
+/// are bundled for the client.
+#[turbo_tasks::function]
+pub fn validate_server_module_graph(entry: Vc<Box<dyn Module>>) -> Vc<Completion> {
+    validate_module_graph(entry, "client-only".to_string())
+}
+
+#[turbo_tasks::function]
+async fn validate_module_graph(
+    entry: Vc<Box<dyn Module>>,
+    forbidden_import: String,
+) -> Result<Vc<Completion>> {
+    let is_server = forbidden_import == "client-only";
+    let entry = entry.resolve().await?;
+    // Maps each visited module to the module it was first imported from.
+    let mut importers = HashMap::from([(entry, None)]);
+    let mut queue = VecDeque::from([entry]);
+
+    while let Some(module) = queue.pop_front() {
+        let Some(parse_result) = parse_ecmascript_module(module).await? else {
+            continue;
+        };
+        let ParseResult::Ok {
+            program: Program::Module(module_ast),
+            ..
+        } = &*parse_result
+        else {
+            continue;
+        };
+        let body = &module_ast.body;
+        if is_server && has_use_client_directive(body) {
+            continue;
+        }
+
+        if imports(body, &forbidden_import) {
+            let mut import_chain = vec![];
+            let mut current = Some(module);
+            while let Some(module) = current {
+                import_chain.push(module.ident().path().await?.path.clone());
+                current = importers[&module];
+            }
+            import_chain.reverse();
+            import_chain.push(forbidden_import.clone());
+
+            EnvironmentOnlyImportIssue {
+                file_path: module.ident().path(),
+                forbidden_import: forbidden_import.clone(),
+                import_chain,
+            }
+            .cell()
+            .emit();
+        }
+
+        for referenced in referenced_modules(module).await? {
+            if let Entry::Vacant(entry) = importers.entry(referenced) {
+                entry.insert(Some(module));
+                queue.push_back(referenced);
+            }
+        }
+    }
+
+    Ok(Completion::new())
+}
+
+/// Whether the module imports or re-exports from the module `specifier`.
+fn imports(body: &[ModuleItem], specifier: &str) -> bool {
+    body.iter().any(|item| {
+        let ModuleItem::ModuleDecl(decl) = item else {
+            return false;
+        };
+        let src = match decl {
+            ModuleDecl::Import(import) => Some(&import.src),
+            ModuleDecl::ExportAll(export) => Some(&export.src),
+            ModuleDecl::ExportNamed(export) => export.src.as_ref(),
+            _ => None,
+        };
+        src.is_some_and(|src| &*src.value == specifier)
+    })
+}
+
+/// An issue for a `server-only` module being bundled for the client, or a
+/// `client-only` module being bundled for the server.
+#[turbo_tasks::value(shared)]
+struct EnvironmentOnlyImportIssue {
+    file_path: Vc<FileSystemPath>,
+    forbidden_import: String,
+    import_chain: Vec<String>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for EnvironmentOnlyImportIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("resolve".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell(if self.forbidden_import == "server-only" {
+            "A module importing \"server-only\" is used in a client component".to_string()
+        } else {
+            "A module importing \"client-only\" is used in a server component".to_string()
+        })
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "\"{}\" marks modules which must not be bundled for this environment. Import \
+             chain:\n  {}",
+            self.forbidden_import,
+            self.import_chain.join("\n  -> ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::{Completion, ReadRef, Value, Vc};
+    use turbopack_binding::turbopack::{
+        core::{
+            context::AssetContext, environment::ServerAddr, file_source::FileSource,
+            issue::PlainIssue, reference_type::ReferenceType,
+        },
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::{validate_client_module_graph, validate_server_module_graph};
+    use crate::{
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{issues_of, Fixture},
+    };
+
+    /// Returns the environment-only import issues, skipping the resolve errors
+    /// of the fixture's unresolvable `server-only` and `client-only` imports.
+    async fn environment_only_issues(
+        validation: Vc<Completion>,
+    ) -> Result<Vec<ReadRef<PlainIssue>>> {
+        Ok(issues_of(validation)
+            .await?
+            .into_iter()
+            .filter(|issue| issue.title.contains("-only\""))
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_environment_only_imports() -> Result<()> {
+        Fixture::new([
+            (
+                "button.js",
+                "'use client'\nimport { getUser } from './data.js'\nimport 'client-only'\nexport \
+                 const Button = () => getUser()\n",
+            ),
+            (
+                "data.js",
+                "import 'server-only'\nexport const getUser = () => 'user'\n",
+            ),
+            (
+                "page.js",
+                "import { format } from './format.js'\nexport default () => format('page')\n",
+            ),
+            (
+                "format.js",
+                "import 'client-only'\nexport const format = (value) => value\n",
+            ),
+            (
+                "layout.js",
+                "import './button.js'\nexport default () => null\n",
+            ),
+        ])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let module = |file: &str| {
+                context.process(
+                    Vc::upcast(FileSource::new(project_path.join(file.to_string()))),
+                    Value::new(ReferenceType::Undefined),
+                )
+            };
+
+            let issues =
+                environment_only_issues(validate_client_module_graph(module("button.js"))).await?;
+            assert_eq!(issues.len(), 1);
+            assert!(issues[0].title.contains("\"server-only\""));
+            assert!(issues[0]
+                .description
+                .contains("button.js\n  -> data.js\n  -> server-only"));
+
+            let issues =
+                environment_only_issues(validate_server_module_graph(module("page.js"))).await?;
+            assert_eq!(issues.len(), 1);
+            assert!(issues[0].title.contains("\"client-only\""));
+            assert!(issues[0]
+                .description
+                .contains("page.js\n  -> format.js\n  -> client-only"));
+
+            // Client components imported from the server are bundled for the
+            // client, so their `client-only` import is fine.
+            let issues =
+                environment_only_issues(validate_server_module_graph(module("layout.js"))).await?;
+            assert!(issues.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
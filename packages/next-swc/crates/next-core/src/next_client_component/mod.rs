@@ -1,3 +1,4 @@
+pub mod environment_only;
 pub mod server_to_client_transition;
 pub mod ssr_client_module_transition;
 pub mod with_chunking_context_scope_asset;
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use turbo_tasks::{TryJoinIterExt, ValueToString, Vc};
+use turbopack_binding::turbopack::turbopack::ModuleAssetContext;
+
+use super::{ClientReferenceGraph, ClientReferenceType};
+use crate::{
+    app_structure::LoaderTree,
+    loader_tree::{LoaderTreeModule, ServerComponentTransition},
+    mode::NextMode,
+    next_server_component::NextServerComponentTransition,
+};
+
+/// The distinct client components (`"use client"` modules) reachable from a
+/// route.
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct ClientComponents {
+    pub count: usize,
+    /// Idents of the client component modules. Only collected when requested.
+    pub idents: Option<Vec<String>>,
+}
+
+/// Collects the client components reachable from the components of a route's
+/// loader tree. Routes with many client components ship a lot of client
+/// JavaScript, so this is useful for bundle analysis.
+///
+/// `context` must be the RSC module context of the app, so that client
+/// references are created for `"use client"` modules.
+#[turbo_tasks::function]
+pub async fn get_client_components_for_loader_tree(
+    loader_tree: Vc<LoaderTree>,
+    context: Vc<ModuleAssetContext>,
+    include_idents: bool,
+) -> Result<Vc<ClientComponents>> {
+    let server_component_transition = Vc::upcast(NextServerComponentTransition::new());
+    let LoaderTreeModule { inner_assets, .. } = LoaderTreeModule::build(
+        loader_tree,
+        context,
+        ServerComponentTransition::Transition(server_component_transition),
+        NextMode::Build,
+    )
+    .await?;
+
+    let client_reference_graph =
+        ClientReferenceGraph::new(Vc::cell(inner_assets.into_values().collect()));
+    let client_components: Vec<_> = client_reference_graph
+        .types()
+        .await?
+        .iter()
+        .filter_map(|ty| match ty {
+            ClientReferenceType::EcmascriptClientReference(module) => Some(*module),
+            ClientReferenceType::CssClientReference(_) => None,
+        })
+        .collect();
+
+    let idents = if include_idents {
+        Some(
+            client_components
+                .iter()
+                .map(|module| async move {
+                    Ok(module.await?.server_ident.to_string().await?.clone_value())
+                })
+                .try_join()
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    Ok(ClientComponents {
+        count: client_components.len(),
+        idents,
+    }
+    .cell())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+
+    use super::get_client_components_for_loader_tree;
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions, Entrypoint},
+        test_util::{client_reference_context, Fixture},
+    };
+
+    #[tokio::test]
+    async fn test_client_components_for_loader_tree() -> Result<()> {
+        Fixture::new([
+            (
+                "layout.js",
+                "import Button from './button.js'\nexport default function Layout() { return \
+                 Button }\n",
+            ),
+            (
+                "page.js",
+                "import Button from './button.js'\nimport Counter from './counter.js'\nimport \
+                 format from './format.js'\nexport default function Page() { return [Button, \
+                 Counter, format] }\n",
+            ),
+            (
+                "button.js",
+                "'use client'\nexport default function Button() { return null }\n",
+            ),
+            (
+                "counter.js",
+                "'use client'\nimport format from './format.js'\nexport default function \
+                 Counter() { return format(0) }\n",
+            ),
+            (
+                "format.js",
+                "export default function format(value) { return String(value) }\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let context = client_reference_context(app_dir);
+
+            let entrypoints = get_entrypoints(
+                app_dir,
+                Vc::cell(vec!["js".to_string()]),
+                AppStructureOptions::default().cell(),
+            )
+            .await?;
+            let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get("/") else {
+                panic!("expected a page at /");
+            };
+
+            // The button is used by both the layout and the page, but only
+            // counted once. Server modules aren't client components, even
+            // when a client component imports them.
+            let client_components =
+                get_client_components_for_loader_tree(loader_tree, context, false).await?;
+            assert_eq!(client_components.count, 2);
+            assert_eq!(client_components.idents, None);
+
+            let client_components =
+                get_client_components_for_loader_tree(loader_tree, context, true).await?;
+            assert_eq!(client_components.count, 2);
+            let idents = client_components.idents.as_ref().unwrap();
+            assert_eq!(idents.len(), 2);
+            assert!(idents.iter().any(|ident| ident.contains("button.js")));
+            assert!(idents.iter().any(|ident| ident.contains("counter.js")));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,7 +1,9 @@
+pub(crate) mod client_components;
 pub(crate) mod css_client_reference;
 pub(crate) mod ecmascript_client_reference;
 pub(crate) mod visit_client_reference;
 
+pub use client_components::{get_client_components_for_loader_tree, ClientComponents};
 pub use css_client_reference::css_client_reference_module::CssClientReferenceModule;
 pub use ecmascript_client_reference::{
     ecmascript_client_reference_module::EcmascriptClientReferenceModule,
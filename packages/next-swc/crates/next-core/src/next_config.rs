@@ -39,7 +39,11 @@ use turbopack_binding::{
     },
 };
 
-use crate::{embed_js::next_asset, next_shared::transforms::ModularizeImportPackageConfig};
+use crate::{
+    embed_js::next_asset,
+    next_shared::transforms::ModularizeImportPackageConfig,
+    util::{client_asset_public_prefix, NextRuntime},
+};
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -59,7 +63,7 @@ struct NextConfigAndCustomRoutes {
 struct CustomRoutesRaw {
     rewrites: Rewrites,
 
-    // unsupported
+    // not applied by the dev server, only passed on to deployment adapters
     headers: Vec<Header>,
     redirects: Vec<Redirect>,
 }
@@ -67,6 +71,7 @@ struct CustomRoutesRaw {
 #[turbo_tasks::value]
 struct CustomRoutes {
     rewrites: Vc<Rewrites>,
+    redirects_and_headers: Vc<RedirectsAndHeaders>,
 }
 
 #[turbo_tasks::value(serialization = "custom", eq = "manual")]
@@ -88,6 +93,7 @@ pub struct NextConfig {
     // Partially supported
     pub compiler: Option<CompilerConfig>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<OutputType>,
 
     // unsupported
@@ -238,6 +244,12 @@ pub struct Header {
     pub has: Option<Vec<RouteHas>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub missing: Option<Vec<RouteHas>>,
+    /// The regex `source` is matched with, compiled by the config loader.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    /// The names of the capture groups of `regex`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regex_keys: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TraceRawVcs)]
@@ -260,6 +272,12 @@ pub struct Redirect {
     pub has: Option<Vec<RouteHas>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub missing: Option<Vec<RouteHas>>,
+    /// The regex `source` is matched with, compiled by the config loader.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    /// The names of the capture groups of `regex`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regex_keys: Vec<String>,
 
     #[serde(flatten)]
     pub status: RedirectStatus,
@@ -278,6 +296,12 @@ pub struct Rewrite {
     pub has: Option<Vec<RouteHas>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub missing: Option<Vec<RouteHas>>,
+    /// The regex `source` is matched with, compiled by the config loader.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    /// The names of the capture groups of `regex`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regex_keys: Vec<String>,
 }
 
 #[turbo_tasks::value(eq = "manual")]
@@ -289,6 +313,15 @@ pub struct Rewrites {
     pub fallback: Vec<Rewrite>,
 }
 
+/// The redirects and headers of the config, in the order they're applied.
+#[turbo_tasks::value(eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectsAndHeaders {
+    pub redirects: Vec<Redirect>,
+    pub headers: Vec<Header>,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TraceRawVcs)]
 #[serde(rename_all = "camelCase")]
 pub struct TypeScriptConfig {
@@ -380,6 +413,10 @@ pub struct ExperimentalTurboConfig {
     pub loaders: Option<JsonValue>,
     pub rules: Option<IndexMap<String, RuleConfigItem>>,
     pub resolve_alias: Option<IndexMap<String, JsonValue>>,
+    /// Modules which are evaluated before any user code, in both client and
+    /// server runtimes, e.g. to initialize error reporting. Requests are
+    /// resolved relative to the project root.
+    pub runtime_modules: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TraceRawVcs)]
@@ -411,6 +448,43 @@ pub struct ExperimentalConfig {
     pub isr_memory_cache_size: Option<f64>,
     pub isr_flush_to_disk: Option<bool>,
     mdx_rs: Option<bool>,
+    /// Glob patterns of directory names inside the app directory which should
+    /// not be treated as route segments, e.g. `__tests__`.
+    pub app_dir_ignore_patterns: Option<Vec<String>>,
+    /// Severity overrides for app directory structure issues, keyed by issue
+    /// name (e.g. `orphaned-alt-text`, see `AppStructureIssue`), with `error`,
+    /// `warning`, `hint` or `info` as values.
+    pub app_dir_issue_severity: Option<IndexMap<String, String>>,
+    /// Additional file extensions for static metadata files, keyed by
+    /// metadata type, e.g. `{ "apple-icon": ["gif"] }`.
+    pub metadata_extensions: Option<IndexMap<String, Vec<String>>>,
+    /// Warn about static `opengraph-image` files below the recommended size.
+    pub validate_metadata_images: Option<bool>,
+    /// Warn about static metadata files above `maxMetadataFileSize`.
+    pub validate_metadata_file_size: Option<bool>,
+    /// Size in bytes above which static metadata files are reported.
+    pub max_metadata_file_size: Option<u64>,
+    /// Report static metadata manifests which aren't valid JSON or don't look
+    /// like a web app manifest.
+    pub validate_metadata_manifest: Option<bool>,
+    /// Maximum directory depth scanned inside the app directory.
+    pub app_dir_max_depth: Option<u32>,
+    /// Report properties of the `config` export of pages and API routes which
+    /// aren't known to Next.js, e.g. typos like `runtine`.
+    pub strict_source_config: Option<bool>,
+    /// Don't look for metadata files in the app directory.
+    pub disable_metadata_files: Option<bool>,
+    /// Maximum number of app subdirectories scanned in parallel.
+    pub app_dir_scan_concurrency: Option<u32>,
+    /// Scan app directories starting with an underscore, which are private
+    /// and skipped by default.
+    pub app_dir_scan_underscore_directories: Option<bool>,
+    /// Directory with metadata files applied to all routes, relative to the
+    /// project directory.
+    pub default_metadata_dir: Option<String>,
+    /// Warn about `loading` components which are never shown, as all segments
+    /// below them are static.
+    pub lint_unused_loading_boundaries: Option<bool>,
     pub swc_plugins: Option<Vec<(String, serde_json::Value)>>,
 
     // unsupported
@@ -441,11 +515,14 @@ pub struct ExperimentalConfig {
     optimistic_client_cache: Option<bool>,
     optimize_css: Option<serde_json::Value>,
     output_file_tracing_ignores: Option<Vec<String>>,
-    output_file_tracing_root: Option<String>,
+    /// Absolute path of the directory files are traced from for standalone
+    /// output, e.g. the root of a monorepo. Defaults to the project directory.
+    pub output_file_tracing_root: Option<String>,
     page_env: Option<bool>,
     profiling: Option<bool>,
     proxy_timeout: Option<f64>,
-    runtime: Option<serde_json::Value>,
+    /// The default runtime of routes, see [NextConfig::default_runtime].
+    pub runtime: Option<serde_json::Value>,
     scroll_restoration: Option<bool>,
     shared_pool: Option<bool>,
     sri: Option<serde_json::Value>,
@@ -505,6 +582,43 @@ pub enum RemoveConsoleConfig {
     Config { exclude: Option<Vec<String>> },
 }
 
+impl NextConfig {
+    /// The runtime used by routes which don't configure one themselves, as set
+    /// by `experimental.runtime`. Unknown values fall back to Node.js.
+    pub fn default_runtime(&self) -> NextRuntime {
+        self.experimental
+            .runtime
+            .clone()
+            .and_then(|runtime| serde_json::from_value(runtime).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns `runtime`, the runtime configured by a route itself, falling
+    /// back to [NextConfig::default_runtime] when there is none.
+    pub fn effective_runtime(&self, runtime: Option<NextRuntime>) -> NextRuntime {
+        runtime.unwrap_or_else(|| self.default_runtime())
+    }
+
+    /// The configured page extensions, without `md` and `mdx` unless MDX is
+    /// enabled with `experimental.mdxRs`, as those files can't be compiled
+    /// otherwise. Like `@next/mdx`, enabling MDX doesn't add them, they still
+    /// need to be listed in `pageExtensions`.
+    pub fn page_extensions_with_mdx(&self) -> Vec<String> {
+        let mdx = self.experimental.mdx_rs.unwrap_or(false);
+        self.page_extensions
+            .iter()
+            .filter(|ext| mdx || !matches!(ext.as_str(), "md" | "mdx"))
+            .cloned()
+            .collect()
+    }
+
+    /// The public URL prefix of client assets, taking `assetPrefix` and
+    /// `basePath` into account (see [client_asset_public_prefix]).
+    pub fn client_asset_public_prefix(&self) -> String {
+        client_asset_public_prefix(&self.asset_prefix, &self.base_path)
+    }
+}
+
 #[turbo_tasks::value_impl]
 impl NextConfig {
     #[turbo_tasks::function]
@@ -515,6 +629,11 @@ impl NextConfig {
         Ok(config.cell())
     }
 
+    #[turbo_tasks::function]
+    pub async fn to_json(self: Vc<Self>) -> Result<Vc<String>> {
+        Ok(Vc::cell(serde_json::to_string(&*self.await?)?))
+    }
+
     #[turbo_tasks::function]
     pub async fn server_component_externals(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
         Ok(Vc::cell(
@@ -539,6 +658,17 @@ impl NextConfig {
         ))
     }
 
+    #[turbo_tasks::function]
+    pub async fn app_dir_ignore_patterns(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
+        Ok(Vc::cell(
+            self.await?
+                .experimental
+                .app_dir_ignore_patterns
+                .clone()
+                .unwrap_or_default(),
+        ))
+    }
+
     #[turbo_tasks::function]
     pub async fn env(self: Vc<Self>) -> Result<Vc<EnvMap>> {
         // The value expected for env is Record<String, String>, but config itself
@@ -574,6 +704,13 @@ impl NextConfig {
         Ok(Vc::cell(self.await?.page_extensions.clone()))
     }
 
+    /// The page extensions used to find pages, routes and other special files
+    /// (see [NextConfig::page_extensions_with_mdx]).
+    #[turbo_tasks::function]
+    pub async fn resolved_page_extensions(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
+        Ok(Vc::cell(self.await?.page_extensions_with_mdx()))
+    }
+
     #[turbo_tasks::function]
     pub async fn transpile_packages(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
         Ok(Vc::cell(
@@ -642,6 +779,18 @@ impl NextConfig {
         Ok(alias_map.cell())
     }
 
+    #[turbo_tasks::function]
+    pub async fn runtime_modules(self: Vc<Self>) -> Result<Vc<Vec<String>>> {
+        Ok(Vc::cell(
+            self.await?
+                .experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.runtime_modules.clone())
+                .unwrap_or_default(),
+        ))
+    }
+
     #[turbo_tasks::function]
     pub async fn mdx_rs(self: Vc<Self>) -> Result<Vc<bool>> {
         Ok(Vc::cell(self.await?.experimental.mdx_rs.unwrap_or(false)))
@@ -699,6 +848,17 @@ pub async fn load_rewrites(execution_context: Vc<ExecutionContext>) -> Result<Vc
         .rewrites)
 }
 
+#[turbo_tasks::function]
+pub async fn load_redirects_and_headers(
+    execution_context: Vc<ExecutionContext>,
+) -> Result<Vc<RedirectsAndHeaders>> {
+    Ok(load_config_and_custom_routes(execution_context)
+        .await?
+        .custom_routes
+        .await?
+        .redirects_and_headers)
+}
+
 #[turbo_tasks::function]
 async fn load_config_and_custom_routes(
     execution_context: Vc<ExecutionContext>,
@@ -771,6 +931,7 @@ async fn load_next_config_and_custom_routes_internal(
             config: NextConfig::default().cell(),
             custom_routes: CustomRoutes {
                 rewrites: Rewrites::default().cell(),
+                redirects_and_headers: RedirectsAndHeaders::default().cell(),
             }
             .cell(),
         }
@@ -800,10 +961,16 @@ async fn load_next_config_and_custom_routes_internal(
         }
     }
 
+    let custom_routes = next_config_and_custom_routes.custom_routes;
     Ok(NextConfigAndCustomRoutes {
         config: next_config_and_custom_routes.config.cell(),
         custom_routes: CustomRoutes {
-            rewrites: next_config_and_custom_routes.custom_routes.rewrites.cell(),
+            rewrites: custom_routes.rewrites.cell(),
+            redirects_and_headers: RedirectsAndHeaders {
+                redirects: custom_routes.redirects,
+                headers: custom_routes.headers,
+            }
+            .cell(),
         }
         .cell(),
     }
@@ -884,3 +1051,97 @@ impl Issue for OutdatedConfigIssue {
         Vc::cell(self.description.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ExperimentalConfig, NextConfig, OutputType};
+    use crate::util::NextRuntime;
+
+    #[test]
+    fn test_serialize_config() {
+        // The defaults are applied by the JS config loader, this only checks
+        // how the resolved values are serialized: `output` is left out when
+        // unset and the experimental options are always an object.
+        let config = NextConfig {
+            page_extensions: vec!["tsx".to_string(), "ts".to_string()],
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&config).unwrap();
+        assert!(value.get("output").is_none());
+        assert_eq!(value["pageExtensions"], serde_json::json!(["tsx", "ts"]));
+        assert!(value["experimental"].is_object());
+
+        let config = NextConfig {
+            output: Some(OutputType::Standalone),
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["output"], "standalone");
+    }
+
+    #[test]
+    fn test_default_runtime() {
+        let config = NextConfig::default();
+        assert_eq!(config.default_runtime(), NextRuntime::NodeJs);
+
+        let config = NextConfig {
+            experimental: ExperimentalConfig {
+                runtime: Some(serde_json::json!("experimental-edge")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(config.default_runtime(), NextRuntime::Edge);
+    }
+
+    #[test]
+    fn test_effective_runtime() {
+        let node_default = NextConfig::default();
+        let edge_default = NextConfig {
+            experimental: ExperimentalConfig {
+                runtime: Some(serde_json::json!("edge")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            node_default.effective_runtime(Some(NextRuntime::Edge)),
+            NextRuntime::Edge
+        );
+        assert_eq!(
+            edge_default.effective_runtime(Some(NextRuntime::NodeJs)),
+            NextRuntime::NodeJs
+        );
+        assert_eq!(node_default.effective_runtime(None), NextRuntime::NodeJs);
+        assert_eq!(edge_default.effective_runtime(None), NextRuntime::Edge);
+    }
+
+    #[test]
+    fn test_page_extensions_with_mdx() {
+        let config = NextConfig {
+            page_extensions: vec!["tsx".to_string(), "mdx".to_string(), "md".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.page_extensions_with_mdx(), vec!["tsx"]);
+
+        let mdx_config = NextConfig {
+            experimental: ExperimentalConfig {
+                mdx_rs: Some(true),
+                ..Default::default()
+            },
+            ..config
+        };
+        assert_eq!(
+            mdx_config.page_extensions_with_mdx(),
+            vec!["tsx", "mdx", "md"]
+        );
+
+        // Enabling MDX doesn't add extensions that aren't configured.
+        let mdx_config = NextConfig {
+            page_extensions: vec!["tsx".to_string()],
+            ..mdx_config
+        };
+        assert_eq!(mdx_config.page_extensions_with_mdx(), vec!["tsx"]);
+    }
+}
@@ -22,7 +22,7 @@ use crate::{
     mode::NextMode,
     next_client::context::get_client_assets_path,
     next_config::NextConfig,
-    next_import_map::get_next_edge_import_map,
+    next_import_map::{get_next_edge_import_map, get_next_server_fallback_import_map},
     next_server::context::ServerContextType,
     next_shared::resolve::{ModuleFeatureReportResolvePlugin, UnsupportedModulesResolvePlugin},
     util::foreign_code_context_condition,
@@ -109,6 +109,7 @@ pub async fn get_edge_resolve_options_context(
         enable_node_modules: Some(project_path.root().resolve().await?),
         custom_conditions,
         import_map: Some(next_edge_import_map),
+        fallback_import_map: Some(get_next_server_fallback_import_map(project_path)),
         module: true,
         browser: true,
         plugins: vec![
@@ -1,4 +1,6 @@
 pub mod context;
+pub mod node_runtime;
 pub mod page_transition;
 pub mod route_regex;
 pub mod route_transition;
+pub mod unsupported;
@@ -0,0 +1,200 @@
+use anyhow::Result;
+use turbo_tasks::Vc;
+use turbopack_binding::{
+    turbo::tasks_fs::FileSystemPath,
+    turbopack::core::{
+        issue::{Issue, IssueDescriptionExt, IssueExt, IssueSeverity},
+        module::{Module, Modules},
+    },
+};
+
+use super::unsupported::EdgeRuntimeUnsupportedApiIssue;
+use crate::next_shared::module_graph::reachable_modules;
+
+/// Whether a route has to run in the Node.js runtime because its module graph
+/// uses APIs which aren't available in the edge runtime.
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct NodeRuntimeRequirement {
+    /// Node.js-only modules imported by the route, in the order they were
+    /// found.
+    pub node_only_modules: Vec<String>,
+}
+
+impl NodeRuntimeRequirement {
+    pub fn is_required(&self) -> bool {
+        !self.node_only_modules.is_empty()
+    }
+}
+
+/// Checks whether the route with the given entry module requires the Node.js
+/// runtime, by resolving its whole module graph with the edge runtime's
+/// detection of Node.js-only modules.
+///
+/// `entry` must be created in the edge module context of the app. The issues
+/// reported for Node.js-only modules are captured, as they are expected when
+/// the route can fall back to the Node.js runtime. All other issues are passed
+/// on.
+#[turbo_tasks::function]
+pub async fn get_node_runtime_requirement(
+    entry: Vc<Box<dyn Module>>,
+) -> Result<Vc<NodeRuntimeRequirement>> {
+    let modules = edge_module_graph(entry);
+    modules.await?;
+
+    let mut node_only_modules = Vec::new();
+    for issue in modules.take_issues_with_path().await?.await?.iter() {
+        match Vc::try_resolve_downcast_type::<EdgeRuntimeUnsupportedApiIssue>(issue).await? {
+            Some(unsupported) => {
+                let module = &unsupported.await?.module;
+                if !node_only_modules.contains(module) {
+                    node_only_modules.push(module.clone());
+                }
+            }
+            None => turbo_tasks::emit::<Box<dyn Issue>>(issue),
+        }
+    }
+
+    Ok(NodeRuntimeRequirement { node_only_modules }.cell())
+}
+
+/// Returns whether the route with the given entry module, which doesn't
+/// declare a runtime, has to use the Node.js runtime instead of the edge
+/// runtime (see [get_node_runtime_requirement]). Falling back to Node.js is
+/// reported as an informational issue.
+#[turbo_tasks::function]
+pub async fn requires_node_runtime(
+    entry: Vc<Box<dyn Module>>,
+    route_path: Vc<FileSystemPath>,
+) -> Result<Vc<bool>> {
+    let requirement = get_node_runtime_requirement(entry).await?;
+    if !requirement.is_required() {
+        return Ok(Vc::cell(false));
+    }
+
+    NodeRuntimeSelectedIssue {
+        route_path,
+        node_only_modules: requirement.node_only_modules.clone(),
+    }
+    .cell()
+    .emit();
+    Ok(Vc::cell(true))
+}
+
+/// Resolves all modules reachable from `entry`. Resolving is what reports
+/// Node.js-only modules in the edge context.
+#[turbo_tasks::function]
+async fn edge_module_graph(entry: Vc<Box<dyn Module>>) -> Result<Vc<Modules>> {
+    Ok(Vc::cell(reachable_modules(entry).await?))
+}
+
+/// An informational issue for a route being switched to the Node.js runtime
+/// because it uses Node.js-only modules.
+#[turbo_tasks::value(shared)]
+struct NodeRuntimeSelectedIssue {
+    route_path: Vc<FileSystemPath>,
+    node_only_modules: Vec<String>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for NodeRuntimeSelectedIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Info.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("runtime".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.route_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("The route uses the Node.js runtime".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "The route imports Node.js-only modules ({}), so it can't run in the edge runtime.",
+            self.node_only_modules.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::{Value, Vc};
+    use turbopack_binding::turbopack::{
+        core::{
+            context::AssetContext, environment::ServerAddr, file_source::FileSource,
+            reference_type::ReferenceType, resolve::options::ImportMap,
+        },
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::get_node_runtime_requirement;
+    use crate::{
+        next_edge::{
+            context::get_edge_compile_time_info, unsupported::insert_edge_unsupported_node_aliases,
+        },
+        test_util::Fixture,
+    };
+
+    #[tokio::test]
+    async fn test_node_only_import_requires_node_runtime() -> Result<()> {
+        Fixture::new([
+            (
+                "route.js",
+                "import { read } from './read.js'\nexport const GET = () => new Response(read())\n",
+            ),
+            (
+                "read.js",
+                "import fs from 'fs'\nexport const read = () => fs.readFileSync('data.txt')\n",
+            ),
+            ("edge.js", "export const GET = () => new Response('ok')\n"),
+        ])?
+        .run(|project_path| async move {
+            let mut import_map = ImportMap::empty();
+            insert_edge_unsupported_node_aliases(&mut import_map);
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext {
+                    import_map: Some(import_map.cell()),
+                    ..Default::default()
+                }
+                .cell(),
+            );
+            let requirement = |file: &str| {
+                let module = context.process(
+                    Vc::upcast(FileSource::new(project_path.join(file.to_string()))),
+                    Value::new(ReferenceType::Undefined),
+                );
+                get_node_runtime_requirement(module)
+            };
+
+            let node_route = requirement("route.js").await?;
+            assert!(node_route.is_required());
+            assert_eq!(node_route.node_only_modules, vec!["fs".to_string()]);
+
+            let edge_route = requirement("edge.js").await?;
+            assert!(!edge_route.is_required());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use turbo_tasks::Vc;
+use turbopack_binding::{
+    turbo::tasks_fs::FileSystemPath,
+    turbopack::core::{
+        issue::{Issue, IssueExt, IssueSeverity},
+        resolve::{
+            options::{ImportMap, ImportMapResult, ImportMapping, ImportMappingReplacement},
+            parse::Request,
+            ResolveResult, ResolveResultItem,
+        },
+    },
+};
+
+use crate::next_app::edge_route_node_api_issue::NODE_ONLY_MODULES;
+
+/// Maps all Node.js-only builtins (with and without the `node:` prefix) to a
+/// replacement which reports an [EdgeRuntimeUnsupportedApiIssue].
+pub fn insert_edge_unsupported_node_aliases(import_map: &mut ImportMap) {
+    let mapping: Vc<ImportMapping> =
+        ImportMapping::Dynamic(Vc::upcast(NextEdgeUnsupportedModuleReplacer::new())).into();
+    for module in NODE_ONLY_MODULES {
+        import_map.insert_exact_alias(*module, mapping);
+        import_map.insert_exact_alias(format!("node:{module}"), mapping);
+    }
+}
+
+#[turbo_tasks::value(shared)]
+pub struct NextEdgeUnsupportedModuleReplacer {}
+
+#[turbo_tasks::value_impl]
+impl NextEdgeUnsupportedModuleReplacer {
+    #[turbo_tasks::function]
+    pub fn new() -> Vc<Self> {
+        NextEdgeUnsupportedModuleReplacer {}.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMappingReplacement for NextEdgeUnsupportedModuleReplacer {
+    #[turbo_tasks::function]
+    fn replace(&self, _capture: String) -> Vc<ImportMapping> {
+        ImportMapping::Ignore.into()
+    }
+
+    /// Reports the usage of a Node.js-only module and resolves it to nothing,
+    /// instead of failing with a generic resolve error.
+    #[turbo_tasks::function]
+    async fn result(
+        &self,
+        context: Vc<FileSystemPath>,
+        request: Vc<Request>,
+    ) -> Result<Vc<ImportMapResult>> {
+        let Request::Module { module, .. } = &*request.await? else {
+            return Ok(ImportMapResult::NoEntry.into());
+        };
+
+        EdgeRuntimeUnsupportedApiIssue {
+            file_path: context,
+            module: module.clone(),
+        }
+        .cell()
+        .emit();
+
+        Ok(
+            ImportMapResult::Result(ResolveResult::primary(ResolveResultItem::Ignore).into())
+                .into(),
+        )
+    }
+}
+
+/// An issue for a Node.js-only API being used in code running in the edge
+/// runtime.
+#[turbo_tasks::value(shared)]
+pub struct EdgeRuntimeUnsupportedApiIssue {
+    pub file_path: Vc<FileSystemPath>,
+    pub module: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for EdgeRuntimeUnsupportedApiIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("resolve".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "The Node.js module \"{}\" is not supported in the edge runtime",
+            self.module
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(
+            "Code running in the edge runtime can't use Node.js-only APIs. Remove the import or \
+             use `export const runtime = 'nodejs'`."
+                .to_string(),
+        )
+    }
+
+    #[turbo_tasks::function]
+    fn documentation_link(&self) -> Vc<String> {
+        Vc::cell("https://nextjs.org/docs/messages/node-module-in-edge-runtime".to_string())
+    }
+}
@@ -7,7 +7,10 @@ use turbopack_binding::{
     turbopack::{
         core::{
             resolve::{
-                options::{ConditionValue, ImportMap, ImportMapping, ResolveOptions, ResolvedMap},
+                options::{
+                    ConditionValue, ImportMap, ImportMapResult, ImportMapping,
+                    ImportMappingReplacement, ResolveOptions, ResolvedMap,
+                },
                 parse::Request,
                 pattern::Pattern,
                 resolve, AliasPattern, ResolveAliasMap, SubpathValue,
@@ -24,11 +27,13 @@ use crate::{
     mode::NextMode,
     next_client::context::ClientContextType,
     next_config::NextConfig,
+    next_edge::unsupported::insert_edge_unsupported_node_aliases,
     next_font::{
         google::{NextFontGoogleCssModuleReplacer, NextFontGoogleReplacer},
         local::{NextFontLocalCssModuleReplacer, NextFontLocalReplacer},
     },
     next_server::context::ServerContextType,
+    transform_options::get_tsconfig_base_url,
     util::NextRuntime,
 };
 
@@ -159,9 +164,14 @@ pub fn get_next_build_import_map() -> Vc<ImportMap> {
 /// Computes the Next-specific client fallback import map, which provides
 /// polyfills to Node.js externals.
 #[turbo_tasks::function]
-pub fn get_next_client_fallback_import_map(ty: Value<ClientContextType>) -> Vc<ImportMap> {
+pub async fn get_next_client_fallback_import_map(
+    project_path: Vc<FileSystemPath>,
+    ty: Value<ClientContextType>,
+) -> Result<Vc<ImportMap>> {
     let mut import_map = ImportMap::empty();
 
+    insert_tsconfig_base_url_alias(&mut import_map, project_path).await?;
+
     match ty.into_value() {
         ClientContextType::Pages {
             pages_dir: context_dir,
@@ -180,7 +190,20 @@ pub fn get_next_client_fallback_import_map(ty: Value<ClientContextType>) -> Vc<I
 
     insert_turbopack_dev_alias(&mut import_map);
 
-    import_map.cell()
+    Ok(import_map.cell())
+}
+
+/// Computes the Next-specific server-side fallback import map, used for both
+/// the Node.js and the edge runtime.
+#[turbo_tasks::function]
+pub async fn get_next_server_fallback_import_map(
+    project_path: Vc<FileSystemPath>,
+) -> Result<Vc<ImportMap>> {
+    let mut import_map = ImportMap::empty();
+
+    insert_tsconfig_base_url_alias(&mut import_map, project_path).await?;
+
+    Ok(import_map.cell())
 }
 
 /// Computes the Next-specific server-side import map.
@@ -286,6 +309,8 @@ pub async fn get_next_edge_import_map(
 
     insert_next_server_special_aliases(&mut import_map, ty, mode, NextRuntime::Edge).await?;
 
+    insert_edge_unsupported_node_aliases(&mut import_map);
+
     match ty {
         ServerContextType::Pages { .. } | ServerContextType::PagesData { .. } => {}
         ServerContextType::AppSSR { .. }
@@ -646,7 +671,9 @@ pub async fn get_next_package(context_directory: Vc<FileSystemPath>) -> Result<V
         .first_source()
         .await?
         .context("Next.js package not found")?;
-    Ok(source.ident().path().parent())
+    // Package managers like pnpm link packages from a virtual store, resolve
+    // through those links so that files next to the package are found.
+    Ok(source.ident().path().realpath().parent())
 }
 
 pub async fn insert_alias_option<const N: usize>(
@@ -716,6 +743,70 @@ fn insert_package_alias(
     );
 }
 
+/// Resolves bare imports relative to the tsconfig `baseUrl`. This is meant
+/// for fallback import maps, so packages in `node_modules` take precedence,
+/// like they do with webpack.
+async fn insert_tsconfig_base_url_alias(
+    import_map: &mut ImportMap,
+    project_path: Vc<FileSystemPath>,
+) -> Result<()> {
+    if let Some(base_url) = get_tsconfig_base_url(project_path).await? {
+        import_map.insert_wildcard_alias(
+            "",
+            ImportMapping::Dynamic(Vc::upcast(TsconfigBaseUrlReplacer::new(base_url))).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Uses the tsconfig `baseUrl` as an additional module root. Only bare
+/// imports (e.g. `components/Foo`) are resolved in it, relative and absolute
+/// imports are left untouched.
+#[turbo_tasks::value(shared)]
+pub struct TsconfigBaseUrlReplacer {
+    base_url: Vc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl TsconfigBaseUrlReplacer {
+    #[turbo_tasks::function]
+    pub fn new(base_url: Vc<FileSystemPath>) -> Vc<Self> {
+        TsconfigBaseUrlReplacer { base_url }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMappingReplacement for TsconfigBaseUrlReplacer {
+    /// The wildcard alias matches every request, so the mapping is decided in
+    /// [Self::result], where the kind of request is known.
+    #[turbo_tasks::function]
+    fn replace(self: Vc<Self>, _capture: String) -> Vc<ImportMapping> {
+        ImportMapping::Dynamic(Vc::upcast(self)).into()
+    }
+
+    #[turbo_tasks::function]
+    async fn result(
+        &self,
+        _context: Vc<FileSystemPath>,
+        request: Vc<Request>,
+    ) -> Result<Vc<ImportMapResult>> {
+        let Request::Module {
+            module,
+            path: Pattern::Constant(path),
+            ..
+        } = &*request.await?
+        else {
+            return Ok(ImportMapResult::NoEntry.into());
+        };
+
+        Ok(ImportMapResult::Alias(
+            Request::parse(Value::new(Pattern::Constant(format!("./{module}{path}")))),
+            Some(self.base_url),
+        )
+        .into())
+    }
+}
+
 /// Inserts an alias to @vercel/turbopack-dev into an import map.
 fn insert_turbopack_dev_alias(import_map: &mut ImportMap) {
     insert_package_alias(
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
+use indexmap::IndexSet;
 use indoc::formatdoc;
 use turbo_tasks::{TryJoinIterExt, ValueToString, Vc};
 use turbo_tasks_fs::{File, FileSystemPath};
 use turbopack_binding::turbopack::{
+    build::BuildChunkingContext,
     core::{
         asset::AssetContent, chunk::ModuleId as TurbopackModuleId, output::OutputAsset,
         virtual_output::VirtualOutputAsset,
@@ -11,12 +13,18 @@ use turbopack_binding::turbopack::{
         chunk::{EcmascriptChunkItemExt, EcmascriptChunkPlaceable, EcmascriptChunkingContext},
         utils::StringifyJs,
     },
+    turbopack::ModuleAssetContext,
 };
 
 use super::{ClientReferenceManifest, ManifestNode, ManifestNodeEntry, ModuleId};
 use crate::{
-    next_app::ClientReferencesChunks,
-    next_client_reference::{ClientReferenceType, ClientReferences},
+    app_structure::LoaderTree,
+    loader_tree::{LoaderTreeModule, ServerComponentTransition},
+    mode::NextMode,
+    next_app::{get_app_client_references_chunks, ClientReferencesChunks},
+    next_client_reference::{ClientReferenceGraph, ClientReferenceType, ClientReferences},
+    next_config::NextConfig,
+    next_server_component::NextServerComponentTransition,
 };
 
 #[turbo_tasks::value_impl]
@@ -30,8 +38,101 @@ impl ClientReferenceManifest {
         client_references_chunks: Vc<ClientReferencesChunks>,
         client_chunking_context: Vc<Box<dyn EcmascriptChunkingContext>>,
         ssr_chunking_context: Vc<Box<dyn EcmascriptChunkingContext>>,
+        next_config: Vc<NextConfig>,
     ) -> Result<Vc<Box<dyn OutputAsset>>> {
+        let entry_manifest = ClientReferenceManifest::build(
+            node_root,
+            client_relative_path,
+            client_references,
+            client_references_chunks,
+            client_chunking_context,
+            ssr_chunking_context,
+            next_config,
+        )
+        .await?;
+        let client_reference_manifest_json = serde_json::to_string(&entry_manifest).unwrap();
+
+        Ok(Vc::upcast(VirtualOutputAsset::new(
+            node_root.join(format!(
+                "server/app/{entry_name}_client-reference-manifest.js",
+            )),
+            AssetContent::file(
+                File::from(formatdoc! {
+                    r#"
+                            globalThis.__RSC_MANIFEST = globalThis.__RSC_MANIFEST || {{}};
+                            globalThis.__RSC_MANIFEST[{entry_name}] = {manifest}
+                        "#,
+                    entry_name = StringifyJs(&entry_name),
+                    manifest = StringifyJs(&client_reference_manifest_json)
+                })
+                .into(),
+            ),
+        )))
+    }
+}
+
+impl ClientReferenceManifest {
+    /// Builds the client reference manifest of the route with the given loader
+    /// tree, without building the rest of the route. This maps the client
+    /// components of the route to their module ids, export names and chunks
+    /// for the client and for SSR.
+    ///
+    /// `context` must be the RSC module context of the app, so that client
+    /// references are created for `"use client"` modules.
+    pub async fn for_loader_tree(
+        loader_tree: Vc<LoaderTree>,
+        context: Vc<ModuleAssetContext>,
+        node_root: Vc<FileSystemPath>,
+        client_relative_path: Vc<FileSystemPath>,
+        client_chunking_context: Vc<Box<dyn EcmascriptChunkingContext>>,
+        ssr_chunking_context: Vc<BuildChunkingContext>,
+        next_config: Vc<NextConfig>,
+    ) -> Result<Self> {
+        let server_component_transition = Vc::upcast(NextServerComponentTransition::new());
+        let LoaderTreeModule { inner_assets, .. } = LoaderTreeModule::build(
+            loader_tree,
+            context,
+            ServerComponentTransition::Transition(server_component_transition),
+            NextMode::Build,
+        )
+        .await?;
+        let entries = inner_assets.into_values().collect::<Vec<_>>();
+
+        let client_reference_graph = ClientReferenceGraph::new(Vc::cell(entries.clone()));
+        let mut client_references = IndexSet::new();
+        for entry in entries {
+            client_references.extend(client_reference_graph.entry(entry).await?.iter().copied());
+        }
+        let client_references_chunks = get_app_client_references_chunks(
+            client_reference_graph.types(),
+            client_chunking_context,
+            ssr_chunking_context,
+        );
+
+        ClientReferenceManifest::build(
+            node_root,
+            client_relative_path,
+            Vc::cell(client_references.into_iter().collect()),
+            client_references_chunks,
+            client_chunking_context,
+            Vc::upcast(ssr_chunking_context),
+            next_config,
+        )
+        .await
+    }
+
+    /// Builds the manifest for the given client references of an entry.
+    async fn build(
+        node_root: Vc<FileSystemPath>,
+        client_relative_path: Vc<FileSystemPath>,
+        client_references: Vc<ClientReferences>,
+        client_references_chunks: Vc<ClientReferencesChunks>,
+        client_chunking_context: Vc<Box<dyn EcmascriptChunkingContext>>,
+        ssr_chunking_context: Vc<Box<dyn EcmascriptChunkingContext>>,
+        next_config: Vc<NextConfig>,
+    ) -> Result<Self> {
         let mut entry_manifest: ClientReferenceManifest = Default::default();
+        entry_manifest.module_loading.prefix = next_config.await?.client_asset_public_prefix();
         let client_references_chunks = client_references_chunks.await?;
         let client_relative_path = client_relative_path.await?;
         let node_root_ref = node_root.await?;
@@ -174,24 +275,7 @@ impl ClientReferenceManifest {
             }
         }
 
-        let client_reference_manifest_json = serde_json::to_string(&entry_manifest).unwrap();
-
-        Ok(Vc::upcast(VirtualOutputAsset::new(
-            node_root.join(format!(
-                "server/app/{entry_name}_client-reference-manifest.js",
-            )),
-            AssetContent::file(
-                File::from(formatdoc! {
-                    r#"
-                            globalThis.__RSC_MANIFEST = globalThis.__RSC_MANIFEST || {{}};
-                            globalThis.__RSC_MANIFEST[{entry_name}] = {manifest}
-                        "#,
-                    entry_name = StringifyJs(&entry_name),
-                    manifest = StringifyJs(&client_reference_manifest_json)
-                })
-                .into(),
-            ),
-        )))
+        Ok(entry_manifest)
     }
 }
 
@@ -212,3 +296,116 @@ pub fn get_client_reference_module_key(server_path: &str, export_name: &str) ->
         format!("{}#{}", server_path, export_name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+    use turbopack_binding::turbopack::{
+        build::BuildChunkingContext, core::environment::ServerAddr, dev::DevChunkingContext,
+    };
+
+    use super::ClientReferenceManifest;
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions, Entrypoint},
+        next_config::NextConfig,
+        next_edge::context::get_edge_compile_time_info,
+        test_util::{client_reference_context, Fixture},
+    };
+
+    #[tokio::test]
+    async fn test_for_loader_tree() -> Result<()> {
+        Fixture::new([
+            (
+                "layout.js",
+                "export default function Layout({ children }) { return children }\n",
+            ),
+            (
+                "page.js",
+                "import Button from './button.js'\nexport default function Page() { return Button \
+                 }\n",
+            ),
+            (
+                "button.js",
+                "'use client'\nexport default function Button() { return null }\n",
+            ),
+        ])?
+        .run(|app_dir| async move {
+            let environment =
+                get_edge_compile_time_info(app_dir, ServerAddr::empty()).environment();
+            let node_root = app_dir.join(".next/server".to_string());
+            let client_root = app_dir.join(".next/client".to_string());
+            let client_chunking_context = DevChunkingContext::builder(
+                app_dir,
+                client_root,
+                client_root.join("_next/static/chunks".to_string()),
+                client_root.join("_next/static/media".to_string()),
+                environment,
+            )
+            .build();
+            let ssr_chunking_context = BuildChunkingContext::builder(
+                app_dir,
+                node_root,
+                node_root.join("chunks".to_string()),
+                client_root.join("_next/static/media".to_string()),
+                environment,
+            )
+            .build();
+
+            let entrypoints = get_entrypoints(
+                app_dir,
+                Vc::cell(vec!["js".to_string()]),
+                AppStructureOptions::default().cell(),
+            )
+            .await?;
+            let Some(&Entrypoint::AppPage { loader_tree, .. }) = entrypoints.get("/") else {
+                panic!("expected a page at /");
+            };
+
+            let manifest = ClientReferenceManifest::for_loader_tree(
+                loader_tree,
+                client_reference_context(app_dir),
+                node_root,
+                client_root,
+                Vc::upcast(client_chunking_context),
+                ssr_chunking_context,
+                NextConfig::default().cell(),
+            )
+            .await?;
+
+            assert_eq!(manifest.module_loading.prefix, "/_next/");
+
+            // The button is the only client component, and the module key is
+            // its server path.
+            let client_modules = &manifest.client_modules.module_exports;
+            assert_eq!(client_modules.len(), 1);
+            let (key, entry) = client_modules.iter().next().unwrap();
+            assert!(key.ends_with("button.js"), "{key}");
+            assert_eq!(entry.name, "*");
+            assert!(!entry.chunks.is_empty());
+            assert!(entry
+                .chunks
+                .iter()
+                .all(|chunk| chunk.starts_with("_next/static/chunks/")));
+
+            // Its client module id maps to the SSR module and chunks.
+            assert_eq!(manifest.ssr_module_mapping.len(), 1);
+            let ssr_entry = &manifest.ssr_module_mapping[&entry.id].module_exports["*"];
+            assert_eq!(ssr_entry.name, "*");
+            assert!(!ssr_entry.chunks.is_empty());
+            assert!(ssr_entry
+                .chunks
+                .iter()
+                .all(|chunk| chunk.starts_with("chunks/")));
+
+            // There is no CSS.
+            assert!(manifest
+                .entry_css_files
+                .values()
+                .all(|files| files.is_empty()));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use turbo_tasks::Vc;
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbopack_binding::turbopack::core::{
+    asset::AssetContent, context::AssetContext, file_source::FileSource, output::OutputAsset,
+    virtual_output::VirtualOutputAsset,
+};
+
+use super::{FunctionConfig, FunctionsConfigManifest, Regions};
+use crate::{
+    app_segment_config::{
+        get_app_route_runtime, parse_app_route_config, parse_segment_config_from_loader_tree,
+    },
+    app_structure::{Entrypoint, Entrypoints},
+    next_config::NextConfig,
+};
+
+/// Emits `server/functions-config-manifest.json`, which describes the runtime,
+/// regions and `maxDuration` of the app pages and route handlers, keyed by
+/// their pathname. These are read from the segment config of each route.
+#[turbo_tasks::function]
+pub async fn functions_config_manifest(
+    node_root: Vc<FileSystemPath>,
+    entrypoints: Vc<Entrypoints>,
+    context: Vc<Box<dyn AssetContext>>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<Box<dyn OutputAsset>>> {
+    let mut functions = HashMap::new();
+    for (pathname, entrypoint) in entrypoints.await?.iter() {
+        let (config, runtime) = match *entrypoint {
+            Entrypoint::AppPage { loader_tree, .. } => {
+                let config = parse_segment_config_from_loader_tree(loader_tree, context).await?;
+                let runtime = next_config.await?.effective_runtime(config.runtime);
+                (config, runtime)
+            }
+            Entrypoint::AppRoute { path, .. } => (
+                parse_app_route_config(Vc::upcast(FileSource::new(path)), context).await?,
+                get_app_route_runtime(path, context, next_config).await?,
+            ),
+        };
+        functions.insert(
+            pathname.clone(),
+            FunctionConfig {
+                runtime,
+                regions: config.preferred_region.clone().map(Regions::Single),
+                max_duration: config.max_duration,
+            },
+        );
+    }
+
+    let manifest = FunctionsConfigManifest {
+        functions,
+        ..Default::default()
+    };
+    Ok(Vc::upcast(VirtualOutputAsset::new(
+        node_root.join("server/functions-config-manifest.json".to_string()),
+        AssetContent::file(File::from(serde_json::to_string_pretty(&manifest)?).into()),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+    use turbo_tasks_fs::FileContent;
+    use turbopack_binding::turbopack::{
+        core::{asset::Asset, environment::ServerAddr, output::OutputAsset},
+        turbopack::{
+            module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+            ModuleAssetContext,
+        },
+    };
+
+    use super::functions_config_manifest;
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions},
+        next_config::NextConfig,
+        next_edge::context::get_edge_compile_time_info,
+        test_util::Fixture,
+    };
+
+    #[tokio::test]
+    async fn test_functions_config_manifest() -> Result<()> {
+        Fixture::new([
+            ("app/layout.js", "export default function Layout() {}\n"),
+            (
+                "app/page.js",
+                "export const maxDuration = 10\nexport default function Page() {}\n",
+            ),
+            (
+                "app/api/users/route.js",
+                "export const runtime = 'edge'\nexport const preferredRegion = 'iad1'\nexport \
+                 const maxDuration = 30\nexport function GET() {}\n",
+            ),
+        ])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let entrypoints = get_entrypoints(
+                project_path.join("app".to_string()),
+                Vc::cell(vec!["js".to_string()]),
+                AppStructureOptions::default().cell(),
+            );
+            let manifest = functions_config_manifest(
+                project_path.join(".next".to_string()),
+                entrypoints,
+                Vc::upcast(context),
+                NextConfig::default().cell(),
+            );
+
+            assert_eq!(
+                manifest.ident().path().await?.path,
+                ".next/server/functions-config-manifest.json"
+            );
+            let FileContent::Content(file) = &*manifest.content().file_content().await? else {
+                panic!("the manifest should have content");
+            };
+            let manifest: serde_json::Value =
+                serde_json::from_str(file.content().to_str()?.as_ref())?;
+            assert_eq!(manifest["version"], 1);
+            assert_eq!(
+                manifest["functions"]["/api/users"],
+                serde_json::json!({
+                    "runtime": "edge",
+                    "regions": "iad1",
+                    "maxDuration": 30,
+                })
+            );
+            assert_eq!(
+                manifest["functions"]["/"],
+                serde_json::json!({
+                    "runtime": "nodejs",
+                    "maxDuration": 10,
+                })
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,12 +1,17 @@
 //! Type definitions for the Next.js manifest formats.
 
 pub(crate) mod client_reference_manifest;
+mod functions_config_manifest;
+mod routing_config_manifest;
 
 use std::collections::HashMap;
 
-use serde::Serialize;
+pub use functions_config_manifest::functions_config_manifest;
+pub use routing_config_manifest::routing_config_manifest;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::trace::TraceRawVcs;
 
-use crate::next_config::Rewrites;
+use crate::{next_config::Rewrites, util::NextRuntime};
 
 #[derive(Serialize, Default, Debug)]
 pub struct PagesManifest {
@@ -41,7 +46,7 @@ impl Default for MiddlewaresManifest {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, TraceRawVcs)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub enum RouteHas {
     Header {
@@ -111,6 +116,93 @@ pub struct MiddlewaresManifestV2 {
     pub functions: HashMap<String, EdgeFunctionDefinition>,
 }
 
+/// Describes the function of each route for deployment platforms.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionsConfigManifest {
+    pub version: u32,
+    pub functions: HashMap<String, FunctionConfig>,
+}
+
+impl Default for FunctionsConfigManifest {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            functions: Default::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionConfig {
+    pub runtime: NextRuntime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<Regions>,
+    /// Maximum duration of the function in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration: Option<u32>,
+}
+
+/// Platform-neutral description of the redirects, rewrites and headers of the
+/// config, which deployment adapters translate to the routing config of their
+/// platform. The rules of each list are in the order they're applied.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingConfigManifest {
+    pub version: u32,
+    pub redirects: Vec<RoutingRule>,
+    pub headers: Vec<RoutingRule>,
+    pub rewrites: RoutingConfigRewrites,
+}
+
+impl Default for RoutingConfigManifest {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            redirects: Default::default(),
+            headers: Default::default(),
+            rewrites: Default::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingConfigRewrites {
+    pub before_files: Vec<RoutingRule>,
+    pub after_files: Vec<RoutingRule>,
+    pub fallback: Vec<RoutingRule>,
+}
+
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingRule {
+    pub source: String,
+    /// The compiled `source`, matched case-insensitively against the
+    /// pathname.
+    pub regex: String,
+    /// The names of the capture groups of `regex`, which are referenced in
+    /// the `destination`.
+    pub keys: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<RoutingHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has: Option<Vec<RouteHas>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing: Option<Vec<RouteHas>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RoutingHeader {
+    pub key: String,
+    pub value: String,
+}
+
 #[derive(Serialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ReactLoadableManifest {
@@ -188,6 +280,15 @@ pub struct ClientReferenceManifest {
     /// Mapping of server component path to required CSS client chunks.
     #[serde(rename = "entryCSSFiles")]
     pub entry_css_files: HashMap<String, Vec<String>>,
+    /// How the client chunks listed in the manifest are loaded.
+    pub module_loading: ModuleLoading,
+}
+
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleLoading {
+    /// The public URL prefix the client chunk paths are relative to.
+    pub prefix: String,
 }
 
 #[derive(Serialize, Default, Debug)]
@@ -0,0 +1,225 @@
+use anyhow::{Context, Result};
+use turbo_tasks::Vc;
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbopack_binding::turbopack::core::{
+    asset::AssetContent, output::OutputAsset, virtual_output::VirtualOutputAsset,
+};
+
+use super::{RouteHas, RoutingConfigManifest, RoutingConfigRewrites, RoutingHeader, RoutingRule};
+use crate::next_config::{self, RedirectStatus, RedirectsAndHeaders, Rewrite, Rewrites};
+
+/// Emits `routing-config.json`, a platform-neutral description of the
+/// redirects, rewrites and headers of the config for deployment adapters.
+///
+/// The rules are the ones resolved by `loadCustomRoutes`, so the `basePath`,
+/// locales and trailing slash redirects are already applied to them. Their
+/// regexes are compiled by the config loader the same way as for the routes
+/// manifest.
+#[turbo_tasks::function]
+pub async fn routing_config_manifest(
+    node_root: Vc<FileSystemPath>,
+    rewrites: Vc<Rewrites>,
+    redirects_and_headers: Vc<RedirectsAndHeaders>,
+) -> Result<Vc<Box<dyn OutputAsset>>> {
+    let Rewrites {
+        before_files,
+        after_files,
+        fallback,
+    } = &*rewrites.await?;
+    let RedirectsAndHeaders { redirects, headers } = &*redirects_and_headers.await?;
+
+    let manifest = RoutingConfigManifest {
+        redirects: redirects
+            .iter()
+            .map(|redirect| {
+                Ok(RoutingRule {
+                    destination: Some(redirect.destination.clone()),
+                    status_code: Some(status_code(&redirect.status)),
+                    ..routing_rule(
+                        &redirect.source,
+                        &redirect.regex,
+                        &redirect.regex_keys,
+                        &redirect.has,
+                        &redirect.missing,
+                    )?
+                })
+            })
+            .collect::<Result<_>>()?,
+        headers: headers
+            .iter()
+            .map(|header| {
+                Ok(RoutingRule {
+                    headers: header
+                        .headers
+                        .iter()
+                        .map(|header| RoutingHeader {
+                            key: header.key.clone(),
+                            value: header.value.clone(),
+                        })
+                        .collect(),
+                    ..routing_rule(
+                        &header.source,
+                        &header.regex,
+                        &header.regex_keys,
+                        &header.has,
+                        &header.missing,
+                    )?
+                })
+            })
+            .collect::<Result<_>>()?,
+        rewrites: RoutingConfigRewrites {
+            before_files: rewrite_rules(before_files)?,
+            after_files: rewrite_rules(after_files)?,
+            fallback: rewrite_rules(fallback)?,
+        },
+        ..Default::default()
+    };
+    Ok(Vc::upcast(VirtualOutputAsset::new(
+        node_root.join("routing-config.json".to_string()),
+        AssetContent::file(File::from(serde_json::to_string_pretty(&manifest)?).into()),
+    )))
+}
+
+fn rewrite_rules(rewrites: &[Rewrite]) -> Result<Vec<RoutingRule>> {
+    rewrites
+        .iter()
+        .map(|rewrite| {
+            Ok(RoutingRule {
+                destination: Some(rewrite.destination.clone()),
+                ..routing_rule(
+                    &rewrite.source,
+                    &rewrite.regex,
+                    &rewrite.regex_keys,
+                    &rewrite.has,
+                    &rewrite.missing,
+                )?
+            })
+        })
+        .collect()
+}
+
+fn routing_rule(
+    source: &str,
+    regex: &Option<String>,
+    keys: &[String],
+    has: &Option<Vec<next_config::RouteHas>>,
+    missing: &Option<Vec<next_config::RouteHas>>,
+) -> Result<RoutingRule> {
+    let regex = regex
+        .clone()
+        .with_context(|| format!("The regex of the route {source} wasn't compiled"))?;
+    Ok(RoutingRule {
+        source: source.to_string(),
+        regex,
+        keys: keys.to_vec(),
+        has: has.as_ref().map(|has| has.iter().map(route_has).collect()),
+        missing: missing
+            .as_ref()
+            .map(|missing| missing.iter().map(route_has).collect()),
+        ..Default::default()
+    })
+}
+
+fn route_has(has: &next_config::RouteHas) -> RouteHas {
+    match has.clone() {
+        next_config::RouteHas::Header { key, value } => RouteHas::Header { key, value },
+        next_config::RouteHas::Cookie { key, value } => RouteHas::Cookie { key, value },
+        next_config::RouteHas::Query { key, value } => RouteHas::Query { key, value },
+        next_config::RouteHas::Host { value } => RouteHas::Host { value },
+    }
+}
+
+fn status_code(status: &RedirectStatus) -> u16 {
+    match *status {
+        RedirectStatus::StatusCode(status_code) => status_code as u16,
+        RedirectStatus::Permanent(true) => 308,
+        RedirectStatus::Permanent(false) => 307,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks_fs::FileContent;
+    use turbopack_binding::turbopack::core::asset::Asset;
+
+    use super::routing_config_manifest;
+    use crate::{
+        next_config::{Redirect, RedirectStatus, RedirectsAndHeaders, Rewrite, Rewrites},
+        test_util::with_memory_fs,
+    };
+
+    #[tokio::test]
+    async fn test_routing_config_manifest() -> Result<()> {
+        with_memory_fs(|root| async move {
+            let node_root = root.join(".next".to_string());
+            let rewrite = |source: &str, regex: &str, destination: &str| Rewrite {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                base_path: None,
+                locale: None,
+                has: None,
+                missing: None,
+                regex: Some(regex.to_string()),
+                regex_keys: vec!["path".to_string()],
+            };
+            let manifest = routing_config_manifest(
+                node_root,
+                Rewrites {
+                    before_files: vec![rewrite(
+                        "/docs/:path*",
+                        "^/docs(?:/((?:[^/]+?)(?:/(?:[^/]+?))*))?(?:/)?$",
+                        "/documentation/:path*",
+                    )],
+                    after_files: vec![],
+                    fallback: vec![rewrite(
+                        "/:path+",
+                        "^(?:/((?:[^/]+?)(?:/(?:[^/]+?))*))(?:/)?$",
+                        "https://example.com/:path+",
+                    )],
+                }
+                .cell(),
+                RedirectsAndHeaders {
+                    redirects: vec![Redirect {
+                        source: "/old/:slug".to_string(),
+                        destination: "/new/:slug".to_string(),
+                        base_path: None,
+                        locale: None,
+                        has: None,
+                        missing: None,
+                        regex: Some("^(?!/_next)/old(?:/([^/]+?))(?:/)?$".to_string()),
+                        regex_keys: vec!["slug".to_string()],
+                        status: RedirectStatus::Permanent(true),
+                    }],
+                    headers: vec![],
+                }
+                .cell(),
+            );
+
+            let FileContent::Content(file) = &*manifest.content().file_content().await? else {
+                panic!("the manifest should have content");
+            };
+            let manifest: serde_json::Value =
+                serde_json::from_str(file.content().to_str()?.as_ref())?;
+            assert_eq!(
+                manifest["redirects"],
+                serde_json::json!([{
+                    "source": "/old/:slug",
+                    "regex": "^(?!/_next)/old(?:/([^/]+?))(?:/)?$",
+                    "keys": ["slug"],
+                    "destination": "/new/:slug",
+                    "statusCode": 308,
+                }])
+            );
+            assert_eq!(
+                manifest["rewrites"]["beforeFiles"][0]["regex"],
+                "^/docs(?:/((?:[^/]+?)(?:/(?:[^/]+?))*))?(?:/)?$"
+            );
+            assert_eq!(manifest["rewrites"]["afterFiles"], serde_json::json!([]));
+            assert_eq!(manifest["rewrites"]["fallback"][0]["source"], "/:path+");
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -43,7 +43,9 @@ use crate::{
     next_build::{get_external_next_compiled_package_mapping, get_postcss_package_mapping},
     next_client::{RuntimeEntries, RuntimeEntry},
     next_config::NextConfig,
-    next_import_map::{get_next_server_import_map, mdx_import_source_file},
+    next_import_map::{
+        get_next_server_fallback_import_map, get_next_server_import_map, mdx_import_source_file,
+    },
     next_server::resolve::ExternalPredicate,
     next_shared::{
         resolve::{ModuleFeatureReportResolvePlugin, UnsupportedModulesResolvePlugin},
@@ -147,6 +149,7 @@ pub async fn get_server_resolve_options_context(
         module: true,
         custom_conditions,
         import_map: Some(next_server_import_map),
+        fallback_import_map: Some(get_next_server_fallback_import_map(project_path)),
         plugins,
         ..Default::default()
     };
@@ -548,19 +551,31 @@ pub fn get_build_module_options_context() -> Vc<ModuleOptionsContext> {
 }
 
 #[turbo_tasks::function]
-pub fn get_server_runtime_entries(
+pub async fn get_server_runtime_entries(
     project_root: Vc<FileSystemPath>,
     env: Vc<Box<dyn ProcessEnv>>,
     ty: Value<ServerContextType>,
     mode: NextMode,
     next_config: Vc<NextConfig>,
-) -> Vc<RuntimeEntries> {
+) -> Result<Vc<RuntimeEntries>> {
     let mut runtime_entries = vec![RuntimeEntry::Source(Vc::upcast(ProcessEnvAsset::new(
         project_root,
         env_for_js(env, false, next_config),
     )))
     .cell()];
 
+    // Injected runtime modules are evaluated in the configured order, right
+    // after the process env is available and before any bootstrap code.
+    for module in next_config.runtime_modules().await?.iter() {
+        runtime_entries.push(
+            RuntimeEntry::Request(
+                Request::parse_string(module.clone()),
+                project_root.join("_".to_string()),
+            )
+            .cell(),
+        );
+    }
+
     match mode {
         NextMode::Development => {}
         NextMode::DevServer => {}
@@ -579,7 +594,7 @@ pub fn get_server_runtime_entries(
         }
     }
 
-    Vc::cell(runtime_entries)
+    Ok(Vc::cell(runtime_entries))
 }
 
 #[turbo_tasks::function]
@@ -1,2 +1,3 @@
+pub(crate) mod module_graph;
 pub(crate) mod resolve;
 pub(crate) mod transforms;
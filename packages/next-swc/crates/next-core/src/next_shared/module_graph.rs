@@ -0,0 +1,67 @@
+use anyhow::Result;
+use turbo_tasks::{
+    graph::{AdjacencyMap, GraphTraversal},
+    ReadRef, TryJoinIterExt, Vc,
+};
+use turbopack_binding::turbopack::{
+    core::module::Module,
+    ecmascript::{parse::ParseResult, EcmascriptModuleAsset},
+};
+
+/// Returns the modules directly referenced by `module`. They are resolved, so
+/// they can be compared and used as keys.
+pub(crate) async fn referenced_modules(
+    module: Vc<Box<dyn Module>>,
+) -> Result<Vec<Vc<Box<dyn Module>>>> {
+    let modules = module
+        .references()
+        .await?
+        .iter()
+        .map(|reference| async move {
+            reference
+                .resolve_reference()
+                .primary_modules()
+                .await?
+                .iter()
+                .map(|module| module.resolve())
+                .try_join()
+                .await
+        })
+        .try_join()
+        .await?;
+    Ok(modules.into_iter().flatten().collect())
+}
+
+async fn get_referenced_modules(
+    module: Vc<Box<dyn Module>>,
+) -> Result<impl Iterator<Item = Vc<Box<dyn Module>>> + Send> {
+    Ok(referenced_modules(module).await?.into_iter())
+}
+
+/// Returns all modules reachable from `entry`, including itself, in reverse
+/// topological order. Resolving the modules reports their resolve issues.
+pub(crate) async fn reachable_modules(
+    entry: Vc<Box<dyn Module>>,
+) -> Result<Vec<Vc<Box<dyn Module>>>> {
+    Ok(AdjacencyMap::new()
+        .skip_duplicates()
+        .visit([entry.resolve().await?], get_referenced_modules)
+        .await
+        .completed()?
+        .into_inner()
+        .into_reverse_topological()
+        .collect())
+}
+
+/// Parses `module` when it's an ecmascript module. Callers borrow the AST from
+/// the returned parse result instead of cloning it.
+pub(crate) async fn parse_ecmascript_module(
+    module: Vc<Box<dyn Module>>,
+) -> Result<Option<ReadRef<ParseResult>>> {
+    let Some(ecmascript_asset) =
+        Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(module).await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(ecmascript_asset.parse().await?))
+}
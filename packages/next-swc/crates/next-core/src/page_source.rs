@@ -73,7 +73,7 @@ use crate::{
     },
     page_loader::create_page_loader,
     pages_structure::{PagesDirectoryStructure, PagesStructure, PagesStructureItem},
-    util::{parse_config_from_source, pathname_for_path, render_data, NextRuntime, PathType},
+    util::{get_page_runtime, pathname_for_path, render_data, NextRuntime, PathType},
 };
 
 /// Create a content source serving the `pages` or `src/pages` directory as
@@ -258,13 +258,14 @@ pub async fn create_page_source(
     );
 
     let render_data = render_data(next_config, server_addr);
-    let page_extensions = next_config.page_extensions();
+    let page_extensions = next_config.resolved_page_extensions();
 
     let sources = vec![
         // Match _next/404 first to ensure rewrites work properly.
         create_not_found_page_source(
             project_root,
             env,
+            next_config,
             server_context,
             client_context,
             Vc::upcast(client_chunking_context),
@@ -284,6 +285,7 @@ pub async fn create_page_source(
             pages_structure,
             project_root,
             env,
+            next_config,
             server_context,
             server_data_context,
             client_context,
@@ -302,6 +304,7 @@ pub async fn create_page_source(
         create_not_found_page_source(
             project_root,
             env,
+            next_config,
             server_context,
             client_context,
             Vc::upcast(client_chunking_context),
@@ -331,6 +334,7 @@ pub async fn create_page_source(
 async fn create_page_source_for_file(
     project_path: Vc<FileSystemPath>,
     env: Vc<Box<dyn ProcessEnv>>,
+    next_config: Vc<NextConfig>,
     server_context: Vc<Box<dyn AssetContext>>,
     server_data_context: Vc<Box<dyn AssetContext>>,
     client_context: Vc<Box<dyn AssetContext>>,
@@ -404,6 +408,7 @@ async fn create_page_source_for_file(
                     node_path,
                     node_root,
                     project_path,
+                    next_config,
                 }
                 .cell(),
             ),
@@ -432,6 +437,7 @@ async fn create_page_source_for_file(
                 node_path,
                 node_root,
                 project_path,
+                next_config,
             }
             .cell(),
         );
@@ -446,6 +452,7 @@ async fn create_page_source_for_file(
                 node_path: data_node_path,
                 node_root,
                 project_path,
+                next_config,
             }
             .cell(),
         );
@@ -508,6 +515,7 @@ async fn get_not_found_page(
 async fn create_not_found_page_source(
     project_path: Vc<FileSystemPath>,
     env: Vc<Box<dyn ProcessEnv>>,
+    next_config: Vc<NextConfig>,
     server_context: Vc<Box<dyn AssetContext>>,
     client_context: Vc<Box<dyn AssetContext>>,
     client_chunking_context: Vc<Box<dyn ChunkingContext>>,
@@ -558,6 +566,7 @@ async fn create_not_found_page_source(
             node_path,
             node_root: node_path,
             project_path,
+            next_config,
         }
         .cell(),
     );
@@ -597,6 +606,7 @@ async fn create_page_source_for_root_directory(
     pages_structure: Vc<PagesStructure>,
     project_root: Vc<FileSystemPath>,
     env: Vc<Box<dyn ProcessEnv>>,
+    next_config: Vc<NextConfig>,
     server_context: Vc<Box<dyn AssetContext>>,
     server_data_context: Vc<Box<dyn AssetContext>>,
     client_context: Vc<Box<dyn AssetContext>>,
@@ -621,6 +631,7 @@ async fn create_page_source_for_root_directory(
             *pages,
             project_root,
             env,
+            next_config,
             server_context,
             server_data_context,
             client_context,
@@ -639,6 +650,7 @@ async fn create_page_source_for_root_directory(
             *api,
             project_root,
             env,
+            next_config,
             server_context,
             server_data_context,
             client_context,
@@ -663,6 +675,7 @@ async fn create_page_source_for_directory(
     pages_structure: Vc<PagesDirectoryStructure>,
     project_root: Vc<FileSystemPath>,
     env: Vc<Box<dyn ProcessEnv>>,
+    next_config: Vc<NextConfig>,
     server_context: Vc<Box<dyn AssetContext>>,
     server_data_context: Vc<Box<dyn AssetContext>>,
     client_context: Vc<Box<dyn AssetContext>>,
@@ -690,6 +703,7 @@ async fn create_page_source_for_directory(
         let source = create_page_source_for_file(
             project_root,
             env,
+            next_config,
             server_context,
             server_data_context,
             client_context,
@@ -721,6 +735,7 @@ async fn create_page_source_for_directory(
             *child,
             project_root,
             env,
+            next_config,
             server_context,
             server_data_context,
             client_context,
@@ -779,6 +794,7 @@ pub struct SsrEntry {
     node_path: Vc<FileSystemPath>,
     node_root: Vc<FileSystemPath>,
     project_path: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
 }
 
 #[derive(
@@ -802,8 +818,9 @@ impl SsrEntry {
             Value::new(ReferenceType::Entry(EntryReferenceSubType::Page)),
         );
         let ty = if this.ty == SsrType::AutoApi {
-            let page_config = parse_config_from_source(entry_asset_page);
-            if page_config.await?.runtime == NextRuntime::Edge {
+            if get_page_runtime(entry_asset_page, this.entry_asset, this.next_config).await?
+                == NextRuntime::Edge
+            {
                 SsrType::EdgeApi
             } else {
                 SsrType::Api
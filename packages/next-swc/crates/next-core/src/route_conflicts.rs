@@ -0,0 +1,168 @@
+use anyhow::Result;
+use async_recursion::async_recursion;
+use indexmap::IndexMap;
+use turbo_tasks::{Completion, Vc};
+use turbopack_binding::{
+    turbo::tasks_fs::FileSystemPath,
+    turbopack::core::issue::{Issue, IssueExt, IssueSeverity},
+};
+
+use crate::{
+    app_structure::{Entrypoint, Entrypoints},
+    pages_structure::{PagesDirectoryStructure, PagesStructure},
+};
+
+/// Emits an error for each path which is served by both a page of the pages
+/// router and a page or route handler of the app router (e.g. `pages/about.tsx`
+/// and `app/about/page.tsx`).
+#[turbo_tasks::function]
+pub async fn validate_route_conflicts(
+    app_entrypoints: Vc<Entrypoints>,
+    pages_structure: Vc<PagesStructure>,
+) -> Result<Vc<Completion>> {
+    let PagesStructure { pages, api, .. } = &*pages_structure.await?;
+    let Some(pages) = pages else {
+        return Ok(Completion::new());
+    };
+    let next_router_root = pages.next_router_path().await?;
+
+    let mut pages_files = IndexMap::new();
+    collect_pages_files(*pages, &next_router_root, &mut pages_files).await?;
+    if let Some(api) = api {
+        collect_pages_files(*api, &next_router_root, &mut pages_files).await?;
+    }
+
+    for (pathname, entrypoint) in app_entrypoints.await?.iter() {
+        let app_original_name = match entrypoint {
+            Entrypoint::AppPage { original_name, .. } => {
+                // The not-found pages are generated for the app router.
+                if original_name == "/not-found" || original_name.ends_with("/_not-found") {
+                    continue;
+                }
+                original_name
+            }
+            Entrypoint::AppRoute { original_name, .. } => original_name,
+        };
+        if let Some(&pages_file) = pages_files.get(pathname) {
+            RouteConflictIssue {
+                pages_file,
+                pathname: pathname.clone(),
+                app_original_name: app_original_name.clone(),
+            }
+            .cell()
+            .emit();
+        }
+    }
+
+    Ok(Completion::new())
+}
+
+/// Collects the files of the pages in `directory` by their pathname.
+#[async_recursion]
+async fn collect_pages_files(
+    directory: Vc<PagesDirectoryStructure>,
+    next_router_root: &FileSystemPath,
+    pages_files: &mut IndexMap<String, Vc<FileSystemPath>>,
+) -> Result<()> {
+    let directory = directory.await?;
+    for item in directory.items.iter() {
+        let item = item.await?;
+        if let Some(path) = next_router_root.get_path_to(&*item.next_router_path.await?) {
+            pages_files.insert(format!("/{path}"), item.project_path);
+        }
+    }
+    for &child in directory.children.iter() {
+        collect_pages_files(child, next_router_root, pages_files).await?;
+    }
+    Ok(())
+}
+
+#[turbo_tasks::value(shared)]
+struct RouteConflictIssue {
+    pages_file: Vc<FileSystemPath>,
+    pathname: String,
+    app_original_name: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for RouteConflictIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> Vc<String> {
+        Vc::cell("next app".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.pages_file
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<String> {
+        Vc::cell("Conflicting app and page file was found".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<String> {
+        Vc::cell(format!(
+            "{} is served by both this page and the app router entry at {}. Remove one of them.",
+            self.pathname, self.app_original_name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+
+    use super::validate_route_conflicts;
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions},
+        pages_structure::find_pages_structure,
+        test_util::{issues_of, with_app_fixture},
+    };
+
+    #[tokio::test]
+    async fn test_route_conflicts() -> Result<()> {
+        with_app_fixture(
+            [
+                "app/layout.tsx",
+                "app/page.tsx",
+                "app/about/page.tsx",
+                "app/api/users/route.tsx",
+                "pages/about.tsx",
+                "pages/blog.tsx",
+                "pages/api/users.tsx",
+            ],
+            |project_path| async move {
+                let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+                let issues = issues_of(validate_route_conflicts(
+                    get_entrypoints(
+                        project_path.join("app".to_string()),
+                        page_extensions,
+                        AppStructureOptions::default().cell(),
+                    ),
+                    find_pages_structure(project_path, project_path, page_extensions),
+                ))
+                .await?;
+
+                let mut conflicts = issues
+                    .iter()
+                    .map(|issue| issue.file_path.clone())
+                    .collect::<Vec<_>>();
+                conflicts.sort();
+                assert_eq!(conflicts.len(), 2);
+                assert!(conflicts[0].ends_with("pages/about.tsx"));
+                assert!(conflicts[1].ends_with("pages/api/users.tsx"));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+}
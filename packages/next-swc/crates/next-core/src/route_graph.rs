@@ -0,0 +1,181 @@
+use anyhow::Result;
+use async_recursion::async_recursion;
+use indexmap::{IndexMap, IndexSet};
+use turbo_tasks::{ValueToString, Vc};
+use turbo_tasks_fs::FileSystemPath;
+
+use crate::app_structure::{component_files, Entrypoint, Entrypoints, LoaderTree};
+
+/// The nodes and edges of a route graph, by the id of their node.
+#[derive(Default)]
+struct RouteGraph {
+    nodes: IndexMap<String, String>,
+    edges: IndexSet<(String, String, Option<String>)>,
+}
+
+/// Renders the entrypoints of the app directory as a Graphviz DOT graph.
+///
+/// Segments of the loader trees are nodes labeled with their component files,
+/// connected by their parallel routes. Each entrypoint is a box labeled with
+/// its pathname and kind, pointing at the segment rendering it. Files are
+/// shown relative to `project_path`.
+#[turbo_tasks::function]
+pub async fn route_tree_dot(
+    entrypoints: Vc<Entrypoints>,
+    project_path: Vc<FileSystemPath>,
+) -> Result<Vc<String>> {
+    let project_path = &*project_path.await?;
+    let mut graph = RouteGraph::default();
+    for (pathname, entrypoint) in entrypoints.await?.iter() {
+        let entry_id = format!("entry:{pathname}");
+        match *entrypoint {
+            Entrypoint::AppPage { loader_tree, .. } => {
+                graph
+                    .nodes
+                    .insert(entry_id.clone(), format!("{pathname}\\n(page)"));
+                let leaf_id =
+                    add_loader_tree(&mut graph, loader_tree, String::new(), project_path).await?;
+                graph.edges.insert((entry_id, leaf_id, None));
+            }
+            Entrypoint::AppRoute { path, .. } => {
+                graph.nodes.insert(
+                    entry_id,
+                    format!(
+                        "{pathname}\\n(route)\\n{}",
+                        display_path(path, project_path).await?
+                    ),
+                );
+            }
+        }
+    }
+    Ok(Vc::cell(to_dot(&graph)))
+}
+
+/// Adds the nodes of a loader tree to the graph and returns the id of the leaf
+/// segment of its `children` route.
+#[async_recursion]
+async fn add_loader_tree(
+    graph: &mut RouteGraph,
+    tree: Vc<LoaderTree>,
+    id: String,
+    project_path: &FileSystemPath,
+) -> Result<String> {
+    let tree = tree.await?;
+    let mut label = if tree.segment.is_empty() {
+        "/".to_string()
+    } else {
+        tree.segment.clone()
+    };
+    for (kind, path) in component_files(&*tree.components.await?) {
+        label.push_str(&format!(
+            "\\n{kind}: {}",
+            display_path(path, project_path).await?
+        ));
+    }
+    graph.nodes.insert(id.clone(), label);
+
+    let mut leaf_id = id.clone();
+    for (key, &parallel_route) in tree.parallel_routes.iter() {
+        let child_id = format!("{id}/{key}:{}", parallel_route.await?.segment);
+        let child_leaf_id =
+            add_loader_tree(graph, parallel_route, child_id.clone(), project_path).await?;
+        if key == "children" {
+            graph.edges.insert((id.clone(), child_id, None));
+            leaf_id = child_leaf_id;
+        } else {
+            graph
+                .edges
+                .insert((id.clone(), child_id, Some(format!("@{key}"))));
+        }
+    }
+    Ok(leaf_id)
+}
+
+/// Returns `path` relative to the project, or its full path when it's outside
+/// of the project.
+async fn display_path(path: Vc<FileSystemPath>, project_path: &FileSystemPath) -> Result<String> {
+    let path_value = path.await?;
+    Ok(match project_path.get_path_to(&path_value) {
+        Some(relative) => relative.to_string(),
+        None => path.to_string().await?.clone_value(),
+    })
+}
+
+fn to_dot(graph: &RouteGraph) -> String {
+    let mut dot = "digraph routes {\n  rankdir=LR;\n".to_string();
+    for (id, label) in &graph.nodes {
+        let shape = if id.starts_with("entry:") {
+            "box"
+        } else {
+            "ellipse"
+        };
+        dot.push_str(&format!(
+            "  {} [label={}, shape={shape}];\n",
+            quote(id),
+            quote(label)
+        ));
+    }
+    for (from, to, label) in &graph.edges {
+        match label {
+            Some(label) => dot.push_str(&format!(
+                "  {} -> {} [label={}];\n",
+                quote(from),
+                quote(to),
+                quote(label)
+            )),
+            None => dot.push_str(&format!("  {} -> {};\n", quote(from), quote(to))),
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Quotes a DOT id. Labels contain `\n` line breaks, so backslashes are kept.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+
+    use super::route_tree_dot;
+    use crate::{
+        app_structure::{get_entrypoints, AppStructureOptions},
+        test_util::with_app_fixture,
+    };
+
+    #[tokio::test]
+    async fn test_route_tree_dot() -> Result<()> {
+        with_app_fixture(
+            [
+                "app/layout.tsx",
+                "app/page.tsx",
+                "app/about/page.tsx",
+                "app/api/health/route.tsx",
+            ],
+            |project_path| async move {
+                let entrypoints = get_entrypoints(
+                    project_path.join("app".to_string()),
+                    Vc::cell(vec!["tsx".to_string()]),
+                    AppStructureOptions::default().cell(),
+                );
+                let dot = route_tree_dot(entrypoints, project_path).await?;
+
+                assert!(dot.starts_with("digraph routes {\n"));
+                assert!(dot.contains(
+                    r#""entry:/about" [label="/about\n(page)", shape=box];"#
+                ));
+                assert!(dot.contains(
+                    r#""entry:/api/health" [label="/api/health\n(route)\napp/api/health/route.tsx", shape=box];"#
+                ));
+                assert!(dot.contains(r#"[label="/\nlayout: app/layout.tsx", shape=ellipse];"#));
+                assert!(dot.contains(r#"[label="__PAGE__\npage: app/about/page.tsx", shape=ellipse];"#));
+
+                Ok(())
+            },
+        )
+        .await
+    }
+}
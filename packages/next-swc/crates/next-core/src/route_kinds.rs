@@ -0,0 +1,116 @@
+use anyhow::Result;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, Vc};
+
+use crate::app_structure::{Entrypoint, Entrypoints, GlobalMetadata};
+
+/// What a route of the app directory responds with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub enum RouteKind {
+    /// A page, rendered to an HTML document.
+    Page,
+    /// A route handler (`route.js`), which responds with arbitrary content.
+    RouteHandler,
+    /// A metadata file like `robots.txt` or `sitemap.xml`.
+    MetadataFile,
+}
+
+impl RouteKind {
+    /// Whether the route responds with an HTML document, i.e. whether a
+    /// crawler can expect a `<title>` and `<meta name="description">` there.
+    pub fn produces_html(self) -> bool {
+        matches!(self, RouteKind::Page)
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct RouteKinds(IndexMap<String, RouteKind>);
+
+/// Classifies the routes of the app directory by their pathname, telling the
+/// pages which produce HTML apart from route handlers and the global metadata
+/// files.
+#[turbo_tasks::function]
+pub async fn get_route_kinds(
+    entrypoints: Vc<Entrypoints>,
+    global_metadata: Vc<GlobalMetadata>,
+) -> Result<Vc<RouteKinds>> {
+    let mut route_kinds = IndexMap::new();
+    for (pathname, entrypoint) in entrypoints.await?.iter() {
+        let kind = match entrypoint {
+            Entrypoint::AppPage { .. } => RouteKind::Page,
+            Entrypoint::AppRoute { .. } => RouteKind::RouteHandler,
+        };
+        route_kinds.insert(pathname.clone(), kind);
+    }
+
+    let GlobalMetadata {
+        favicon,
+        robots,
+        sitemap,
+        ..
+    } = &*global_metadata.await?;
+    for (pathname, item) in [
+        ("/favicon.ico", favicon),
+        ("/robots.txt", robots),
+        ("/sitemap.xml", sitemap),
+    ] {
+        if item.is_some() {
+            route_kinds
+                .entry(pathname.to_string())
+                .or_insert(RouteKind::MetadataFile);
+        }
+    }
+
+    Ok(Vc::cell(route_kinds))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks::Vc;
+
+    use super::{get_route_kinds, RouteKind};
+    use crate::{
+        app_structure::{get_entrypoints, get_global_metadata, AppStructureOptions},
+        test_util::with_app_fixture,
+    };
+
+    #[tokio::test]
+    async fn test_get_route_kinds() -> Result<()> {
+        with_app_fixture(
+            [
+                "app/layout.tsx",
+                "app/page.tsx",
+                "app/api/health/route.tsx",
+                "app/sitemap.tsx",
+            ],
+            |project_path| async move {
+                let app_dir = project_path.join("app".to_string());
+                let page_extensions = Vc::cell(vec!["tsx".to_string()]);
+                let options = AppStructureOptions::default().cell();
+                let route_kinds = get_route_kinds(
+                    get_entrypoints(app_dir, page_extensions, options),
+                    get_global_metadata(app_dir, page_extensions, options),
+                )
+                .await?;
+
+                assert_eq!(route_kinds.get("/"), Some(&RouteKind::Page));
+                assert_eq!(
+                    route_kinds.get("/api/health"),
+                    Some(&RouteKind::RouteHandler)
+                );
+                assert_eq!(
+                    route_kinds.get("/sitemap.xml"),
+                    Some(&RouteKind::MetadataFile)
+                );
+                assert!(route_kinds["/"].produces_html());
+                assert!(!route_kinds["/sitemap.xml"].produces_html());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+}
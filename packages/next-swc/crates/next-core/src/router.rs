@@ -38,6 +38,7 @@ use turbopack_binding::{
 
 use crate::{
     embed_js::next_asset,
+    middleware::get_middleware_config,
     mode::NextMode,
     next_config::NextConfig,
     next_edge::{
@@ -46,7 +47,6 @@ use crate::{
     },
     next_import_map::get_next_build_import_map,
     next_server::context::{get_server_module_options_context, ServerContextType},
-    util::parse_config_from_source,
 };
 
 #[turbo_tasks::function]
@@ -59,20 +59,6 @@ fn next_configs() -> Vc<Vec<String>> {
     )
 }
 
-#[turbo_tasks::function]
-async fn middleware_files(page_extensions: Vc<Vec<String>>) -> Result<Vc<Vec<String>>> {
-    let extensions = page_extensions.await?;
-    let files = ["middleware.", "src/middleware."]
-        .into_iter()
-        .flat_map(|f| {
-            extensions
-                .iter()
-                .map(move |ext| String::from(f) + ext.as_str())
-        })
-        .collect();
-    Ok(Vc::cell(files))
-}
-
 #[turbo_tasks::value(shared)]
 #[derive(Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
@@ -156,30 +142,24 @@ async fn config_assets(
     project_path: Vc<FileSystemPath>,
     page_extensions: Vc<Vec<String>>,
 ) -> Result<Vc<InnerAssets>> {
-    let find_config_result = find_context_file(project_path, middleware_files(page_extensions));
+    let middleware_config = get_middleware_config(project_path, page_extensions, context);
 
     // The router.ts file expects a manifest of chunks for the middleware. If there
     // is no middleware file, then we need to generate a default empty manifest
     // and we cannot process it with the next-edge transition because it
     // requires a real file for some reason.
-    let (manifest, config) = match *find_config_result.await? {
-        FindContextFileResult::Found(config_path, _) => {
-            let config = context.process(
-                Vc::upcast(FileSource::new(config_path)),
-                Value::new(ReferenceType::EcmaScriptModules(
-                    EcmaScriptModulesReferenceSubType::Undefined,
-                )),
-            );
-            let config = parse_config_from_source(config);
+    let (manifest, config) = match *middleware_config.await? {
+        Some(middleware_config) => {
+            let middleware_config = middleware_config.await?;
             let manifest = context.with_transition("next-edge".to_string()).process(
-                Vc::upcast(FileSource::new(config_path)),
+                Vc::upcast(FileSource::new(middleware_config.path)),
                 Value::new(ReferenceType::EcmaScriptModules(
                     EcmaScriptModulesReferenceSubType::Undefined,
                 )),
             );
-            (manifest, config)
+            (manifest, middleware_config.config)
         }
-        FindContextFileResult::NotFound(_) => {
+        None => {
             let manifest = context.process(
                 Vc::upcast(VirtualSource::new(
                     project_path.join("middleware.js".to_string()),
@@ -346,7 +326,11 @@ async fn route_internal(
         )),
     );
 
-    let configs = config_assets(context, project_path, next_config.page_extensions());
+    let configs = config_assets(
+        context,
+        project_path,
+        next_config.resolved_page_extensions(),
+    );
     let router_asset = route_executor(context, configs);
 
     // This invalidates the router when the next config changes
@@ -2,6 +2,7 @@ use anyhow::{anyhow, bail, Context, Result};
 use futures::{Stream, TryStreamExt};
 use indexmap::IndexSet;
 use turbo_tasks::{Completion, Completions, Value, Vc};
+use turbo_tasks_fs::FileSystemPath;
 use turbopack_binding::turbopack::{
     core::{
         environment::ServerAddr,
@@ -31,6 +32,7 @@ pub struct NextRouterContentSource {
     server_addr: Vc<ServerAddr>,
     app_dir: Vc<OptionAppDir>,
     pages_structure: Vc<PagesStructure>,
+    project_path: Vc<FileSystemPath>,
 }
 
 #[turbo_tasks::value_impl]
@@ -43,6 +45,7 @@ impl NextRouterContentSource {
         server_addr: Vc<ServerAddr>,
         app_dir: Vc<OptionAppDir>,
         pages_structure: Vc<PagesStructure>,
+        project_path: Vc<FileSystemPath>,
     ) -> Vc<NextRouterContentSource> {
         NextRouterContentSource {
             inner,
@@ -51,6 +54,7 @@ impl NextRouterContentSource {
             server_addr,
             app_dir,
             pages_structure,
+            project_path,
         }
         .cell()
     }
@@ -61,9 +65,10 @@ fn routes_changed(
     app_dir: Vc<OptionAppDir>,
     pages_structure: Vc<PagesStructure>,
     next_config: Vc<NextConfig>,
+    project_path: Vc<FileSystemPath>,
 ) -> Vc<Completion> {
     Completions::all(vec![
-        app_dir.routes_changed(next_config),
+        app_dir.routes_changed(next_config, project_path),
         pages_structure.routes_changed(),
     ])
 }
@@ -146,7 +151,12 @@ impl GetContentSourceContent for NextRouterContentSource {
             request,
             this.next_config,
             this.server_addr,
-            routes_changed(this.app_dir, this.pages_structure, this.next_config),
+            routes_changed(
+                this.app_dir,
+                this.pages_structure,
+                this.next_config,
+                this.project_path,
+            ),
         );
 
         let res = res
@@ -0,0 +1,175 @@
+//! Helpers shared by the unit tests of this crate.
+
+use std::{fs, future::Future, path::Path};
+
+use anyhow::Result;
+use tempfile::TempDir;
+use turbo_tasks::{ReadRef, Vc};
+use turbopack_binding::{
+    turbo::{
+        tasks::{run_once, TurboTasks},
+        tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath},
+        tasks_memory::MemoryBackend,
+    },
+    turbopack::{
+        core::{
+            environment::ServerAddr,
+            issue::{IssueDescriptionExt, PlainIssue},
+        },
+        ecmascript_plugin::transform::directives::client::ClientDirectiveTransformer,
+        turbopack::{
+            module_options::{CustomEcmascriptTransformPlugins, ModuleOptionsContext},
+            resolve_options_context::ResolveOptionsContext,
+            transition::ContextTransition,
+            ModuleAssetContext,
+        },
+    },
+};
+
+use crate::{
+    memory_fs::MemoryFileSystem, next_client_reference::NextEcmascriptClientReferenceTransition,
+    next_edge::context::get_edge_compile_time_info,
+};
+
+/// A temporary directory holding the files of a test fixture. It's removed
+/// when dropped.
+pub(crate) struct Fixture(TempDir);
+
+impl Fixture {
+    /// Writes `files`, given as paths relative to the fixture root and their
+    /// contents. Parent directories are created as needed.
+    pub(crate) fn new<P, C>(files: impl IntoIterator<Item = (P, C)>) -> Result<Self>
+    where
+        P: AsRef<str>,
+        C: AsRef<[u8]>,
+    {
+        let dir = tempfile::tempdir()?;
+        for (path, content) in files {
+            let path = dir.path().join(path.as_ref());
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(Self(dir))
+    }
+
+    /// An empty fixture, for tests which only write to the file system.
+    pub(crate) fn empty() -> Result<Self> {
+        Ok(Self(tempfile::tempdir()?))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    /// Runs `f` on a fresh turbo tasks instance, passing it the root of a disk
+    /// file system on the fixture.
+    pub(crate) async fn run<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Vc<FileSystemPath>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+    {
+        crate::register();
+
+        let root = self.path().canonicalize()?.to_string_lossy().to_string();
+        let tt = TurboTasks::new(MemoryBackend::default());
+        run_once(tt, async move {
+            let fs =
+                Vc::upcast::<Box<dyn FileSystem>>(DiskFileSystem::new("project".to_string(), root));
+            f(fs.root()).await
+        })
+        .await
+    }
+}
+
+/// Runs `f` on a disk file system holding the empty `files`, e.g. an app
+/// directory with `["layout.tsx", "blog/page.tsx"]`.
+pub(crate) async fn with_app_fixture<P, T, F, Fut>(
+    files: impl IntoIterator<Item = P>,
+    f: F,
+) -> Result<T>
+where
+    P: AsRef<str>,
+    T: Send + 'static,
+    F: FnOnce(Vc<FileSystemPath>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    Fixture::new(files.into_iter().map(|path| (path, "")))?
+        .run(f)
+        .await
+}
+
+/// Runs `f` on a fresh turbo tasks instance, passing it the root of an empty
+/// in-memory file system.
+pub(crate) async fn with_memory_fs<T, F, Fut>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(Vc<FileSystemPath>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    crate::register();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    run_once(tt, async move {
+        let fs = Vc::upcast::<Box<dyn FileSystem>>(MemoryFileSystem::new("test".to_string()));
+        f(fs.root()).await
+    })
+    .await
+}
+
+/// The issues emitted while computing `source`, once it's strongly
+/// consistent.
+pub(crate) async fn issues_of<T>(source: Vc<T>) -> Result<Vec<ReadRef<PlainIssue>>>
+where
+    T: ?Sized + Send,
+{
+    source
+        .peek_issues_with_path()
+        .await?
+        .strongly_consistent()
+        .await?
+        .get_plain_issues()
+        .await
+}
+
+/// A module context for the files of `project_path` which turns `"use client"`
+/// modules into client references, like the RSC context of the app does.
+pub(crate) fn client_reference_context(project_path: Vc<FileSystemPath>) -> Vc<ModuleAssetContext> {
+    const CLIENT_REFERENCE_TRANSITION: &str = "next-ecmascript-client-reference";
+
+    let compile_time_info = get_edge_compile_time_info(project_path, ServerAddr::empty());
+    let client_transition = ContextTransition::new(
+        compile_time_info,
+        ModuleOptionsContext::default().cell(),
+        ResolveOptionsContext::default().cell(),
+    );
+    ModuleAssetContext::new(
+        Vc::cell(
+            [(
+                CLIENT_REFERENCE_TRANSITION.to_string(),
+                Vc::upcast(NextEcmascriptClientReferenceTransition::new(
+                    client_transition,
+                    client_transition,
+                )),
+            )]
+            .into_iter()
+            .collect(),
+        ),
+        compile_time_info,
+        ModuleOptionsContext {
+            custom_ecma_transform_plugins: Some(CustomEcmascriptTransformPlugins::cell(
+                CustomEcmascriptTransformPlugins {
+                    source_transforms: vec![Vc::cell(Box::new(ClientDirectiveTransformer::new(
+                        Vc::cell(CLIENT_REFERENCE_TRANSITION.to_string()),
+                    )) as _)],
+                    output_transforms: vec![],
+                },
+            )),
+            ..Default::default()
+        }
+        .cell(),
+        ResolveOptionsContext::default().cell(),
+    )
+}
@@ -21,7 +21,7 @@ use turbopack_binding::{
 
 use crate::mode::NextMode;
 
-async fn get_typescript_options(
+pub(crate) async fn get_typescript_options(
     project_path: Vc<FileSystemPath>,
 ) -> Option<Vec<(Vc<FileJsonContent>, Vc<Box<dyn Source>>)>> {
     let tsconfig = find_context_file(project_path, tsconfig());
@@ -39,6 +39,22 @@ async fn get_typescript_options(
     }
 }
 
+/// Returns the directory configured as `compilerOptions.baseUrl` in the
+/// project's tsconfig, if any.
+pub(crate) async fn get_tsconfig_base_url(
+    project_path: Vc<FileSystemPath>,
+) -> Result<Option<Vc<FileSystemPath>>> {
+    let Some(tsconfig) = get_typescript_options(project_path).await else {
+        return Ok(None);
+    };
+    read_from_tsconfigs(&tsconfig, |json, source| {
+        json["compilerOptions"]["baseUrl"]
+            .as_str()
+            .map(|base_url| source.ident().path().parent().join(base_url.to_string()))
+    })
+    .await
+}
+
 /// Build the transform options for specifically for the typescript's runtime
 /// outputs
 #[turbo_tasks::function]
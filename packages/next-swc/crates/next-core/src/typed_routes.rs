@@ -0,0 +1,240 @@
+use anyhow::Result;
+use async_recursion::async_recursion;
+use indoc::indoc;
+use turbo_tasks::Vc;
+use turbo_tasks_fs::{rope::Rope, FileSystemPath};
+
+use crate::{
+    app_structure::{parse_dynamic_segment, DynamicParamKind, Entrypoint, Entrypoints},
+    pages_structure::{PagesDirectoryStructure, PagesStructure},
+};
+
+const TYPED_ROUTES_HEADER: &str = indoc! {r#"
+    // Type definitions for Next.js routes
+
+    /**
+     * Internal types used by the Next.js router and Link component.
+     * These types are not meant to be used directly.
+     * @internal
+     */
+    declare namespace __next_route_internal_types__ {
+      type SearchOrHash = `?${string}` | `#${string}`
+      type WithProtocol = `${string}:${string}`
+
+      type Suffix = '' | SearchOrHash
+
+      type SafeSlug<S extends string> = S extends `${string}/${string}`
+        ? never
+        : S extends `${string}${SearchOrHash}`
+        ? never
+        : S extends ''
+        ? never
+        : S
+
+      type CatchAllSlug<S extends string> = S extends `${string}${SearchOrHash}`
+        ? never
+        : S extends ''
+        ? never
+        : S
+
+      type OptionalCatchAllSlug<S extends string> =
+        S extends `${string}${SearchOrHash}` ? never : S
+"#};
+
+const TYPED_ROUTES_FOOTER: &str = indoc! {r#"
+
+      type RouteImpl<T> =
+        | StaticRoutes
+        | SearchOrHash
+        | WithProtocol
+        | `${StaticRoutes}${SearchOrHash}`
+        | (T extends `${DynamicRoutes<infer _>}${Suffix}` ? T : never)
+    }
+
+    declare module 'next' {
+      export { default } from 'next/types/index.js'
+      export * from 'next/types/index.js'
+
+      export type Route<T extends string = string> =
+        __next_route_internal_types__.RouteImpl<T>
+    }
+
+    declare module 'next/link' {
+      import type { LinkProps as OriginalLinkProps } from 'next/dist/client/link.js'
+      import type { AnchorHTMLAttributes, DetailedHTMLProps } from 'react'
+      import type { UrlObject } from 'url'
+
+      type LinkRestProps = Omit<
+        Omit<
+          DetailedHTMLProps<
+            AnchorHTMLAttributes<HTMLAnchorElement>,
+            HTMLAnchorElement
+          >,
+          keyof OriginalLinkProps
+        > &
+          OriginalLinkProps,
+        'href'
+      >
+
+      export type LinkProps<RouteInferType> = LinkRestProps & {
+        /**
+         * The path or URL to navigate to. This is the only required prop. It can also be an object.
+         * @see https://nextjs.org/docs/api-reference/next/link
+         */
+        href: __next_route_internal_types__.RouteImpl<RouteInferType> | UrlObject
+      }
+
+      export default function Link<RouteType>(props: LinkProps<RouteType>): JSX.Element
+    }
+"#};
+
+/// Returns the pathnames of all pages of the app directory. Route handlers
+/// and internal routes (e.g. `/_not-found`) can't be linked to and are
+/// skipped.
+#[turbo_tasks::function]
+pub async fn app_page_pathnames(entrypoints: Vc<Entrypoints>) -> Result<Vc<Vec<String>>> {
+    Ok(Vc::cell(
+        entrypoints
+            .await?
+            .iter()
+            .filter(|(pathname, entrypoint)| {
+                matches!(entrypoint, Entrypoint::AppPage { .. }) && !is_internal_route(pathname)
+            })
+            .map(|(pathname, _)| pathname.clone())
+            .collect(),
+    ))
+}
+
+/// Returns the pathnames of all pages of the pages directory, excluding API
+/// routes and the special `_app`, `_document` and `_error` pages.
+#[turbo_tasks::function]
+pub async fn pages_pathnames(pages_structure: Vc<PagesStructure>) -> Result<Vc<Vec<String>>> {
+    let mut pathnames = Vec::new();
+    if let Some(pages) = pages_structure.await?.pages {
+        let root = pages.next_router_path();
+        collect_pages_pathnames(&*root.await?, pages, &mut pathnames).await?;
+    }
+    Ok(Vc::cell(pathnames))
+}
+
+#[async_recursion]
+async fn collect_pages_pathnames(
+    root: &FileSystemPath,
+    directory: Vc<PagesDirectoryStructure>,
+    pathnames: &mut Vec<String>,
+) -> Result<()> {
+    let directory = directory.await?;
+    for item in directory.items.iter() {
+        let next_router_path = item.await?.next_router_path.await?;
+        if let Some(path) = root.get_path_to(&next_router_path) {
+            pathnames.push(format!("/{path}"));
+        }
+    }
+    for &child in directory.children.iter() {
+        collect_pages_pathnames(root, child, pathnames).await?;
+    }
+    Ok(())
+}
+
+fn is_internal_route(pathname: &str) -> bool {
+    pathname.split('/').any(|segment| segment.starts_with('_'))
+}
+
+/// Generates the route type declaration (`link.d.ts`) used by
+/// `experimental.typedRoutes`, restricting `next/link` hrefs to the given
+/// route pathnames (e.g. `/blog/[slug]`). Dynamic routes become template
+/// literal types matching any value of their parameters.
+#[turbo_tasks::function]
+pub async fn typed_routes_declaration(pathnames: Vc<Vec<String>>) -> Result<Vc<Rope>> {
+    Ok(Rope::from(routes_declaration(&pathnames.await?)).cell())
+}
+
+fn routes_declaration(pathnames: &[String]) -> String {
+    let mut static_routes = Vec::new();
+    let mut dynamic_routes = Vec::new();
+    for pathname in pathnames {
+        let (route, is_dynamic) = route_type(pathname);
+        let routes = if is_dynamic {
+            &mut dynamic_routes
+        } else {
+            &mut static_routes
+        };
+        if !routes.contains(&route) {
+            routes.push(route);
+        }
+    }
+
+    let mut declaration = TYPED_ROUTES_HEADER.to_string();
+    declaration.push_str(&format!(
+        "\n  type StaticRoutes = {}\n  type DynamicRoutes<T extends string = string> = {}\n",
+        route_union(&static_routes),
+        route_union(&dynamic_routes)
+    ));
+    declaration.push_str(TYPED_ROUTES_FOOTER);
+    declaration
+}
+
+/// Converts a pathname into a template literal type, returning whether the
+/// route is dynamic.
+fn route_type(pathname: &str) -> (String, bool) {
+    let mut is_dynamic = false;
+    let route = pathname
+        .split('/')
+        .map(|segment| match parse_dynamic_segment(segment) {
+            Some(param) => {
+                is_dynamic = true;
+                match param.kind {
+                    DynamicParamKind::Dynamic => "${SafeSlug<T>}",
+                    DynamicParamKind::CatchAll => "${CatchAllSlug<T>}",
+                    DynamicParamKind::OptionalCatchAll => "${OptionalCatchAllSlug<T>}",
+                }
+            }
+            None => segment,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (format!("`{route}`"), is_dynamic)
+}
+
+fn route_union(routes: &[String]) -> String {
+    if routes.is_empty() {
+        return "never".to_string();
+    }
+    routes
+        .iter()
+        .map(|route| format!("\n    | {route}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_internal_route, routes_declaration};
+
+    #[test]
+    fn test_routes_declaration() {
+        let declaration = routes_declaration(&[
+            "/".to_string(),
+            "/about".to_string(),
+            "/blog/[slug]".to_string(),
+            "/docs/[[...path]]".to_string(),
+        ]);
+        assert!(declaration.contains("type StaticRoutes = \n    | `/`\n    | `/about`\n"));
+        assert!(declaration.contains(
+            "type DynamicRoutes<T extends string = string> = \n    | `/blog/${SafeSlug<T>}`\n    \
+             | `/docs/${OptionalCatchAllSlug<T>}`\n"
+        ));
+        assert!(declaration.contains("declare module 'next/link'"));
+    }
+
+    #[test]
+    fn test_routes_declaration_without_dynamic_routes() {
+        let declaration = routes_declaration(&["/about".to_string()]);
+        assert!(declaration.contains("type DynamicRoutes<T extends string = string> = never\n"));
+    }
+
+    #[test]
+    fn test_internal_route() {
+        assert!(is_internal_route("/_not-found"));
+        assert!(!is_internal_route("/not-found"));
+    }
+}
@@ -360,9 +360,127 @@ pub fn get_sorted_routes(normalized_pages: &[String]) -> Result<Vec<String>, Url
     root.smoosh()
 }
 
+/// How two routes relate when matching a URL, following the precedence of
+/// [get_sorted_routes].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutePrecedence {
+    /// No URL is matched by both routes.
+    Disjoint,
+    /// Some URLs are matched by both routes, and the first route takes
+    /// precedence for them, e.g. `/blog/about` over `/blog/[slug]`.
+    First,
+    /// Some URLs are matched by both routes, and the second route takes
+    /// precedence for them.
+    Second,
+    /// Some URLs are matched by both routes with the same specificity, e.g.
+    /// `/blog/[id]` and `/blog/[slug]`, or `/` and `/[[...all]]`. Such routes
+    /// conflict.
+    Ambiguous,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RouteSegment<'a> {
+    Static(&'a str),
+    Dynamic,
+    CatchAll,
+    OptionalCatchAll,
+}
+
+impl<'a> RouteSegment<'a> {
+    fn parse(segment: &'a str) -> Self {
+        if segment.starts_with("[[...") && segment.ends_with("]]") {
+            RouteSegment::OptionalCatchAll
+        } else if segment.starts_with("[...") && segment.ends_with(']') {
+            RouteSegment::CatchAll
+        } else if segment.starts_with('[') && segment.ends_with(']') {
+            RouteSegment::Dynamic
+        } else {
+            RouteSegment::Static(segment)
+        }
+    }
+
+    /// The precedence of the segment among the segments at the same level,
+    /// lower is matched first.
+    fn rank(self) -> u8 {
+        match self {
+            RouteSegment::Static(_) => 0,
+            RouteSegment::Dynamic => 1,
+            RouteSegment::CatchAll => 2,
+            RouteSegment::OptionalCatchAll => 3,
+        }
+    }
+
+    fn is_catch_all(self) -> bool {
+        matches!(
+            self,
+            RouteSegment::CatchAll | RouteSegment::OptionalCatchAll
+        )
+    }
+}
+
+fn route_segments(route: &str) -> Vec<RouteSegment<'_>> {
+    route
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(RouteSegment::parse)
+        .collect()
+}
+
+/// Whether any URL is matched by both lists of segments.
+fn segments_overlap(a: &[RouteSegment], b: &[RouteSegment]) -> bool {
+    match (a.first(), b.first()) {
+        (None, None) => true,
+        (None, Some(segment)) | (Some(segment), None) => *segment == RouteSegment::OptionalCatchAll,
+        // Catch-alls are last and match any number of remaining segments.
+        (Some(a), Some(b)) if a.is_catch_all() || b.is_catch_all() => true,
+        (Some(RouteSegment::Static(a_name)), Some(RouteSegment::Static(b_name))) => {
+            a_name == b_name && segments_overlap(&a[1..], &b[1..])
+        }
+        (Some(_), Some(_)) => segments_overlap(&a[1..], &b[1..]),
+    }
+}
+
+/// Returns which of the routes `a` and `b` (e.g. `/blog/[slug]`) takes
+/// precedence for the URLs matched by both. This allows more precise conflict
+/// messages than comparing the normalized routes, as e.g. `/blog/[id]` and
+/// `/blog/[slug]` are ambiguous, while `/blog/[id]` shadows
+/// `/blog/[...path]` for URLs with a single segment after `/blog`.
+///
+/// The routes are expected to be valid (see [get_sorted_routes]).
+pub fn get_route_precedence(a: &str, b: &str) -> RoutePrecedence {
+    let a = route_segments(a);
+    let b = route_segments(b);
+    for i in 0.. {
+        match (a.get(i).copied(), b.get(i).copied()) {
+            (None, None) => return RoutePrecedence::Ambiguous,
+            (None, Some(RouteSegment::OptionalCatchAll))
+            | (Some(RouteSegment::OptionalCatchAll), None) => return RoutePrecedence::Ambiguous,
+            (None, Some(_)) | (Some(_), None) => return RoutePrecedence::Disjoint,
+            (Some(a_segment), Some(b_segment)) if a_segment == b_segment => {
+                if a_segment.is_catch_all() {
+                    return RoutePrecedence::Ambiguous;
+                }
+            }
+            (Some(RouteSegment::Static(_)), Some(RouteSegment::Static(_))) => {
+                return RoutePrecedence::Disjoint;
+            }
+            (Some(a_segment), Some(b_segment)) => {
+                return if !segments_overlap(&a[i..], &b[i..]) {
+                    RoutePrecedence::Disjoint
+                } else if a_segment.rank() < b_segment.rank() {
+                    RoutePrecedence::First
+                } else {
+                    RoutePrecedence::Second
+                };
+            }
+        }
+    }
+    unreachable!()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::get_sorted_routes;
+    use super::{get_route_precedence, get_sorted_routes, RoutePrecedence};
 
     #[test]
     fn does_not_add_extra_routes() {
@@ -685,4 +803,66 @@ mod tests {
             .to_string()
             .contains("differ only by non-word"));
     }
+
+    #[test]
+    fn route_precedence_of_static_and_dynamic_segments() {
+        assert_eq!(
+            get_route_precedence("/blog/about", "/blog/[slug]"),
+            RoutePrecedence::First
+        );
+        assert_eq!(
+            get_route_precedence("/[lang]/docs", "/en/[page]"),
+            RoutePrecedence::Second
+        );
+        assert_eq!(
+            get_route_precedence("/blog/[id]", "/blog/[slug]"),
+            RoutePrecedence::Ambiguous
+        );
+        assert_eq!(
+            get_route_precedence("/blog/about/team", "/blog/[slug]/posts"),
+            RoutePrecedence::Disjoint
+        );
+        assert_eq!(
+            get_route_precedence("/blog", "/blog/[slug]"),
+            RoutePrecedence::Disjoint
+        );
+        assert_eq!(
+            get_route_precedence("/blog", "/docs"),
+            RoutePrecedence::Disjoint
+        );
+    }
+
+    #[test]
+    fn route_precedence_of_catch_all_segments() {
+        assert_eq!(
+            get_route_precedence("/docs/[...path]", "/docs/[page]"),
+            RoutePrecedence::Second
+        );
+        assert_eq!(
+            get_route_precedence("/docs/intro/setup", "/docs/[...path]"),
+            RoutePrecedence::First
+        );
+        assert_eq!(
+            get_route_precedence("/docs/[...path]", "/docs/[[...path]]"),
+            RoutePrecedence::First
+        );
+        assert_eq!(
+            get_route_precedence("/docs/[...a]", "/docs/[...b]"),
+            RoutePrecedence::Ambiguous
+        );
+        // A required catch-all doesn't match the parent route, an optional one
+        // does.
+        assert_eq!(
+            get_route_precedence("/docs", "/docs/[...path]"),
+            RoutePrecedence::Disjoint
+        );
+        assert_eq!(
+            get_route_precedence("/docs", "/docs/[[...path]]"),
+            RoutePrecedence::Ambiguous
+        );
+        assert_eq!(
+            get_route_precedence("/blog/[...path]", "/docs/[...path]"),
+            RoutePrecedence::Disjoint
+        );
+    }
 }
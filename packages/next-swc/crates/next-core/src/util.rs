@@ -1,17 +1,29 @@
+use std::{io::Read, path::Path};
+
 use anyhow::{bail, Result};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use swc_core::ecma::ast::Program;
+use swc_core::{
+    common::{source_map::Pos, Span, Spanned},
+    ecma::ast::{Expr, Program, Prop, PropName, PropOrSpread},
+};
 use turbo_tasks::{trace::TraceRawVcs, TaskInput, ValueDefault, ValueToString, Vc};
 use turbo_tasks_fs::rope::Rope;
 use turbopack_binding::{
-    turbo::tasks_fs::{json::parse_json_rope_with_source_context, FileContent, FileSystemPath},
+    turbo::{
+        tasks_env::{CommandLineProcessEnv, ProcessEnv},
+        tasks_fs::{
+            json::parse_json_rope_with_source_context, DiskFileSystem, FileContent, FileSystem,
+            FileSystemEntryType, FileSystemPath,
+        },
+    },
     turbopack::{
         core::{
             environment::{ServerAddr, ServerInfo},
             ident::AssetIdent,
-            issue::{Issue, IssueExt, IssueSeverity},
+            issue::{Issue, IssueExt, IssueSeverity, IssueSource, OptionIssueSource},
             module::Module,
+            source::Source,
         },
         ecmascript::{
             analyzer::{JsValue, ObjectPart},
@@ -23,8 +35,10 @@ use turbopack_binding::{
 };
 
 use crate::{
+    app_segment_config::{NextRevalidate, OptionNextRevalidate},
     next_config::{NextConfig, OutputType},
     next_import_map::get_next_package,
+    next_manifests::RouteHas,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, TaskInput)]
@@ -80,6 +94,24 @@ pub fn get_asset_path_from_pathname(pathname: &str, ext: &str) -> String {
     format!("{}{}", get_asset_prefix_from_pathname(pathname), ext)
 }
 
+/// Returns the public URL prefix under which client assets are served, i.e.
+/// the `_next` directory under the `assetPrefix`, or under the `basePath` when
+/// no asset prefix is configured. The prefix always ends with a slash.
+pub fn client_asset_public_prefix(asset_prefix: &str, base_path: &str) -> String {
+    let prefix = if asset_prefix.is_empty() {
+        base_path
+    } else {
+        asset_prefix
+    };
+    format!("{}/_next/", prefix.trim_end_matches('/'))
+}
+
+/// Maps the path of a client asset relative to the `_next` directory (as
+/// stored in manifests) to its public URL.
+pub fn client_asset_public_path(public_prefix: &str, path: &str) -> String {
+    format!("{public_prefix}{}", path.trim_start_matches('/'))
+}
+
 pub async fn foreign_code_context_condition(
     next_config: Vc<NextConfig>,
 ) -> Result<ContextCondition> {
@@ -125,10 +157,43 @@ pub enum NextRuntime {
 #[turbo_tasks::value]
 #[derive(Default)]
 pub struct NextSourceConfig {
-    pub runtime: NextRuntime,
+    /// The runtime exported by the config, see
+    /// [NextConfig::effective_runtime].
+    pub runtime: Option<NextRuntime>,
 
     /// Middleware router matchers
-    pub matcher: Option<Vec<String>>,
+    pub matcher: Option<Vec<MiddlewareMatcherKind>>,
+}
+
+/// A middleware matcher as declared in the `matcher` config property, either
+/// a path pattern or an object which also restricts the matched requests with
+/// `has` and `missing` conditions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(untagged)]
+pub enum MiddlewareMatcherKind {
+    Str(String),
+    Matcher(ConfigMatcher),
+}
+
+impl MiddlewareMatcherKind {
+    /// The path pattern of the matcher.
+    pub fn source(&self) -> &str {
+        match self {
+            MiddlewareMatcherKind::Str(source) => source,
+            MiddlewareMatcherKind::Matcher(matcher) => &matcher.source,
+        }
+    }
+}
+
+/// The object form of a middleware matcher.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigMatcher {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has: Option<Vec<RouteHas>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing: Option<Vec<RouteHas>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -144,6 +209,7 @@ impl ValueDefault for NextSourceConfig {
 pub struct NextSourceConfigParsingIssue {
     ident: Vc<AssetIdent>,
     detail: Vc<String>,
+    source: Vc<IssueSource>,
 }
 
 #[turbo_tasks::value_impl]
@@ -181,10 +247,87 @@ impl Issue for NextSourceConfigParsingIssue {
     fn detail(&self) -> Vc<String> {
         self.detail
     }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        OptionIssueSource::some(self.source)
+    }
+}
+
+/// The properties of the config export which are known to Next.js. Only
+/// `runtime` and `matcher` are parsed here, the others are read by other parts
+/// of Next.js.
+const KNOWN_CONFIG_KEYS: &[&str] = &[
+    "runtime",
+    "matcher",
+    "amp",
+    "api",
+    "maxDuration",
+    "regions",
+    "unstable_allowDynamic",
+    "unstable_runtimeJS",
+    "unstable_JsPreload",
+];
+
+#[turbo_tasks::function]
+pub fn parse_config_from_source(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+) -> Vc<NextSourceConfig> {
+    parse_config_from_source_with_strictness(module, source, false)
+}
+
+/// Like [parse_config_from_source], but also reports properties of the config
+/// export which aren't known to Next.js (see [KNOWN_CONFIG_KEYS]), which are
+/// usually typos like `runtine`.
+#[turbo_tasks::function]
+pub fn parse_config_from_source_strict(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+) -> Vc<NextSourceConfig> {
+    parse_config_from_source_with_strictness(module, source, true)
+}
+
+/// Parses the config export of a page or API route, strictly when
+/// `experimental.strictSourceConfig` is set (see
+/// [parse_config_from_source_strict]) and leniently otherwise.
+#[turbo_tasks::function]
+pub async fn parse_page_config_from_source(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<NextSourceConfig>> {
+    Ok(
+        if next_config
+            .await?
+            .experimental
+            .strict_source_config
+            .unwrap_or(false)
+        {
+            parse_config_from_source_strict(module, source)
+        } else {
+            parse_config_from_source(module, source)
+        },
+    )
+}
+
+/// Returns the runtime of the page or API route `source`: the `runtime` of its
+/// `config` export, or otherwise the default runtime of the Next.js config.
+pub async fn get_page_runtime(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+    next_config: Vc<NextConfig>,
+) -> Result<NextRuntime> {
+    let config = parse_page_config_from_source(module, source, next_config).await?;
+    Ok(next_config.await?.effective_runtime(config.runtime))
 }
 
 #[turbo_tasks::function]
-pub async fn parse_config_from_source(module: Vc<Box<dyn Module>>) -> Result<Vc<NextSourceConfig>> {
+async fn parse_config_from_source_with_strictness(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+    strict: bool,
+) -> Result<Vc<NextSourceConfig>> {
     if let Some(ecmascript_asset) =
         Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(module).await?
     {
@@ -209,7 +352,10 @@ pub async fn parse_config_from_source(module: Vc<Box<dyn Module>>) -> Result<Vc<
                         {
                             if let Some(init) = decl.init.as_ref() {
                                 let value = eval_context.eval(init);
-                                return Ok(parse_config_from_js_value(module, &value).cell());
+                                return Ok(parse_config_from_js_value(
+                                    module, source, &value, init, strict,
+                                )
+                                .cell());
                             } else {
                                 NextSourceConfigParsingIssue {
                                     ident: module.ident(),
@@ -218,6 +364,7 @@ pub async fn parse_config_from_source(module: Vc<Box<dyn Module>>) -> Result<Vc<
                                          initializer."
                                             .to_string(),
                                     ),
+                                    source: issue_source(source, decl.span),
                                 }
                                 .cell()
                                 .emit()
@@ -231,13 +378,52 @@ pub async fn parse_config_from_source(module: Vc<Box<dyn Module>>) -> Result<Vc<
     Ok(Default::default())
 }
 
-fn parse_config_from_js_value(module: Vc<Box<dyn Module>>, value: &JsValue) -> NextSourceConfig {
+fn issue_source(source: Vc<Box<dyn Source>>, span: Span) -> Vc<IssueSource> {
+    IssueSource::from_byte_offset(source, span.lo.to_usize(), span.hi.to_usize())
+}
+
+/// Returns the span of the value of the `key` property in the config object
+/// literal `init`, or the span of the whole initializer if there is no such
+/// property.
+fn config_property_span(init: &Expr, key: &str) -> Span {
+    let Expr::Object(object) = init else {
+        return init.span();
+    };
+    object
+        .props
+        .iter()
+        .rev()
+        .find_map(|prop| {
+            let PropOrSpread::Prop(prop) = prop else {
+                return None;
+            };
+            let Prop::KeyValue(key_value) = &**prop else {
+                return None;
+            };
+            let matches = match &key_value.key {
+                PropName::Ident(ident) => &*ident.sym == key,
+                PropName::Str(str) => &*str.value == key,
+                _ => false,
+            };
+            matches.then(|| key_value.value.span())
+        })
+        .unwrap_or_else(|| init.span())
+}
+
+fn parse_config_from_js_value(
+    module: Vc<Box<dyn Module>>,
+    source: Vc<Box<dyn Source>>,
+    value: &JsValue,
+    init: &Expr,
+    strict: bool,
+) -> NextSourceConfig {
     let mut config = NextSourceConfig::default();
-    let invalid_config = |detail: &str, value: &JsValue| {
+    let invalid_config = |detail: &str, value: &JsValue, span: Span| {
         let (explainer, hints) = value.explain(2, 0);
         NextSourceConfigParsingIssue {
             ident: module.ident(),
             detail: Vc::cell(format!("{detail} Got {explainer}.{hints}")),
+            source: issue_source(source, span),
         }
         .cell()
         .emit()
@@ -248,24 +434,27 @@ fn parse_config_from_js_value(module: Vc<Box<dyn Module>>, value: &JsValue) -> N
                 ObjectPart::Spread(_) => invalid_config(
                     "Spread properties are not supported in the config export.",
                     value,
+                    init.span(),
                 ),
                 ObjectPart::KeyValue(key, value) => {
                     if let Some(key) = key.as_str() {
+                        let span = config_property_span(init, key);
                         if key == "runtime" {
                             if let JsValue::Constant(runtime) = value {
                                 if let Some(runtime) = runtime.as_str() {
                                     match runtime {
                                         "edge" | "experimental-edge" => {
-                                            config.runtime = NextRuntime::Edge;
+                                            config.runtime = Some(NextRuntime::Edge);
                                         }
                                         "nodejs" => {
-                                            config.runtime = NextRuntime::NodeJs;
+                                            config.runtime = Some(NextRuntime::NodeJs);
                                         }
                                         _ => {
                                             invalid_config(
                                                 "The runtime property must be either \"nodejs\" \
                                                  or \"edge\".",
                                                 value,
+                                                span,
                                             );
                                         }
                                     }
@@ -274,47 +463,49 @@ fn parse_config_from_js_value(module: Vc<Box<dyn Module>>, value: &JsValue) -> N
                                 invalid_config(
                                     "The runtime property must be a constant string.",
                                     value,
+                                    span,
                                 );
                             }
                         }
                         if key == "matcher" {
                             let mut matchers = vec![];
-                            match value {
-                                JsValue::Constant(matcher) => {
-                                    if let Some(matcher) = matcher.as_str() {
-                                        matchers.push(matcher.to_string());
-                                    } else {
-                                        invalid_config(
-                                            "The matcher property must be a string or array of \
-                                             strings",
-                                            value,
-                                        );
-                                    }
-                                }
-                                JsValue::Array { items, .. } => {
-                                    for item in items {
-                                        if let Some(matcher) = item.as_str() {
-                                            matchers.push(matcher.to_string());
-                                        } else {
-                                            invalid_config(
-                                                "The matcher property must be a string or array \
-                                                 of strings",
-                                                value,
-                                            );
-                                        }
-                                    }
+                            let items = match value {
+                                JsValue::Array { items, .. } => &items[..],
+                                value => std::slice::from_ref(value),
+                            };
+                            for item in items {
+                                if let Some(matcher) = parse_matcher_from_js_value(item) {
+                                    matchers.push(matcher);
+                                } else {
+                                    invalid_config(
+                                        "The matcher property must be a string, a matcher object \
+                                         with a source and optional has/missing conditions, or an \
+                                         array of those.",
+                                        value,
+                                        span,
+                                    );
                                 }
-                                _ => invalid_config(
-                                    "The matcher property must be a string or array of strings",
-                                    value,
-                                ),
                             }
                             config.matcher = Some(matchers);
                         }
+                        if strict && !KNOWN_CONFIG_KEYS.contains(&key) {
+                            NextSourceConfigParsingIssue {
+                                ident: module.ident(),
+                                detail: Vc::cell(format!(
+                                    "The config property \"{key}\" is unknown. Known properties \
+                                     are {}.",
+                                    KNOWN_CONFIG_KEYS.join(", ")
+                                )),
+                                source: issue_source(source, span),
+                            }
+                            .cell()
+                            .emit()
+                        }
                     } else {
                         invalid_config(
                             "The exported config object must not contain non-constant strings.",
                             key,
+                            init.span(),
                         );
                     }
                 }
@@ -324,28 +515,142 @@ fn parse_config_from_js_value(module: Vc<Box<dyn Module>>, value: &JsValue) -> N
         invalid_config(
             "The exported config object must be a valid object literal.",
             value,
+            init.span(),
         );
     }
 
     config
 }
 
+fn parse_matcher_from_js_value(value: &JsValue) -> Option<MiddlewareMatcherKind> {
+    serde_json::from_value(js_value_to_json(value)?).ok()
+}
+
+/// Converts a statically known value made of strings, arrays and object
+/// literals into JSON.
+fn js_value_to_json(value: &JsValue) -> Option<JsonValue> {
+    match value {
+        JsValue::Array { items, .. } => items
+            .iter()
+            .map(js_value_to_json)
+            .collect::<Option<Vec<_>>>()
+            .map(JsonValue::Array),
+        JsValue::Object { parts, .. } => parts
+            .iter()
+            .map(|part| match part {
+                ObjectPart::KeyValue(key, value) => {
+                    Some((key.as_str()?.to_string(), js_value_to_json(value)?))
+                }
+                ObjectPart::Spread(_) => None,
+            })
+            .collect::<Option<serde_json::Map<_, _>>>()
+            .map(JsonValue::Object),
+        value => value.as_str().map(|str| JsonValue::String(str.to_string())),
+    }
+}
+
+/// The env var to load Next.js templates from a specific `next` package
+/// instead of the one resolved from the project, e.g. to pin the templates to
+/// a certain Next.js version. Relative paths are resolved from the project
+/// directory, absolute paths must be inside the project's file system.
+pub const NEXT_PACKAGE_PATH_ENV: &str = "NEXT_PACKAGE_PATH";
+
+/// Returns the `next` package to load templates from, see
+/// [next_templates_package]. [NEXT_PACKAGE_PATH_ENV] is read from the
+/// environment of the process.
+#[turbo_tasks::function]
+pub fn get_next_templates_package(project_path: Vc<FileSystemPath>) -> Vc<FileSystemPath> {
+    next_templates_package(project_path, Vc::upcast(CommandLineProcessEnv::new()))
+}
+
+/// Returns the `next` package to load templates from, which is the package
+/// set with [NEXT_PACKAGE_PATH_ENV] in `env` or else the one resolved from
+/// the project.
+#[turbo_tasks::function]
+pub async fn next_templates_package(
+    project_path: Vc<FileSystemPath>,
+    env: Vc<Box<dyn ProcessEnv>>,
+) -> Result<Vc<FileSystemPath>> {
+    Ok(match &*env.read(NEXT_PACKAGE_PATH_ENV.to_string()).await? {
+        Some(path) if !path.is_empty() => next_package_override(project_path, path.clone()),
+        _ => get_next_package(project_path),
+    })
+}
+
+/// Resolves an overridden `next` package location (see
+/// [NEXT_PACKAGE_PATH_ENV]) and validates that it contains the compiled
+/// templates in `dist/esm`.
 #[turbo_tasks::function]
-pub async fn load_next_js_template(
+pub async fn next_package_override(
     project_path: Vc<FileSystemPath>,
     path: String,
+) -> Result<Vc<FileSystemPath>> {
+    let package = if Path::new(&path).is_absolute() {
+        let fs = project_path.await?.fs;
+        let Some(disk_fs) = Vc::try_resolve_downcast_type::<DiskFileSystem>(fs).await? else {
+            bail!("The next package path {path} must be relative to the project");
+        };
+        let root = disk_fs.await?.to_sys_path(fs.root()).await?;
+        let Ok(relative) = Path::new(&path).strip_prefix(&root) else {
+            bail!(
+                "The next package path {path} must be inside the project root {}",
+                root.display()
+            );
+        };
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        fs.root().join(relative)
+    } else {
+        let Some(package) = *project_path.try_join(path.clone()).await? else {
+            bail!("The next package path {path} must be inside the project root");
+        };
+        package
+    };
+
+    let templates_path = package.join("dist/esm".to_string());
+    if !matches!(
+        *templates_path.get_type().await?,
+        FileSystemEntryType::Directory
+    ) {
+        bail!("The next package path {path} doesn't contain the templates in dist/esm");
+    }
+    Ok(package)
+}
+
+#[turbo_tasks::function]
+pub fn load_next_js_template(project_path: Vc<FileSystemPath>, path: String) -> Vc<Rope> {
+    load_next_js_template_from_package(get_next_templates_package(project_path), path)
+}
+
+/// Loads a Next.js template from the given `next` package.
+#[turbo_tasks::function]
+pub async fn load_next_js_template_from_package(
+    next_package: Vc<FileSystemPath>,
+    path: String,
 ) -> Result<Vc<Rope>> {
-    let file_path = get_next_package(project_path)
-        .join("dist/esm".to_string())
-        .join(path);
+    let templates_path = next_package.join("dist/esm".to_string());
 
-    let content = &*file_path.read().await?;
+    if let FileContent::Content(file) = &*templates_path.join(path.clone()).read().await? {
+        return Ok(file.content().to_owned().cell());
+    }
 
-    let FileContent::Content(file) = content else {
+    // Templates may be shipped brotli-compressed, the plain file is preferred
+    // when both exist.
+    let compressed_path = templates_path.join(format!("{path}.br"));
+    let FileContent::Content(file) = &*compressed_path.read().await? else {
         bail!("Expected file content for file");
     };
 
-    Ok(file.content().to_owned().cell())
+    Ok(Rope::from(decompress_brotli(&file.content().to_bytes()?)?).cell())
+}
+
+fn decompress_brotli(compressed: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
 }
 
 #[turbo_tasks::function]
@@ -353,11 +658,24 @@ pub fn virtual_next_js_template_path(
     project_path: Vc<FileSystemPath>,
     path: String,
 ) -> Vc<FileSystemPath> {
-    get_next_package(project_path)
+    get_next_templates_package(project_path)
         .join("dist/esm".to_string())
         .join(path)
 }
 
+/// Invalidates every read from the given file system, if it is backed by the
+/// disk. Other file systems are left untouched.
+///
+/// All tasks that read a file or directory from it are recomputed on their
+/// next use, which is about as expensive as a cold start for everything
+/// depending on the file system.
+pub async fn invalidate_disk_fs(fs: Vc<Box<dyn FileSystem>>) -> Result<()> {
+    if let Some(disk_fs) = Vc::try_resolve_downcast_type::<DiskFileSystem>(fs).await? {
+        disk_fs.await?.invalidate();
+    }
+    Ok(())
+}
+
 pub async fn load_next_js_templateon<T: DeserializeOwned>(
     project_path: Vc<FileSystemPath>,
     path: String,
@@ -406,3 +724,514 @@ pub async fn render_data(
     })?;
     Ok(Vc::cell(value))
 }
+
+/// Adds the effective `revalidate` of a route (see
+/// [crate::get_route_revalidate]) to its render data, in seconds or `false` to
+/// never revalidate.
+#[turbo_tasks::function]
+pub async fn route_render_data(
+    render_data: Vc<JsonValue>,
+    revalidate: Vc<OptionNextRevalidate>,
+) -> Result<Vc<JsonValue>> {
+    let revalidate = match *revalidate.await? {
+        Some(NextRevalidate::Frequency { seconds }) => JsonValue::from(seconds),
+        Some(NextRevalidate::Never) => JsonValue::Bool(false),
+        Some(NextRevalidate::ForceCache) | None => return Ok(render_data),
+    };
+    let mut data = (*render_data.await?).clone();
+    if let JsonValue::Object(data) = &mut data {
+        data.insert("revalidate".to_string(), revalidate);
+    }
+    Ok(Vc::cell(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use anyhow::Result;
+    use indexmap::IndexMap;
+    use indoc::indoc;
+    use swc_core::{
+        common::{source_map::Pos, sync::Lrc, FileName, SourceMap},
+        ecma::parser::{Parser, StringInput, Syntax},
+    };
+    use turbo_tasks::{Value, Vc};
+    use turbopack_binding::{
+        turbo::{
+            tasks::TurboTasks,
+            tasks_env::{EnvMap, ProcessEnv},
+            tasks_fs::{DiskFileSystem, FileSystem},
+            tasks_memory::MemoryBackend,
+        },
+        turbopack::{
+            core::{
+                context::AssetContext, environment::ServerAddr, file_source::FileSource,
+                reference_type::ReferenceType,
+            },
+            turbopack::{
+                module_options::ModuleOptionsContext,
+                resolve_options_context::ResolveOptionsContext, ModuleAssetContext,
+            },
+        },
+    };
+
+    use super::{
+        client_asset_public_path, client_asset_public_prefix, config_property_span,
+        decompress_brotli, invalidate_disk_fs, load_next_js_template,
+        load_next_js_template_from_package, next_package_override, next_templates_package,
+        parse_config_from_source, parse_config_from_source_strict, parse_page_config_from_source,
+        ConfigMatcher, MiddlewareMatcherKind, NextRuntime, NEXT_PACKAGE_PATH_ENV,
+    };
+    use crate::{
+        next_config::{ExperimentalConfig, NextConfig},
+        next_edge::context::get_edge_compile_time_info,
+        next_import_map::get_next_package,
+        next_manifests::RouteHas,
+        test_util::{issues_of, Fixture},
+    };
+
+    #[test]
+    fn test_decompress_brotli_template() {
+        // `export default "template"\n` stored as an uncompressed brotli meta-block.
+        let compressed = [
+            0x90, 0x01, 0x10, 0x65, 0x78, 0x70, 0x6f, 0x72, 0x74, 0x20, 0x64, 0x65, 0x66, 0x61,
+            0x75, 0x6c, 0x74, 0x20, 0x22, 0x74, 0x65, 0x6d, 0x70, 0x6c, 0x61, 0x74, 0x65, 0x22,
+            0x0a, 0x03,
+        ];
+        assert_eq!(
+            decompress_brotli(&compressed).unwrap(),
+            b"export default \"template\"\n"
+        );
+    }
+
+    #[test]
+    fn test_client_asset_public_path() {
+        let public_path = |asset_prefix: &str, base_path: &str| {
+            client_asset_public_path(
+                &client_asset_public_prefix(asset_prefix, base_path),
+                "static/chunks/main.js",
+            )
+        };
+        assert_eq!(public_path("", ""), "/_next/static/chunks/main.js");
+        assert_eq!(
+            public_path("", "/docs"),
+            "/docs/_next/static/chunks/main.js"
+        );
+        assert_eq!(
+            public_path("", "/docs/"),
+            "/docs/_next/static/chunks/main.js"
+        );
+        assert_eq!(
+            public_path("https://cdn.example.com", "/docs"),
+            "https://cdn.example.com/_next/static/chunks/main.js"
+        );
+        assert_eq!(
+            public_path("https://cdn.example.com/", ""),
+            "https://cdn.example.com/_next/static/chunks/main.js"
+        );
+        assert_eq!(
+            public_path("/assets/", "/docs"),
+            "/assets/_next/static/chunks/main.js"
+        );
+        assert_eq!(
+            client_asset_public_path("/_next/", "/static/media/font.woff2"),
+            "/_next/static/media/font.woff2"
+        );
+    }
+
+    #[test]
+    fn test_config_property_span() {
+        let code = "export const config = { matcher: '/about', runtime: 'bun' }";
+        let cm = Lrc::<SourceMap>::default();
+        let fm = cm.new_source_file(FileName::Anon, code.to_string());
+        let mut parser = Parser::new(
+            Syntax::Es(Default::default()),
+            StringInput::from(&*fm),
+            None,
+        );
+        let module = parser.parse_module().unwrap();
+        let init = module.body[0]
+            .as_module_decl()
+            .and_then(|decl| decl.as_export_decl())
+            .and_then(|decl| decl.decl.as_var())
+            .and_then(|decl| decl.decls[0].init.as_deref())
+            .unwrap();
+
+        let span = config_property_span(init, "runtime");
+        assert!(span.lo < span.hi);
+        let start = (span.lo - fm.start_pos).to_usize();
+        let end = (span.hi - fm.start_pos).to_usize();
+        assert_eq!(&code[start..end], "'bun'");
+
+        // Unknown keys point at the whole object.
+        let span = config_property_span(init, "regions");
+        let start = (span.lo - fm.start_pos).to_usize();
+        let end = (span.hi - fm.start_pos).to_usize();
+        assert_eq!(&code[start..end], "{ matcher: '/about', runtime: 'bun' }");
+    }
+
+    #[tokio::test]
+    async fn test_load_template_from_pnpm_store() -> Result<()> {
+        let fixture = Fixture::new([
+            (
+                "node_modules/.pnpm/next@13.4.20/node_modules/next/package.json",
+                r#"{ "name": "next", "version": "13.4.20" }"#,
+            ),
+            (
+                "node_modules/.pnpm/next@13.4.20/node_modules/next/dist/esm/build/templates/\
+                 app-page.js",
+                "export default \"template\"\n",
+            ),
+        ])?;
+        let root = fixture.path().canonicalize()?;
+        let store_next = root.join("node_modules/.pnpm/next@13.4.20/node_modules/next");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&store_next, root.join("node_modules/next"))?;
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_dir(&store_next, root.join("node_modules/next"))?;
+
+        fixture
+            .run(|project_path| async move {
+                let next_package = get_next_package(project_path).await?;
+                assert_eq!(
+                    next_package.path,
+                    "node_modules/.pnpm/next@13.4.20/node_modules/next"
+                );
+
+                let template =
+                    load_next_js_template(project_path, "build/templates/app-page.js".to_string())
+                        .await?;
+                assert_eq!(template.to_str()?, "export default \"template\"\n");
+
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_next_package_from_workspace_package() -> Result<()> {
+        let fixture = Fixture::new([
+            (
+                "node_modules/.pnpm/next@13.4.20/node_modules/next/package.json",
+                r#"{ "name": "next", "version": "13.4.20" }"#,
+            ),
+            ("apps/web/package.json", "{}"),
+        ])?;
+        let root = fixture.path().canonicalize()?;
+        fs::create_dir_all(root.join("apps/web/node_modules"))?;
+        // pnpm links the dependencies of a workspace package relatively into
+        // the store at the workspace root.
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(
+            "../../../node_modules/.pnpm/next@13.4.20/node_modules/next",
+            root.join("apps/web/node_modules/next"),
+        )?;
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_dir(
+            root.join("node_modules/.pnpm/next@13.4.20/node_modules/next"),
+            root.join("apps/web/node_modules/next"),
+        )?;
+
+        fixture
+            .run(|root| async move {
+                let next_package = get_next_package(root.join("apps/web".to_string())).await?;
+                assert_eq!(
+                    next_package.path,
+                    "node_modules/.pnpm/next@13.4.20/node_modules/next"
+                );
+
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_template_from_next_package_override() -> Result<()> {
+        let fixture = Fixture::new([
+            (
+                "node_modules/next/dist/esm/build/templates/app-page.js",
+                "export default \"project\"\n",
+            ),
+            ("node_modules/next/package.json", "{ \"name\": \"next\" }"),
+            (
+                "vendor/next-13.4.20/dist/esm/build/templates/app-page.js",
+                "export default \"pinned\"\n",
+            ),
+            ("vendor/empty/package.json", "{}"),
+        ])?;
+        let pinned_next = fixture
+            .path()
+            .canonicalize()?
+            .join("vendor/next-13.4.20")
+            .to_string_lossy()
+            .to_string();
+
+        fixture
+            .run(|project_path| async move {
+                let load = |next_package: String| async move {
+                    let next_package = next_package_override(project_path, next_package);
+                    let template = load_next_js_template_from_package(
+                        next_package,
+                        "build/templates/app-page.js".to_string(),
+                    )
+                    .await?;
+                    anyhow::Ok(template.to_str()?.to_string())
+                };
+
+                assert_eq!(
+                    load("vendor/next-13.4.20".to_string()).await?,
+                    "export default \"pinned\"\n"
+                );
+                assert_eq!(load(pinned_next).await?, "export default \"pinned\"\n");
+
+                let error = load("vendor/empty".to_string()).await.unwrap_err();
+                assert!(format!("{error:#}").contains("dist/esm"), "{error:#}");
+
+                // The override is read from the environment, falling back to the
+                // package resolved from the project.
+                let load_with_env = |env: IndexMap<String, String>| async move {
+                    let env = Vc::upcast::<Box<dyn ProcessEnv>>(Vc::<EnvMap>::cell(env));
+                    let template = load_next_js_template_from_package(
+                        next_templates_package(project_path, env),
+                        "build/templates/app-page.js".to_string(),
+                    )
+                    .await?;
+                    anyhow::Ok(template.to_str()?.to_string())
+                };
+                assert_eq!(
+                    load_with_env(IndexMap::from([(
+                        NEXT_PACKAGE_PATH_ENV.to_string(),
+                        "vendor/next-13.4.20".to_string()
+                    )]))
+                    .await?,
+                    "export default \"pinned\"\n"
+                );
+                assert_eq!(
+                    load_with_env(IndexMap::new()).await?,
+                    "export default \"project\"\n"
+                );
+
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reload_template_after_invalidation() -> Result<()> {
+        crate::register();
+
+        let fixture = Fixture::new([
+            (
+                "node_modules/next/package.json",
+                r#"{ "name": "next", "version": "13.4.20" }"#,
+            ),
+            (
+                "node_modules/next/dist/esm/build/templates/app-page.js",
+                "export default \"old\"\n",
+            ),
+        ])?;
+        let root = fixture.path().canonicalize()?;
+        let template = root.join("node_modules/next/dist/esm/build/templates/app-page.js");
+
+        let tt = TurboTasks::new(MemoryBackend::default());
+        let root = root.to_string_lossy().to_string();
+        let fs = tt
+            .run_once(async move {
+                let fs = Vc::upcast::<Box<dyn FileSystem>>(DiskFileSystem::new(
+                    "project".to_string(),
+                    root,
+                ));
+                Ok(fs.resolve().await?)
+            })
+            .await?;
+        let load = || {
+            tt.run_once(async move {
+                let template =
+                    load_next_js_template(fs.root(), "build/templates/app-page.js".to_string())
+                        .await?;
+                Ok(template.to_str()?.to_string())
+            })
+        };
+
+        assert_eq!(load().await?, "export default \"old\"\n");
+
+        // The file system isn't watched, so the change is only picked up after
+        // invalidating it.
+        fs::write(&template, "export default \"new\"\n")?;
+        assert_eq!(load().await?, "export default \"old\"\n");
+
+        tt.run_once(async move { invalidate_disk_fs(fs).await })
+            .await?;
+        assert_eq!(load().await?, "export default \"new\"\n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_middleware_matchers() -> Result<()> {
+        Fixture::new([
+            (
+                "middleware.js",
+                indoc! {r#"
+                export const config = {
+                  matcher: [
+                    '/about/:path*',
+                    {
+                      source: '/api/:path*',
+                      has: [{ type: 'header', key: 'authorization' }],
+                      missing: [{ type: 'cookie', key: 'session', value: 'guest' }],
+                    },
+                  ],
+                }
+                export function middleware() {}
+            "#},
+            ),
+            (
+                "edge.js",
+                "export const config = { runtime: 'edge' }\nexport function middleware() {}\n",
+            ),
+        ])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let config = |file: &str| {
+                let source = Vc::upcast(FileSource::new(project_path.join(file.to_string())));
+                let module = context.process(source, Value::new(ReferenceType::Undefined));
+                parse_config_from_source(module, source)
+            };
+
+            let middleware = config("middleware.js").await?;
+            assert_eq!(
+                middleware.matcher,
+                Some(vec![
+                    MiddlewareMatcherKind::Str("/about/:path*".to_string()),
+                    MiddlewareMatcherKind::Matcher(ConfigMatcher {
+                        source: "/api/:path*".to_string(),
+                        has: Some(vec![RouteHas::Header {
+                            key: "authorization".to_string(),
+                            value: None,
+                        }]),
+                        missing: Some(vec![RouteHas::Cookie {
+                            key: "session".to_string(),
+                            value: Some("guest".to_string()),
+                        }]),
+                    }),
+                ])
+            );
+
+            let edge = config("edge.js").await?;
+            assert_eq!(edge.matcher, None);
+            assert_eq!(edge.runtime, Some(NextRuntime::Edge));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_source_config_effective_runtime() -> Result<()> {
+        Fixture::new([
+            ("route.js", "export function GET() {}\n"),
+            (
+                "node.js",
+                "export const config = { runtime: 'nodejs' }\nexport function GET() {}\n",
+            ),
+        ])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let config = |file: &str| {
+                let source = Vc::upcast(FileSource::new(project_path.join(file.to_string())));
+                parse_config_from_source(
+                    context.process(source, Value::new(ReferenceType::Undefined)),
+                    source,
+                )
+            };
+            let edge_default = NextConfig {
+                experimental: ExperimentalConfig {
+                    runtime: Some(serde_json::json!("edge")),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let route = config("route.js").await?;
+            assert_eq!(route.runtime, None);
+            assert_eq!(
+                NextConfig::default().effective_runtime(route.runtime),
+                NextRuntime::NodeJs
+            );
+            assert_eq!(
+                edge_default.effective_runtime(route.runtime),
+                NextRuntime::Edge
+            );
+
+            let node = config("node.js").await?;
+            assert_eq!(
+                edge_default.effective_runtime(node.runtime),
+                NextRuntime::NodeJs
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_strict_config_reports_unknown_keys() -> Result<()> {
+        Fixture::new([(
+            "page.js",
+            "export const config = { runtine: 'edge' }\nexport default function Page() {}\n",
+        )])?
+        .run(|project_path| async move {
+            let context = ModuleAssetContext::new(
+                Vc::cell(HashMap::new()),
+                get_edge_compile_time_info(project_path, ServerAddr::empty()),
+                ModuleOptionsContext::default().cell(),
+                ResolveOptionsContext::default().cell(),
+            );
+            let source = Vc::upcast(FileSource::new(project_path.join("page.js".to_string())));
+            let module = context.process(source, Value::new(ReferenceType::Undefined));
+
+            let lenient = parse_config_from_source(module, source);
+            assert_eq!(lenient.await?.runtime, None);
+            assert!(issues_of(lenient).await?.is_empty());
+
+            let strict = parse_config_from_source_strict(module, source);
+            assert_eq!(strict.await?.runtime, None);
+            let issues = issues_of(strict).await?;
+            assert_eq!(issues.len(), 1);
+            assert!(issues[0].detail.contains("\"runtine\""));
+
+            // Pages are only parsed strictly when enabled in the Next.js config.
+            let default_config = NextConfig::default().cell();
+            let page = parse_page_config_from_source(module, source, default_config);
+            assert!(issues_of(page).await?.is_empty());
+            let strict_config = NextConfig {
+                experimental: ExperimentalConfig {
+                    strict_source_config: Some(true),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .cell();
+            let page = parse_page_config_from_source(module, source, strict_config);
+            assert_eq!(issues_of(page).await?.len(), 1);
+
+            Ok(())
+        })
+        .await
+    }
+}
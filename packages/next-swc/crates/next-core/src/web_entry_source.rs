@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
 use turbo_tasks::Vc;
 use turbopack_binding::{
     turbo::{
@@ -16,6 +17,7 @@ use turbopack_binding::{
             environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
             file_source::FileSource,
             free_var_references,
+            output::OutputAsset,
             reference_type::{EntryReferenceSubType, ReferenceType},
             resolve::{
                 origin::{PlainResolveOrigin, ResolveOrigin, ResolveOriginExt},
@@ -164,6 +166,14 @@ fn get_web_client_asset_context(
     context
 }
 
+/// Named groups of entry requests, each of which becomes its own web entry.
+#[turbo_tasks::value(transparent)]
+pub struct WebEntryRequests(IndexMap<String, Vec<Vc<Request>>>);
+
+/// Web entry content sources by entry name.
+#[turbo_tasks::value(transparent)]
+pub struct WebEntrySources(IndexMap<String, Vc<Box<dyn ContentSource>>>);
+
 #[turbo_tasks::function]
 pub async fn create_web_entry_source(
     project_root: Vc<FileSystemPath>,
@@ -174,9 +184,76 @@ pub async fn create_web_entry_source(
     browserslist_query: String,
     next_config: Vc<NextConfig>,
 ) -> Result<Vc<Box<dyn ContentSource>>> {
+    let compile_time_info = get_compile_time_info(browserslist_query);
+    let entry_asset = get_web_entry_html(
+        project_root,
+        execution_context,
+        entry_requests,
+        client_root,
+        client_root.join("index.html".to_string()),
+        compile_time_info,
+        next_config,
+    );
+
+    let graph = Vc::upcast(if eager_compile {
+        AssetGraphContentSource::new_eager(client_root, entry_asset)
+    } else {
+        AssetGraphContentSource::new_lazy(client_root, entry_asset)
+    });
+    Ok(graph)
+}
+
+/// Creates a web entry for every named group of requests. Each entry is
+/// served from `<name>/index.html`. All entries share the same asset and
+/// chunking contexts, so chunks of modules used by several entries are only
+/// emitted once.
+#[turbo_tasks::function]
+pub async fn create_web_entry_sources(
+    project_root: Vc<FileSystemPath>,
+    execution_context: Vc<ExecutionContext>,
+    entry_requests: Vc<WebEntryRequests>,
+    client_root: Vc<FileSystemPath>,
+    eager_compile: bool,
+    browserslist_query: String,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<WebEntrySources>> {
+    let compile_time_info = get_compile_time_info(browserslist_query);
+    let sources = entry_requests
+        .await?
+        .iter()
+        .map(|(name, requests)| {
+            let entry_asset = get_web_entry_html(
+                project_root,
+                execution_context,
+                requests.clone(),
+                client_root,
+                client_root.join(format!("{name}/index.html")),
+                compile_time_info,
+                next_config,
+            );
+            let source: Vc<Box<dyn ContentSource>> = Vc::upcast(if eager_compile {
+                AssetGraphContentSource::new_eager(client_root, entry_asset)
+            } else {
+                AssetGraphContentSource::new_lazy(client_root, entry_asset)
+            });
+            (name.clone(), source)
+        })
+        .collect();
+    Ok(Vc::cell(sources))
+}
+
+#[turbo_tasks::function]
+async fn get_web_entry_html(
+    project_root: Vc<FileSystemPath>,
+    execution_context: Vc<ExecutionContext>,
+    entry_requests: Vec<Vc<Request>>,
+    client_root: Vc<FileSystemPath>,
+    html_path: Vc<FileSystemPath>,
+    compile_time_info: Vc<CompileTimeInfo>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<Box<dyn OutputAsset>>> {
     let ty = Value::new(ClientContextType::Other);
     let mode = NextMode::DevServer;
-    let compile_time_info = get_compile_time_info(browserslist_query);
     let context = get_web_client_asset_context(
         project_root,
         execution_context,
@@ -238,15 +315,105 @@ pub async fn create_web_entry_source(
         .try_join()
         .await?;
 
-    let entry_asset = Vc::upcast(DevHtmlAsset::new(
-        client_root.join("index.html".to_string()),
-        entries,
-    ));
+    Ok(Vc::upcast(DevHtmlAsset::new(html_path, entries)))
+}
 
-    let graph = Vc::upcast(if eager_compile {
-        AssetGraphContentSource::new_eager(client_root, entry_asset)
-    } else {
-        AssetGraphContentSource::new_lazy(client_root, entry_asset)
-    });
-    Ok(graph)
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use turbo_tasks::{TryJoinIterExt, Vc};
+    use turbopack_binding::{
+        turbo::tasks_env::{CommandLineProcessEnv, ProcessEnv},
+        turbopack::{
+            core::{
+                output::{OutputAsset, OutputAssets},
+                resolve::parse::Request,
+            },
+            dev::DevChunkingContext,
+            node::execution_context::ExecutionContext,
+            turbopack::evaluate_context::node_build_environment,
+        },
+    };
+
+    use super::{get_compile_time_info, get_web_entry_html};
+    use crate::{emit::all_assets_from_entries, next_config::NextConfig, test_util::Fixture};
+
+    #[tokio::test]
+    async fn test_shared_chunks_are_emitted_once() -> Result<()> {
+        Fixture::new([
+            ("shared.js", "export const shared = 'shared'\n"),
+            (
+                "a.js",
+                "import { shared } from './shared.js'\nconsole.log('a', shared)\n",
+            ),
+            (
+                "b.js",
+                "import { shared } from './shared.js'\nconsole.log('b', shared)\n",
+            ),
+        ])?
+        .run(|project_root| async move {
+            let build_root = project_root.join(".next/build".to_string());
+            let execution_context = ExecutionContext::new(
+                project_root,
+                Vc::upcast(
+                    DevChunkingContext::builder(
+                        project_root,
+                        build_root,
+                        build_root.join("chunks".to_string()),
+                        build_root.join("assets".to_string()),
+                        node_build_environment(),
+                    )
+                    .build(),
+                ),
+                Vc::upcast::<Box<dyn ProcessEnv>>(CommandLineProcessEnv::new()),
+            );
+            let client_root = project_root.join(".next/client".to_string());
+            // The same contexts `create_web_entry_sources` uses for every entry.
+            let compile_time_info = get_compile_time_info("last 1 Chrome versions".to_string());
+            let next_config = NextConfig::default().cell();
+            let entry_assets = |name: &str| {
+                let html = get_web_entry_html(
+                    project_root,
+                    execution_context,
+                    vec![Request::parse_string(format!("./{name}.js"))],
+                    client_root,
+                    client_root.join(format!("{name}/index.html")),
+                    compile_time_info,
+                    next_config,
+                );
+                async move {
+                    all_assets_from_entries(Vc::<OutputAssets>::cell(vec![html]))
+                        .await?
+                        .iter()
+                        .map(|&asset| async move {
+                            Ok((
+                                asset.ident().path().await?.path.clone(),
+                                asset.resolve().await?,
+                            ))
+                        })
+                        .try_join()
+                        .await
+                }
+            };
+
+            let a = entry_assets("a").await?;
+            let b = entry_assets("b").await?;
+            // The chunks both entries depend on, e.g. the runtime, are shared.
+            assert!(a.iter().any(|asset| b.contains(asset)));
+
+            // Assets at the same path are the same asset, so they are only
+            // emitted once.
+            let mut assets_by_path = HashMap::<String, Vc<Box<dyn OutputAsset>>>::new();
+            for (path, asset) in a.into_iter().chain(b) {
+                if let Some(existing) = assets_by_path.insert(path.clone(), asset) {
+                    assert_eq!(existing, asset, "{path} is emitted by two different assets");
+                }
+            }
+
+            Ok(())
+        })
+        .await
+    }
 }
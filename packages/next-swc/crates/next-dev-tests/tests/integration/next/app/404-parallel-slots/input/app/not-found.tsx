@@ -0,0 +1,3 @@
+export default function NotFound() {
+  return <div data-test-notfound>Custom not found</div>
+}
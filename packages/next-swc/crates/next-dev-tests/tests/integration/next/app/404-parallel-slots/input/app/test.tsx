@@ -0,0 +1,13 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('returns a 404 status code for the not-found page with a root slot', async () => {
+      const res = await fetch('/not-found')
+      expect(res.status).toBe(404)
+      expect(await res.text()).toContain('Custom not found')
+    }, 20000)
+  })
+}
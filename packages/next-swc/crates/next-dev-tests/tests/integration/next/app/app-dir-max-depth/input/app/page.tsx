@@ -0,0 +1,9 @@
+import Test from './test'
+
+export default function Page() {
+  return (
+    <div>
+      <Test />
+    </div>
+  )
+}
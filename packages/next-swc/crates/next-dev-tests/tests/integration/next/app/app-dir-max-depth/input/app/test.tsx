@@ -0,0 +1,17 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should serve routes within the maximum depth', async () => {
+      const res = await fetch('/a/b')
+      expect(res.status).toBe(200)
+    })
+
+    it('should not serve routes below the maximum depth', async () => {
+      const res = await fetch('/a/b/c')
+      expect(res.status).toBe(404)
+    })
+  })
+}
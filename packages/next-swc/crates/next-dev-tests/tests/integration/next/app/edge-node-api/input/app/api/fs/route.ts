@@ -0,0 +1,7 @@
+import { readFileSync } from 'fs'
+
+export async function GET(): Promise<Response> {
+  return new Response(typeof readFileSync)
+}
+
+export const runtime = 'edge'
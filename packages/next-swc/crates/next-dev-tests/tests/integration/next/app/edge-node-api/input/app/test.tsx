@@ -0,0 +1,15 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    // The unsupported `fs` import is reported as an issue (see the issue
+    // snapshots) and resolves to nothing instead of failing the build.
+    it('should still serve the edge route handler', async () => {
+      const res = await fetch('/api/fs')
+      expect(res.status).toEqual(200)
+      expect(await res.text()).toEqual('undefined')
+    }, 20000)
+  })
+}
@@ -0,0 +1,3 @@
+export default function BlogTest() {
+  return <div>This is not a route</div>
+}
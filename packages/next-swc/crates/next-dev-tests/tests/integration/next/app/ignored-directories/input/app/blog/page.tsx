@@ -0,0 +1,3 @@
+export default function Blog() {
+  return <div>Blog</div>
+}
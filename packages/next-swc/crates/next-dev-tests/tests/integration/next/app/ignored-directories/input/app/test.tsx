@@ -0,0 +1,17 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should render a regular segment', async () => {
+      const res = await fetch('/blog')
+      expect(res.status).toBe(200)
+    }, 20000)
+
+    it('should not treat an ignored directory as a segment', async () => {
+      const res = await fetch('/blog/__tests__')
+      expect(res.status).toBe(404)
+    }, 20000)
+  })
+}
@@ -0,0 +1,16 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should pick up a gif apple icon when enabled via config', () => {
+      const links = Array.from(
+        document.querySelectorAll('link[rel="apple-touch-icon"]')
+      )
+      expect(links.map((l) => l.getAttribute('href'))).toEqual([
+        expect.stringMatching(/^\/_next\/static\/.+\.gif$/),
+      ])
+    })
+  })
+}
@@ -0,0 +1,8 @@
+module.exports = {
+  experimental: {
+    appDir: true,
+    metadataExtensions: {
+      'apple-icon': ['gif'],
+    },
+  },
+}
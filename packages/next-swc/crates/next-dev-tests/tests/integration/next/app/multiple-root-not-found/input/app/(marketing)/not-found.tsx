@@ -0,0 +1,3 @@
+export default function NotFound() {
+  return <div data-test-notfound>Not found (marketing)</div>
+}
@@ -0,0 +1,5 @@
+import Test from '../test'
+
+export default function Page() {
+  return <Test />
+}
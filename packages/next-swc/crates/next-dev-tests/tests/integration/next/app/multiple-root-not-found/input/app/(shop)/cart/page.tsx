@@ -0,0 +1,3 @@
+export default function Cart() {
+  return <div data-test-cart>Cart</div>
+}
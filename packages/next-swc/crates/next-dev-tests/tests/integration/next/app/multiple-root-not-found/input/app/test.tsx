@@ -0,0 +1,19 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('renders pages of both root layouts', async () => {
+      const res = await fetch('/cart')
+      expect(res.status).toBe(200)
+      expect(await res.text()).toContain('data-test-root="shop"')
+    })
+
+    it('returns a 404 status code using a route group not-found page', async () => {
+      const res = await fetch('/does-not-exist')
+      expect(res.status).toBe(404)
+      expect(await res.text()).toContain('Not found (marketing)')
+    }, 20000)
+  })
+}
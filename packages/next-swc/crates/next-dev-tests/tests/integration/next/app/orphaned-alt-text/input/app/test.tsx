@@ -0,0 +1,14 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should not render an og:image for an orphaned alt text file', () => {
+      const meta = Array.from(document.querySelectorAll('meta')).filter((m) =>
+        m.getAttribute('property')?.startsWith('og:image')
+      )
+      expect(meta).toEqual([])
+    })
+  })
+}
@@ -0,0 +1,29 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should only link the favicon of the route group', () => {
+      const icons = Array.from(
+        document.querySelectorAll('link[rel="icon"]')
+      ).map((l) => ({
+        href: l.getAttribute('href'),
+        sizes: l.getAttribute('sizes'),
+      }))
+      expect(icons).toEqual([
+        {
+          href: expect.stringMatching(/^\/_next\/static\/.+\.ico$/),
+          sizes: '16x16',
+        },
+      ])
+    })
+
+    it('should still serve the root favicon.ico', async () => {
+      const res = await fetch('/favicon.ico')
+      expect(res.status).toBe(200)
+      expect(res.headers.get('content-type')).toBe('image/x-icon')
+      expect((await res.arrayBuffer()).byteLength).toBe(15086)
+    })
+  })
+}
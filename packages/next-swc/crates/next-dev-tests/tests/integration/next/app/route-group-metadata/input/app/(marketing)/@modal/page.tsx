@@ -0,0 +1,3 @@
+export default function Modal() {
+  return <div data-test-modal>Modal</div>
+}
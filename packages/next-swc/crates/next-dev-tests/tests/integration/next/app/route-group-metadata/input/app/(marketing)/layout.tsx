@@ -0,0 +1,14 @@
+export default function MarketingLayout({
+  children,
+  modal,
+}: {
+  children: any
+  modal: any
+}) {
+  return (
+    <>
+      {children}
+      {modal}
+    </>
+  )
+}
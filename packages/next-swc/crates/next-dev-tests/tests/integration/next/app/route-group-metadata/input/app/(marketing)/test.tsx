@@ -0,0 +1,28 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should apply the route group icon exactly once', () => {
+      const links = Array.from(document.querySelectorAll('link')).filter(
+        (l) => l.rel === 'icon'
+      )
+      expect(
+        links.map((l) => ({
+          href: l.getAttribute('href'),
+          sizes: l.getAttribute('sizes'),
+        }))
+      ).toEqual([
+        {
+          href: expect.stringMatching(/^\/_next\/static\/.+\.png$/),
+          sizes: '32x32',
+        },
+      ])
+    })
+
+    it('should render the parallel slot of the route group', () => {
+      expect(document.querySelector('[data-test-modal]')).not.toBeNull()
+    })
+  })
+}
@@ -0,0 +1,18 @@
+export async function GET(): Promise<Response> {
+  const encoder = new TextEncoder()
+  const stream = new ReadableStream({
+    async start(controller) {
+      for (const chunk of ['hello', ' ', 'stream']) {
+        controller.enqueue(encoder.encode(chunk))
+        await new Promise((resolve) => setTimeout(resolve, 10))
+      }
+      controller.close()
+    },
+  })
+
+  return new Response(stream, {
+    headers: { runtime: process.env.NEXT_RUNTIME ?? '' },
+  })
+}
+
+export const runtime = 'edge'
@@ -24,6 +24,13 @@ function test() {
     const text = await res.text()
     expect(text).toEqual('{"data":"secret"}')
   }, 20000)
+
+  it('should stream a response from an edge route handler', async () => {
+    const res = await fetch('/api/stream')
+    expect(res.headers.get('runtime')).toEqual('edge')
+    const text = await res.text()
+    expect(text).toEqual('hello stream')
+  }, 20000)
 }
 
 export default function Test() {
@@ -0,0 +1,18 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+const globalOrder = globalThis as any
+globalOrder.__RUNTIME_MODULE_ORDER ??= []
+globalOrder.__RUNTIME_MODULE_ORDER.push('test')
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should evaluate injected runtime modules before user code', () => {
+      expect(globalOrder.__RUNTIME_MODULE_ORDER).toEqual([
+        'instrumentation-init',
+        'test',
+      ])
+    })
+  })
+}
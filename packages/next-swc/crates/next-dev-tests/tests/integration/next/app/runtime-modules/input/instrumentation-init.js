@@ -0,0 +1 @@
+(globalThis.__RUNTIME_MODULE_ORDER ??= []).push('instrumentation-init')
@@ -0,0 +1,8 @@
+module.exports = {
+  experimental: {
+    appDir: true,
+    turbo: {
+      runtimeModules: ['./instrumentation-init.js'],
+    },
+  },
+}
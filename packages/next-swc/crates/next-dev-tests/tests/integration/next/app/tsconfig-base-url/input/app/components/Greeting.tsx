@@ -0,0 +1,3 @@
+export default function Greeting() {
+  return <h1 id="local-greeting">hello from app</h1>
+}
@@ -0,0 +1,13 @@
+import Greeting from 'components/Greeting'
+import LocalGreeting from './components/Greeting'
+import Test from './test'
+
+export default function Page() {
+  return (
+    <div>
+      <Greeting />
+      <LocalGreeting />
+      <Test />
+    </div>
+  )
+}
@@ -0,0 +1,19 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    it('should resolve bare imports relative to the tsconfig baseUrl', () => {
+      expect(document.getElementById('greeting')?.textContent).toEqual(
+        'hello from baseUrl'
+      )
+    })
+
+    it('should not resolve relative imports in the tsconfig baseUrl', () => {
+      expect(document.getElementById('local-greeting')?.textContent).toEqual(
+        'hello from app'
+      )
+    })
+  })
+}
@@ -0,0 +1,3 @@
+export default function Greeting() {
+  return <h1 id="greeting">hello from baseUrl</h1>
+}
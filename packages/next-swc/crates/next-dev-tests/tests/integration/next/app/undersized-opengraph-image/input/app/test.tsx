@@ -0,0 +1,23 @@
+'use client'
+
+import { useTestHarness } from '@turbo/pack-test-harness'
+
+export default function Test() {
+  useTestHarness(() => {
+    // The undersized image is reported as a warning (see the issue
+    // snapshots), but is still used.
+    it('should still render the og:image meta tags', () => {
+      const meta = Array.from(document.querySelectorAll('meta'))
+      const metaObject = Object.fromEntries(
+        meta
+          .filter((l) => l.getAttribute('property'))
+          .map((l) => [l.getAttribute('property'), l.getAttribute('content')])
+      )
+      expect(metaObject).toEqual({
+        'og:image': expect.stringMatching(/^.+\/_next\/static\/.+\.png$/),
+        'og:image:width': '114',
+        'og:image:height': '114',
+      })
+    })
+  })
+}
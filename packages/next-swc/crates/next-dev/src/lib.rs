@@ -374,8 +374,11 @@ async fn source(
         client_compile_time_info.environment(),
         mode,
     );
-    let pages_structure =
-        find_pages_structure(project_path, dev_server_root, next_config.page_extensions());
+    let pages_structure = find_pages_structure(
+        project_path,
+        dev_server_root,
+        next_config.resolved_page_extensions(),
+    );
     let page_source = create_page_source(
         pages_structure,
         project_path,
@@ -437,6 +440,7 @@ async fn source(
         server_addr,
         app_dir,
         pages_structure,
+        project_path,
     ));
     let source = Vc::upcast(
         PrefixedRouterContentSource {
@@ -519,6 +519,49 @@ export interface Project {
     TurbopackResult<HmrIdentifiers>
   >
   updateInfoSubscribe(): AsyncIterableIterator<TurbopackResult<UpdateInfo>>
+  diagnosticsSubscribe(): AsyncIterableIterator<Diagnostics[]>
+  /**
+   * Collects the issues of the project structure and config without writing
+   * any endpoint.
+   */
+  collectIssues(): Promise<TurbopackResult<void>>
+  /**
+   * The paths of all output assets of the page or route at `pathname`, without
+   * building any other route or writing anything to disk. Empty for unknown
+   * and conflicting routes.
+   */
+  routeAssets(pathname: string): Promise<TurbopackResult<string[]>>
+  /**
+   * Writes the output assets of the page or route at `pathname` to disk,
+   * calling `onEmit` with each file as soon as it has been written.
+   */
+  emitRouteAssets(
+    pathname: string,
+    onEmit: (file: EmittedFile) => void
+  ): Promise<TurbopackResult<void>>
+  /**
+   * The app directory of the project, with symlinks resolved, or null when it
+   * has none.
+   */
+  appDir(): Promise<AppDir | null>
+  /**
+   * The resolved next config of the project, with defaults applied, as JSON.
+   */
+  configJson(): Promise<string>
+}
+
+export interface EmittedFile {
+  /** The path of the file, relative to the project root. */
+  path: string
+  /** The size of the written content in bytes. */
+  size: number
+}
+
+export interface AppDir {
+  /** The absolute path of the app directory. */
+  path: string
+  /** Whether the app directory is `src/app` instead of `app`. */
+  isSrcDir: boolean
 }
 
 export type Route =
@@ -547,6 +590,16 @@ export type Route =
 export interface Endpoint {
   /** Write files for the endpoint to disk. */
   writeToDisk(): Promise<TurbopackResult<WrittenEndpoint>>
+  /**
+   * The server files required to run the endpoint, relative to the node root.
+   * Doesn't write anything to disk.
+   */
+  requiredServerFiles(): Promise<TurbopackResult<string[]>>
+  /**
+   * The middleware matchers configured in the endpoint's source. Empty when no
+   * matcher is configured. Doesn't write anything to disk.
+   */
+  matchers(): Promise<TurbopackResult<EndpointMatcher[]>>
   /**
    * Listen to changes to the endpoint.
    * After changed() has been awaited it will listen to changes.
@@ -555,6 +608,18 @@ export interface Endpoint {
   changed(): Promise<AsyncIterableIterator<TurbopackResult>>
 }
 
+interface RouteHas {
+  type: 'header' | 'cookie' | 'query' | 'host'
+  key?: string
+  value?: string
+}
+
+export interface EndpointMatcher {
+  source: string
+  has?: RouteHas[]
+  missing?: RouteHas[]
+}
+
 interface EndpointConfig {
   dynamic?: 'auto' | 'force-dynamic' | 'error' | 'force-static'
   dynamicParams?: boolean
@@ -875,6 +940,61 @@ function bindingToApi(binding: any, _wasm: boolean) {
       )
       return subscription
     }
+
+    diagnosticsSubscribe() {
+      const subscription = subscribe<Diagnostics[]>(
+        false,
+        async (callback) =>
+          binding.projectDiagnosticsSubscribe(this._nativeProject, callback)
+      )
+      return subscription
+    }
+
+    async collectIssues(): Promise<TurbopackResult<void>> {
+      return await withErrorCause(() =>
+        binding.projectCollectIssues(this._nativeProject)
+      )
+    }
+
+    async routeAssets(pathname: string): Promise<TurbopackResult<string[]>> {
+      return await withErrorCause(() =>
+        binding.projectRouteAssets(this._nativeProject, pathname)
+      )
+    }
+
+    async emitRouteAssets(
+      pathname: string,
+      onEmit: (file: EmittedFile) => void
+    ): Promise<TurbopackResult<void>> {
+      const subscription = subscribe<TurbopackResult<void>>(
+        false,
+        async (callback) =>
+          binding.projectEmitRouteAssetsSubscribe(
+            this._nativeProject,
+            pathname,
+            (_err: Error, file: EmittedFile) => onEmit(file),
+            callback
+          )
+      )
+      // Leaving the loop disposes the subscription, so files are only emitted
+      // once.
+      for await (const result of subscription) {
+        return result
+      }
+      throw new Error('Invariant: emit subscription ended without a result')
+    }
+
+    async appDir(): Promise<AppDir | null> {
+      return await withErrorCause(() =>
+        binding.projectAppDir(this._nativeProject)
+      )
+    }
+
+    async configJson(): Promise<string> {
+      return await withErrorCause(() =>
+        binding.projectConfigJson(this._nativeProject)
+      )
+    }
   }
 
   class EndpointImpl implements Endpoint {
@@ -890,6 +1010,18 @@ function bindingToApi(binding: any, _wasm: boolean) {
       )
     }
 
+    async requiredServerFiles(): Promise<TurbopackResult<string[]>> {
+      return await withErrorCause(() =>
+        binding.endpointRequiredServerFiles(this._nativeEndpoint)
+      )
+    }
+
+    async matchers(): Promise<TurbopackResult<EndpointMatcher[]>> {
+      return await withErrorCause(() =>
+        binding.endpointMatchers(this._nativeEndpoint)
+      )
+    }
+
     async changed(): Promise<
       AsyncIterableIterator<TurbopackResult<ServerClientChange>>
     > {
@@ -1109,6 +1241,7 @@ async function loadWasm(importPath = '', isCustomTurbopack: boolean) {
               rootDir: string,
               applicationDir: string,
               pageExtensions: string[],
+              nextConfig: string,
               callbackFn: (err: Error, entrypoints: any) => void
             ) => {
               return bindings.streamEntrypoints(
@@ -1116,6 +1249,7 @@ async function loadWasm(importPath = '', isCustomTurbopack: boolean) {
                 rootDir,
                 applicationDir,
                 pageExtensions,
+                nextConfig,
                 callbackFn
               )
             },
@@ -1123,13 +1257,15 @@ async function loadWasm(importPath = '', isCustomTurbopack: boolean) {
               turboTasks: any,
               rootDir: string,
               applicationDir: string,
-              pageExtensions: string[]
+              pageExtensions: string[],
+              nextConfig: string
             ) => {
               return bindings.getEntrypoints(
                 turboTasks,
                 rootDir,
                 applicationDir,
-                pageExtensions
+                pageExtensions,
+                nextConfig
               )
             },
           },
@@ -1382,6 +1518,7 @@ function loadNative(isCustomTurbopack = false, importPath?: string) {
             rootDir: string,
             applicationDir: string,
             pageExtensions: string[],
+            nextConfig: string,
             fn: (entrypoints: any) => void
           ) => {
             return (customBindings ?? bindings).streamEntrypoints(
@@ -1389,6 +1526,7 @@ function loadNative(isCustomTurbopack = false, importPath?: string) {
               rootDir,
               applicationDir,
               pageExtensions,
+              nextConfig,
               fn
             )
           },
@@ -1396,13 +1534,15 @@ function loadNative(isCustomTurbopack = false, importPath?: string) {
             turboTasks: any,
             rootDir: string,
             applicationDir: string,
-            pageExtensions: string[]
+            pageExtensions: string[],
+            nextConfig: string
           ) => {
             return (customBindings ?? bindings).getEntrypoints(
               turboTasks,
               rootDir,
               applicationDir,
-              pageExtensions
+              pageExtensions,
+              nextConfig
             )
           },
         },
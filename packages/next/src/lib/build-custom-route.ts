@@ -0,0 +1,42 @@
+import { pathToRegexp } from 'next/dist/compiled/path-to-regexp'
+import { normalizeRouteRegex, Redirect, RouteType } from './load-custom-routes'
+import { getRedirectStatus, modifyRouteRegex } from './redirect-status'
+
+export function buildCustomRoute(
+  r: {
+    source: string
+    locale?: false
+    basePath?: false
+    statusCode?: number
+    destination?: string
+  },
+  type: RouteType,
+  restrictedRedirectPaths?: string[]
+) {
+  const keys: any[] = []
+
+  const routeRegex = pathToRegexp(r.source, keys, {
+    strict: true,
+    sensitive: false,
+    delimiter: '/', // default is `/#?`, but Next does not pass query info
+  })
+  let regexSource = routeRegex.source
+
+  if (!(r as any).internal) {
+    regexSource = modifyRouteRegex(
+      routeRegex.source,
+      type === 'redirect' ? restrictedRedirectPaths : undefined
+    )
+  }
+
+  return {
+    ...r,
+    ...(type === 'redirect'
+      ? {
+          statusCode: getRedirectStatus(r as Redirect),
+          permanent: undefined,
+        }
+      : {}),
+    regex: normalizeRouteRegex(regexSource),
+  }
+}
@@ -4,6 +4,7 @@ import {
   PAGES_MANIFEST,
   SERVER_DIRECTORY,
   APP_PATHS_MANIFEST,
+  SHORTENED_PATHS_MANIFEST,
 } from '../shared/lib/constants'
 import { normalizeLocalePath } from '../shared/lib/i18n/normalize-locale-path'
 import { normalizePagePath } from '../shared/lib/page-path/normalize-page-path'
@@ -12,7 +13,7 @@ import type { PagesManifest } from '../build/webpack/plugins/pages-manifest-plug
 import { PageNotFoundError, MissingStaticPage } from '../shared/lib/utils'
 import LRUCache from 'next/dist/compiled/lru-cache'
 import { loadManifest } from './load-manifest'
-import { promises } from 'fs'
+import { existsSync, promises } from 'fs'
 
 const isDev = process.env.NODE_ENV === 'development'
 const pagePathCache = isDev
@@ -89,12 +90,29 @@ export function getMaybePagePath(
     return null
   }
 
-  const path = join(serverBuildPath, pagePath)
+  const path = getOutputPath(distDir, `${SERVER_DIRECTORY}/${pagePath}`)
   pagePathCache.set(cacheKey, path)
 
   return path
 }
 
+/**
+ * Returns the path a file of the build output was written to. Builds with a
+ * maximum path length (e.g. for Windows) write files with longer paths to a
+ * shorter one, which is listed in the shortened paths manifest.
+ */
+function getOutputPath(distDir: string, relativePath: string): string {
+  const manifestPath = join(distDir, SHORTENED_PATHS_MANIFEST)
+  if (!existsSync(manifestPath)) {
+    return join(distDir, relativePath)
+  }
+  const shortenedPaths: Record<string, string> = loadManifest(
+    manifestPath,
+    !isDev
+  )
+  return join(distDir, shortenedPaths[relativePath] ?? relativePath)
+}
+
 export function getPagePath(
   page: string,
   distDir: string,
@@ -45,6 +45,7 @@ export const MIDDLEWARE_MANIFEST = 'middleware-manifest.json'
 export const DEV_MIDDLEWARE_MANIFEST = '_devMiddlewareManifest.json'
 export const REACT_LOADABLE_MANIFEST = 'react-loadable-manifest.json'
 export const FONT_MANIFEST = 'font-manifest.json'
+export const SHORTENED_PATHS_MANIFEST = 'shortened-paths-manifest.json'
 export const SERVER_DIRECTORY = 'server'
 export const CONFIG_FILES = ['next.config.js', 'next.config.mjs']
 export const BUILD_ID_FILE = 'BUILD_ID'
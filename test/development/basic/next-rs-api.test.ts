@@ -3,6 +3,7 @@ import { trace } from 'next/src/trace'
 import { PHASE_DEVELOPMENT_SERVER } from 'next/constants'
 import {
   Diagnostics,
+  EmittedFile,
   Entrypoints,
   Issue,
   loadBindings,
@@ -12,6 +13,8 @@ import {
 } from 'next/src/build/swc'
 import loadConfig from 'next/src/server/config'
 import path from 'path'
+import fs from 'fs-extra'
+import { retry } from 'next-test-utils'
 
 function normalizePath(path: string) {
   return path
@@ -200,6 +203,45 @@ describe('next.rs api', () => {
     entrypointsSubscribtion.return()
   })
 
+  it('should subscribe to diagnostics', async () => {
+    const diagnosticsSubscription = project.diagnosticsSubscribe()
+    const diagnostics = await diagnosticsSubscription.next()
+    expect(diagnostics.done).toBe(false)
+    expect(
+      diagnostics.value.some(
+        (diagnostic) => diagnostic.name === 'EVENT_BUILD_FEATURE_USAGE'
+      )
+    ).toBe(true)
+    diagnosticsSubscription.return()
+  })
+
+  it('should collect project issues without writing endpoints', async () => {
+    const valid = await project.collectIssues()
+    expect(
+      valid.issues.filter((issue) => issue.severity === 'error')
+    ).toBeEmpty()
+
+    await next.patchFile(
+      'app/route-nodejs/page.ts',
+      'export default () => <div>hello world</div>'
+    )
+    try {
+      await retry(async () => {
+        const result = await project.collectIssues()
+        expect(result.issues).toContainEqual(
+          expect.objectContaining({
+            severity: 'error',
+            description: expect.stringContaining(
+              'Conflicting page and route at /route-nodejs'
+            ),
+          })
+        )
+      })
+    } finally {
+      await next.deleteFile('app/route-nodejs/page.ts')
+    }
+  })
+
   const routes = [
     {
       name: 'root page',
@@ -335,6 +377,70 @@ describe('next.rs api', () => {
     })
   }
 
+  it('should list the required server files of an endpoint', async () => {
+    const entrypointsSubscribtion = project.entrypointsSubscribe()
+    const entrypoints: TurbopackResult<Entrypoints> = (
+      await entrypointsSubscribtion.next()
+    ).value
+    const route = entrypoints.routes.get('/route-nodejs')
+    entrypointsSubscribtion.return()
+
+    expect(route.type).toBe('app-route')
+    if (route.type !== 'app-route') return
+
+    const { result: files } = await route.endpoint.requiredServerFiles()
+    // The endpoint's own chunk and the shared Node.js runtime chunks.
+    expect(files).toContainEqual('server/app/route-nodejs/route.js')
+    expect(files).toContainEqual(expect.stringMatching(/^server\/chunks\//))
+
+    const written = await route.endpoint.writeToDisk()
+    expect(written.type).toBe('nodejs')
+    expect([...files].sort()).toEqual([...written.serverPaths].sort())
+  })
+
+  it('should return the resolved next config', async () => {
+    const config = JSON.parse(await project.configJson())
+    // The fixture has no next.config.js, so every value is a default.
+    expect(config.pageExtensions).toEqual(['tsx', 'ts', 'jsx', 'js'])
+    expect(config).not.toHaveProperty('output')
+    expect(config.basePath).toBe('')
+    expect(config.experimental).toBeObject()
+  })
+
+  it('should resolve the app directory', async () => {
+    const appDir = await project.appDir()
+    expect(appDir).toEqual({
+      path: path.join(await fs.realpath(next.testDir), 'app'),
+      isSrcDir: false,
+    })
+  })
+
+  it('should list the assets of a single route', async () => {
+    const { result: assets } = await project.routeAssets('/page-nodejs')
+    expect(assets).toContainEqual(
+      expect.stringMatching(/server\/pages\/page-nodejs\.js$/)
+    )
+    expect(assets).not.toContainEqual(
+      expect.stringMatching(/server\/pages\/page-edge\.js$/)
+    )
+
+    const { result: missing } = await project.routeAssets('/missing')
+    expect(missing).toEqual([])
+  })
+
+  it('should report each emitted file of a single route', async () => {
+    const emitted: EmittedFile[] = []
+    await project.emitRouteAssets('/page-nodejs', (file) => emitted.push(file))
+    expect(emitted).toContainEqual({
+      path: expect.stringMatching(/server\/pages\/page-nodejs\.js$/),
+      size: expect.any(Number),
+    })
+    expect(emitted).not.toContainEqual({
+      path: expect.stringMatching(/server\/pages\/page-edge\.js$/),
+      size: expect.any(Number),
+    })
+  })
+
   const hmrCases: {
     name: string
     path: string
@@ -534,3 +640,40 @@ describe('next.rs api', () => {
       })
   }
 })
+
+describe('next.rs api with src/app', () => {
+  let next: NextInstance
+  beforeAll(async () => {
+    next = await createNext({
+      skipStart: true,
+      files: {
+        'src/app/layout.ts':
+          'export default function RootLayout({ children }: { children: any }) { return (<html><body>{children}</body></html>)}',
+        'src/app/page.ts': 'export default () => <div>hello world</div>',
+      },
+    })
+  })
+  afterAll(() => next.destroy())
+
+  it('should resolve the app directory', async () => {
+    const nextConfig = await loadConfig(PHASE_DEVELOPMENT_SERVER, next.testDir)
+    const bindings = await loadBindings()
+    const project = await bindings.turbo.createProject({
+      env: {},
+      jsConfig: {
+        compilerOptions: {},
+      },
+      nextConfig: nextConfig,
+      projectPath: next.testDir,
+      rootPath: process.env.NEXT_SKIP_ISOLATE
+        ? path.resolve(__dirname, '../../..')
+        : next.testDir,
+      watch: false,
+    })
+
+    expect(await project.appDir()).toEqual({
+      path: path.join(await fs.realpath(next.testDir), 'src/app'),
+      isSrcDir: true,
+    })
+  })
+})